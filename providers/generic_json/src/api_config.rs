@@ -0,0 +1,153 @@
+use config::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use weather_abstractions::{SettingDescriptor, Temperature, TemperatureError};
+
+/// The settings key listing the names of the generic providers, each one configured in the
+/// section of its name.
+pub const GENERIC_PROVIDERS: &str = "genericProviders";
+
+/// The settings supported by every generic provider.
+pub const SETTINGS: &[SettingDescriptor] = &[
+    SettingDescriptor::required(
+        "currentUrl",
+        "The URL template of the current weather, with the {location} and {key} placeholders",
+    ),
+    SettingDescriptor::required(
+        "temperaturePointer",
+        "The JSON pointer of the temperature in the responses, for example /main/temp",
+    ),
+    SettingDescriptor::optional_secret("apiKey", "The API key, if the API requires one"),
+    SettingDescriptor::optional(
+        "forecastUrl",
+        "The URL template of the forecast for a day, with the {date} and {days} placeholders as well",
+    ),
+    SettingDescriptor::optional(
+        "historyUrl",
+        "The URL template of the weather of a past day, with the {date} and {days} placeholders as well",
+    ),
+    SettingDescriptor::optional(
+        "auth",
+        "Where the API key is sent: template (only the {key} placeholder), query, header or bearer",
+    ),
+    SettingDescriptor::optional(
+        "authName",
+        "The name of the query parameter or of the header of the API key, key and X-API-Key by default",
+    ),
+    SettingDescriptor::optional(
+        "temperatureUnit",
+        "The unit of the temperatures: celsius, fahrenheit or kelvin, celsius by default",
+    ),
+    SettingDescriptor::optional("conditionPointer", "The JSON pointer of the condition text"),
+    SettingDescriptor::optional("humidityPointer", "The JSON pointer of the humidity in percent"),
+    SettingDescriptor::optional("windSpeedPointer", "The JSON pointer of the wind speed"),
+    SettingDescriptor::optional(
+        "windSpeedUnit",
+        "The unit of the wind speed: m/s, km/h, mph or knots, m/s by default",
+    ),
+    SettingDescriptor::optional(
+        "pressurePointer",
+        "The JSON pointer of the pressure in hectopascals",
+    ),
+    SettingDescriptor::optional(
+        "feelsLikePointer",
+        "The JSON pointer of the apparent temperature, in the temperature unit",
+    ),
+    SettingDescriptor::optional(
+        "dewPointPointer",
+        "The JSON pointer of the dew point, in the temperature unit",
+    ),
+];
+
+/// Returns the names of the generic providers listed in the settings.
+pub fn generic_providers(cfg: &Config) -> Vec<String> {
+    match cfg.get::<Vec<String>>(GENERIC_PROVIDERS) {
+        Ok(names) => names,
+        Err(ConfigError::NotFound(_)) => Vec::new(),
+        Err(e) => {
+            tracing::warn!("The generic providers in the settings are ignored: {e}");
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiConfig {
+    pub api_key: Option<String>,
+    pub current_url: Option<String>,
+    pub forecast_url: Option<String>,
+    pub history_url: Option<String>,
+    #[serde(default)]
+    pub auth: Auth,
+    pub auth_name: Option<String>,
+    pub temperature_pointer: Option<String>,
+    #[serde(default)]
+    pub temperature_unit: TemperatureUnit,
+    pub condition_pointer: Option<String>,
+    pub humidity_pointer: Option<String>,
+    pub wind_speed_pointer: Option<String>,
+    #[serde(default)]
+    pub wind_speed_unit: WindSpeedUnit,
+    pub pressure_pointer: Option<String>,
+    pub feels_like_pointer: Option<String>,
+    pub dew_point_pointer: Option<String>,
+}
+
+/// Where the API key is sent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Auth {
+    /// Only where the URL template has the {key} placeholder
+    #[default]
+    Template,
+    /// In a query parameter, `key` by default
+    Query,
+    /// In a header, `X-API-Key` by default
+    Header,
+    /// In the `Authorization: Bearer` header
+    Bearer,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    pub fn temperature(self, value: f32) -> Result<Temperature, TemperatureError> {
+        match self {
+            TemperatureUnit::Celsius => Temperature::from_c(value),
+            TemperatureUnit::Fahrenheit => Temperature::from_f(value),
+            TemperatureUnit::Kelvin => Temperature::from_k(value),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WindSpeedUnit {
+    #[default]
+    #[serde(rename = "m/s")]
+    MetersPerSecond,
+    #[serde(rename = "km/h")]
+    KilometersPerHour,
+    #[serde(rename = "mph")]
+    MilesPerHour,
+    #[serde(rename = "knots")]
+    Knots,
+}
+
+impl WindSpeedUnit {
+    /// Converts the wind speed to meters per second.
+    pub fn meters_per_second(self, value: f32) -> f32 {
+        match self {
+            WindSpeedUnit::MetersPerSecond => value,
+            WindSpeedUnit::KilometersPerHour => value / 3.6,
+            WindSpeedUnit::MilesPerHour => value * 0.447_04,
+            WindSpeedUnit::Knots => value * 0.514_444,
+        }
+    }
+}
@@ -0,0 +1,470 @@
+//! A provider for the JSON weather APIs without a dedicated crate, declared entirely in the
+//! settings.
+//!
+//! The names of the providers are listed in `genericProviders` and every provider is configured in
+//! the section of its name, with the URL templates of the requests and the JSON pointers of the
+//! values in the responses:
+//!
+//! ```json
+//! {
+//!   "genericProviders": ["station"],
+//!   "station": {
+//!     "currentUrl": "https://station.example/api/now?city={location}",
+//!     "forecastUrl": "https://station.example/api/daily?city={location}&date={date}",
+//!     "apiKey": "...",
+//!     "auth": "query",
+//!     "authName": "token",
+//!     "temperaturePointer": "/temperature",
+//!     "temperatureUnit": "fahrenheit",
+//!     "conditionPointer": "/summary"
+//!   }
+//! }
+//! ```
+//!
+//! The templates accept the `{location}`, `{date}` (YYYY-MM-DD), `{days}` (the number of days
+//! from today, negative in the past) and `{key}` placeholders, the pointers the `{date}` and
+//! `{days}` ones to select the day in an array of days.
+pub mod api_config;
+mod error;
+
+use std::error::Error;
+
+pub use api_config::{generic_providers, GENERIC_PROVIDERS, SETTINGS};
+use api_config::{ApiConfig, Auth};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use config::Config;
+pub use error::GenericJsonError;
+use reqwest::Url;
+use serde_json::Value;
+use tracing::Instrument;
+use weather_abstractions::{utils::redacted_url, *};
+
+pub struct GenericJsonProvider {
+    name: String,
+    current_url: String,
+    temperature_pointer: String,
+    conf: ApiConfig,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl WeatherProvider for GenericJsonProvider {
+    async fn get_weather(
+        &self,
+        address: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather(self, address, date).await?)
+    }
+}
+
+impl GenericJsonProvider {
+    /// Reads the settings of the provider from the section of its name and checks the templates.
+    pub fn new(name: &str, cfg: &Config) -> Result<Self, GenericJsonError> {
+        let conf: ApiConfig = cfg
+            .get(name)
+            .map_err(|e| GenericJsonError::Parse(name.to_string(), String::new(), e.to_string()))?;
+        let missing = |key: &str| GenericJsonError::MissingConf(key.to_string(), name.to_string());
+        let current_url = conf
+            .current_url
+            .clone()
+            .ok_or_else(|| missing("currentUrl"))?;
+        let temperature_pointer = conf
+            .temperature_pointer
+            .clone()
+            .ok_or_else(|| missing("temperaturePointer"))?;
+        if conf.auth != Auth::Template && conf.api_key.is_none() {
+            return Err(missing("apiKey"));
+        }
+        let templates = [
+            ("currentUrl", Some(&current_url)),
+            ("forecastUrl", conf.forecast_url.as_ref()),
+            ("historyUrl", conf.history_url.as_ref()),
+        ];
+        for (key, template) in templates {
+            if let Some(template) = template {
+                let sample = render(template, "London", NaiveDate::MIN, 0, "key");
+                Url::parse(&sample).map_err(|e| {
+                    GenericJsonError::Parse(
+                        format!("{name}/{key}"),
+                        template.clone(),
+                        e.to_string(),
+                    )
+                })?;
+            }
+        }
+        Ok(Self {
+            name: name.to_string(),
+            current_url,
+            temperature_pointer,
+            conf,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Requests the current URL without the date, the history URL for the past days and the
+    /// forecast URL for the others, or the current URL for today without the forecast URL.
+    async fn get_weather(
+        &self,
+        address: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<Weather, GenericJsonError> {
+        let today = estimated_local_date(address);
+        let date_or_today = date.unwrap_or(today);
+        let days = date_or_today.signed_duration_since(today).num_days();
+        let (template, kind) = match date {
+            None => (Some(&self.current_url), WeatherKind::Current),
+            Some(_) if days < 0 => (self.conf.history_url.as_ref(), WeatherKind::History),
+            Some(_) if days == 0 && self.conf.forecast_url.is_none() => {
+                (Some(&self.current_url), WeatherKind::Current)
+            }
+            Some(_) => (self.conf.forecast_url.as_ref(), WeatherKind::Forecast),
+        };
+        let template = template.ok_or(GenericJsonError::UnsupportedDate(date_or_today))?;
+        let json = self
+            .request(template, kind, address, date_or_today, days)
+            .await?;
+        self.weather(&json, kind, date_or_today, days)
+    }
+
+    async fn request(
+        &self,
+        template: &str,
+        kind: WeatherKind,
+        address: &str,
+        date: NaiveDate,
+        days: i64,
+    ) -> Result<Value, GenericJsonError> {
+        let key = self.conf.api_key.as_deref().unwrap_or_default();
+        let parse_error = |e: url::ParseError| {
+            GenericJsonError::Parse(self.name.clone(), template.to_string(), e.to_string())
+        };
+        let mut url =
+            Url::parse(&render(template, address, date, days, key)).map_err(parse_error)?;
+        let mut traced_url =
+            Url::parse(&render(template, address, date, days, "REDACTED")).map_err(parse_error)?;
+        let auth_name = self.auth_name();
+        if self.conf.auth == Auth::Query {
+            url.query_pairs_mut().append_pair(auth_name, key);
+            traced_url.query_pairs_mut().append_pair(auth_name, key);
+        }
+        let mut request = self.client.get(url);
+        match self.conf.auth {
+            Auth::Template | Auth::Query => {}
+            Auth::Header => request = request.header(auth_name, key),
+            Auth::Bearer => request = request.bearer_auth(key),
+        }
+        let span = tracing::info_span!(
+            "GET",
+            provider = %self.name,
+            endpoint = ?kind,
+            http.url = %redacted_url(&traced_url, &[auth_name]),
+        );
+        let response = request.send().instrument(span).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            let message = match message.trim() {
+                "" => status.to_string(),
+                message => message.to_string(),
+            };
+            return Err(GenericJsonError::Api(message, status.as_u16()));
+        }
+        Ok(response.json().await?)
+    }
+
+    fn auth_name(&self) -> &str {
+        match (&self.conf.auth_name, self.conf.auth) {
+            (Some(name), _) => name,
+            (None, Auth::Header) => "X-API-Key",
+            (None, _) => "key",
+        }
+    }
+
+    fn weather(
+        &self,
+        json: &Value,
+        kind: WeatherKind,
+        date: NaiveDate,
+        days: i64,
+    ) -> Result<Weather, GenericJsonError> {
+        let optional = |pointer: &Option<String>| {
+            pointer
+                .as_deref()
+                .and_then(|pointer| number(json, &render_pointer(pointer, date, days)))
+        };
+        let temperature_pointer = render_pointer(&self.temperature_pointer, date, days);
+        let temp = number(json, &temperature_pointer).ok_or_else(|| {
+            GenericJsonError::MissingValue(self.name.clone(), temperature_pointer.clone())
+        })?;
+        let unit = self.conf.temperature_unit;
+        let condition = self
+            .conf
+            .condition_pointer
+            .as_deref()
+            .and_then(|pointer| json.pointer(&render_pointer(pointer, date, days)))
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown");
+        let temp = unit.temperature(temp)?;
+        let mut weather = match kind {
+            WeatherKind::History => Weather::history(temp, condition),
+            WeatherKind::Current => Weather::current(temp, condition),
+            _ => Weather::forecast(temp, condition),
+        };
+        weather.humidity = optional(&self.conf.humidity_pointer);
+        weather.wind_speed = optional(&self.conf.wind_speed_pointer)
+            .map(|speed| self.conf.wind_speed_unit.meters_per_second(speed));
+        weather.pressure = optional(&self.conf.pressure_pointer);
+        weather.feels_like = optional(&self.conf.feels_like_pointer)
+            .map(|value| unit.temperature(value))
+            .transpose()?;
+        weather.dew_point = optional(&self.conf.dew_point_pointer)
+            .map(|value| unit.temperature(value))
+            .transpose()?;
+        Ok(weather)
+    }
+}
+
+/// Replaces the placeholders of the URL template, the location and the key are encoded.
+fn render(template: &str, location: &str, date: NaiveDate, days: i64, key: &str) -> String {
+    template
+        .replace("{location}", &encode(location))
+        .replace("{date}", &date.format("%Y-%m-%d").to_string())
+        .replace("{days}", &days.to_string())
+        .replace("{key}", &encode(key))
+}
+
+fn render_pointer(pointer: &str, date: NaiveDate, days: i64) -> String {
+    pointer
+        .replace("{date}", &date.format("%Y-%m-%d").to_string())
+        .replace("{days}", &days.to_string())
+}
+
+/// Encodes the value for both the path and the query, the `+` being encoded by `byte_serialize`
+/// the remaining ones are spaces.
+fn encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+/// Reads the number at the pointer, the APIs returning the numbers as strings included.
+fn number(json: &Value, pointer: &str) -> Option<f32> {
+    match json.pointer(pointer)? {
+        Value::Number(number) => number.as_f64().map(|number| number as f32),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Days;
+    use httptest::{all_of, matchers::*, responders::*, Expectation, ServerHandle, ServerPool};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use weather_abstractions::assert_error;
+
+    static SERVER_POOL: ServerPool = ServerPool::new(20);
+    const NAME: &str = "station";
+
+    fn setup<'a>(settings: Value) -> (Config, ServerHandle<'a>) {
+        let server = SERVER_POOL.get_server();
+        let base_url = server.url_str("");
+        let mut settings = settings;
+        for key in ["currentUrl", "forecastUrl", "historyUrl"] {
+            if let Some(Value::String(template)) = settings.get_mut(key) {
+                *template = template.replace("{base}/", &base_url);
+            }
+        }
+        let cfg = Config::builder()
+            .add_source(config::File::from_str(
+                &json!({ NAME: settings }).to_string(),
+                config::FileFormat::Json,
+            ))
+            .build()
+            .expect("config built");
+        (cfg, server)
+    }
+
+    #[tokio::test]
+    async fn current_with_query_auth_and_units() {
+        let (cfg, server) = setup(json!({
+            "currentUrl": "{base}/now?city={location}",
+            "apiKey": "secret",
+            "auth": "query",
+            "authName": "token",
+            "temperaturePointer": "/main/temp",
+            "temperatureUnit": "fahrenheit",
+            "conditionPointer": "/summary",
+            "humidityPointer": "/main/humidity",
+            "windSpeedPointer": "/wind",
+            "windSpeedUnit": "km/h",
+        }));
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/now"),
+                request::query(url_decoded(contains(("city", "New York")))),
+                request::query(url_decoded(contains(("token", "secret")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "main": { "temp": 50.0, "humidity": "64" },
+                "wind": 36.0,
+                "summary": "Light rain",
+            }))),
+        );
+
+        let provider = GenericJsonProvider::new(NAME, &cfg).expect("provider created");
+        let weather = provider
+            .get_weather("New York", None)
+            .await
+            .expect("weather returned");
+
+        assert_eq!(weather.kind, WeatherKind::Current);
+        assert_eq!(weather.temp.to_value(Units::Metric), 10.0);
+        assert_eq!(weather.condition, "Light rain");
+        assert_eq!(weather.condition_kind, ConditionKind::Rain);
+        assert_eq!(weather.humidity, Some(64.0));
+        assert_eq!(weather.wind_speed, Some(10.0));
+    }
+
+    #[rstest]
+    #[case(1, "forecast")]
+    #[case(-2, "history")]
+    #[tokio::test]
+    async fn day_with_header_auth(#[case] days: i64, #[case] path: &str) {
+        let (cfg, server) = setup(json!({
+            "currentUrl": "{base}/now/{location}",
+            "forecastUrl": "{base}/forecast/{location}?date={date}",
+            "historyUrl": "{base}/history/{location}?date={date}",
+            "apiKey": "secret",
+            "auth": "header",
+            "temperaturePointer": "/days/{date}/temp",
+        }));
+        let date = estimated_local_date("Lviv") + chrono::Duration::days(days);
+        let date_param = date.format("%Y-%m-%d").to_string();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", format!("/{path}/Lviv")),
+                request::query(url_decoded(contains(("date", date_param.clone())))),
+                request::headers(contains(("x-api-key", "secret"))),
+            ])
+            .respond_with(json_encoded(
+                json!({ "days": { date_param: { "temp": 21.5 } } }),
+            )),
+        );
+
+        let provider = GenericJsonProvider::new(NAME, &cfg).expect("provider created");
+        let weather = provider
+            .get_weather("Lviv", Some(date))
+            .await
+            .expect("weather returned");
+
+        let kind = if days < 0 {
+            WeatherKind::History
+        } else {
+            WeatherKind::Forecast
+        };
+        assert_eq!(weather.kind, kind);
+        assert_eq!(weather.temp.to_value(Units::Metric), 21.5);
+        assert_eq!(weather.condition, "Unknown");
+    }
+
+    #[tokio::test]
+    async fn forecast_day_index_with_template_key() {
+        let (cfg, server) = setup(json!({
+            "currentUrl": "{base}/now?q={location}&appid={key}",
+            "forecastUrl": "{base}/daily/{key}?q={location}",
+            "apiKey": "secret",
+            "temperaturePointer": "/daily/{days}/temp",
+            "temperatureUnit": "kelvin",
+        }));
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/daily/secret")).respond_with(
+                json_encoded(json!({ "daily": [{ "temp": 283.15 }, { "temp": 293.15 }] })),
+            ),
+        );
+
+        let provider = GenericJsonProvider::new(NAME, &cfg).expect("provider created");
+        let tomorrow = estimated_local_date("Kyiv") + Days::new(1);
+        let weather = provider
+            .get_weather("Kyiv", Some(tomorrow))
+            .await
+            .expect("weather returned");
+
+        assert_eq!(weather.temp.to_value(Units::Metric).round(), 20.0);
+    }
+
+    #[tokio::test]
+    async fn missing_temperature() {
+        let (cfg, server) = setup(json!({
+            "currentUrl": "{base}/now?q={location}",
+            "temperaturePointer": "/main/temp",
+        }));
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/now"))
+                .respond_with(json_encoded(json!({ "main": {} }))),
+        );
+
+        let provider = GenericJsonProvider::new(NAME, &cfg).expect("provider created");
+        let error = provider
+            .get_weather("London", None)
+            .await
+            .expect_err("weather result should be err");
+        assert_error!(error, GenericJsonError::MissingValue(_, _));
+    }
+
+    #[tokio::test]
+    async fn api_error() {
+        let (cfg, server) = setup(json!({
+            "currentUrl": "{base}/now?q={location}",
+            "temperaturePointer": "/main/temp",
+        }));
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/now"))
+                .respond_with(status_code(401).body("invalid key")),
+        );
+
+        let provider = GenericJsonProvider::new(NAME, &cfg).expect("provider created");
+        let error = provider
+            .get_weather("London", None)
+            .await
+            .expect_err("weather result should be err");
+        assert_error!(&error, GenericJsonError::Api(_, 401));
+        assert_eq!(error.to_string(), "Api error: invalid key code: 401");
+    }
+
+    #[rstest]
+    #[case(-1)]
+    #[case(3)]
+    #[tokio::test]
+    async fn unsupported_date(#[case] days: i64) {
+        let (cfg, _server) = setup(json!({
+            "currentUrl": "{base}/now?q={location}",
+            "temperaturePointer": "/main/temp",
+        }));
+        let provider = GenericJsonProvider::new(NAME, &cfg).expect("provider created");
+        let date = estimated_local_date("London") + chrono::Duration::days(days);
+
+        let error = provider
+            .get_weather("London", Some(date))
+            .await
+            .expect_err("weather result should be err");
+        assert_error!(error, GenericJsonError::UnsupportedDate(_));
+    }
+
+    #[rstest]
+    #[case(json!({ "temperaturePointer": "/temp" }))]
+    #[case(json!({ "currentUrl": "{base}/now" }))]
+    #[case(json!({ "currentUrl": "{base}/now", "temperaturePointer": "/temp", "auth": "bearer" }))]
+    fn missing_settings(#[case] settings: Value) {
+        let (cfg, _server) = setup(settings);
+
+        let error = GenericJsonProvider::new(NAME, &cfg)
+            .err()
+            .expect("provider should not be created");
+        assert_error!(error, GenericJsonError::MissingConf(_, _));
+    }
+}
@@ -0,0 +1,110 @@
+use std::{collections::HashMap, error::Error, sync::Mutex};
+
+use crate::error::MetNorwayError;
+
+use super::MetNorway;
+use config::Config;
+use reqwest::{Client, Url};
+use reqwest_middleware::ClientBuilder;
+use reqwest_tracing::TracingMiddleware;
+use serde::{Deserialize, Serialize};
+use weather_abstractions::utils::*;
+use weather_abstractions::*;
+
+pub const PROVIDER_NAME: &str = "met-norway";
+
+/// The settings supported by the provider.
+pub const SETTINGS: &[SettingDescriptor] = &[
+    SettingDescriptor::required(
+        "userAgent",
+        "The User-Agent identifying the application with a contact, like myapp/1.0 me@example.com",
+    ),
+    SettingDescriptor::optional("baseUrl", "The base URL of the MET Norway API"),
+    SettingDescriptor::optional("forecastPath", "The path of the Locationforecast endpoint"),
+    SettingDescriptor::optional(
+        "geocodingBaseUrl",
+        "The base URL of the Nominatim geocoding API",
+    ),
+    SettingDescriptor::optional("geocodingPath", "The path of the geocoding search endpoint"),
+];
+
+generate_functions! {
+    base_url, "https://api.met.no",
+    forecast_path, "/weatherapi/locationforecast/2.0/compact",
+    geocoding_base_url, "https://nominatim.openstreetmap.org",
+    geocoding_path, "/search"
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ApiConfig {
+    pub user_agent: Option<String>,
+    #[serde(default = "base_url")]
+    pub base_url: String,
+    #[serde(default = "forecast_path")]
+    pub forecast_path: String,
+    #[serde(default = "geocoding_base_url")]
+    pub geocoding_base_url: String,
+    #[serde(default = "geocoding_path")]
+    pub geocoding_path: String,
+}
+
+pub struct Endpoints {
+    pub forecast: Url,
+    pub geocoding: Url,
+}
+
+impl TryFrom<ApiConfig> for Endpoints {
+    type Error = MetNorwayError;
+    fn try_from(value: ApiConfig) -> Result<Self, Self::Error> {
+        let parse = |name: &str, url: &str| {
+            Url::parse(url).map_err(|e| {
+                MetNorwayError::Parse(
+                    format!("{PROVIDER_NAME}/{name}"),
+                    url.to_string(),
+                    e.to_string(),
+                )
+            })
+        };
+        let base_url = parse("baseUrl", &value.base_url)?;
+        let geocoding_base_url = parse("geocodingBaseUrl", &value.geocoding_base_url)?;
+        Ok(Self {
+            forecast: build_endpoint(&base_url, &value.forecast_path),
+            geocoding: build_endpoint(&geocoding_base_url, &value.geocoding_path),
+        })
+    }
+}
+
+impl MetNorway {
+    /// Builds the provider, the terms of service of both APIs require the User-Agent to identify
+    /// the application, so it has no default.
+    pub fn new(cfg: &Config) -> Result<MetNorway, Box<dyn Error + Send + Sync>> {
+        let missing_user_agent =
+            || MetNorwayError::MissingConf("userAgent".to_string(), PROVIDER_NAME.to_string());
+        let api_conf: ApiConfig = cfg.get(PROVIDER_NAME).map_err(|_| missing_user_agent())?;
+        let user_agent = api_conf
+            .user_agent
+            .clone()
+            .filter(|user_agent| !user_agent.trim().is_empty())
+            .ok_or_else(missing_user_agent)?;
+        let endpoints = Endpoints::try_from(api_conf)?;
+        let client = Client::builder()
+            .user_agent(user_agent)
+            .build()
+            .map_err(reqwest_middleware::Error::Reqwest)
+            .map_err(MetNorwayError::HttpClient)?;
+        Ok(MetNorway {
+            endpoints,
+            client: ClientBuilder::new(client)
+                .with(TracingMiddleware::<ProviderSpan<Provider>>::new())
+                .build(),
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+/// The provider of the request spans, the URLs of both APIs have no secrets.
+struct Provider;
+impl ProviderSpanConfig for Provider {
+    const NAME: &'static str = PROVIDER_NAME;
+}
@@ -0,0 +1,622 @@
+mod api_config;
+mod error;
+mod protocol;
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use self::api_config::Endpoints;
+pub use api_config::{PROVIDER_NAME, SETTINGS};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc};
+pub use error::MetNorwayError;
+use protocol::*;
+use reqwest::{
+    header::{EXPIRES, IF_MODIFIED_SINCE, LAST_MODIFIED},
+    StatusCode, Url,
+};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::de::DeserializeOwned;
+use weather_abstractions::*;
+
+/// The provider of the MET Norway Locationforecast API, the addresses are found with the
+/// Nominatim geocoding as the API accepts only the coordinates.
+pub struct MetNorway {
+    endpoints: Endpoints,
+    client: ClientWithMiddleware,
+    /// The forecasts by the request URL, reused until they expire
+    cache: Mutex<HashMap<Url, CachedForecast>>,
+}
+
+/// A forecast with the validators of its response. The terms of service forbid requesting the
+/// same forecast again before it expires, and require `If-Modified-Since` afterwards.
+struct CachedForecast {
+    forecast: Arc<ForecastResponse>,
+    last_modified: Option<String>,
+    expires: Option<DateTime<Utc>>,
+}
+
+/// The steps farther than this from the requested time are not used for the time of the day.
+const MAX_STEP_DISTANCE_HOURS: i64 = 6;
+
+use async_trait::async_trait;
+#[async_trait]
+impl WeatherProvider for MetNorway {
+    async fn get_weather(
+        &self,
+        address: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather(self, address, date).await?)
+    }
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather_at(self, address, date, time).await?)
+    }
+    async fn local_date(
+        &self,
+        address: &str,
+    ) -> Result<NaiveDate, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::local_date(self, address).await?)
+    }
+}
+impl MetNorway {
+    /// Returns the step closest to now without the date, and the day of the forecast for the
+    /// other dates, the forecast covers about 9 days.
+    async fn get_weather(
+        &self,
+        address: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<Weather, MetNorwayError> {
+        let coordinates = self.coordinates(address).await?;
+        let offset = local_offset(coordinates);
+        let today = Utc::now().with_timezone(&offset).date_naive();
+        if let Some(date) = date.filter(|date| *date < today) {
+            return Err(MetNorwayError::UnsupportedDate(date));
+        }
+        let forecast = self.forecast(coordinates).await?;
+        match date {
+            Some(date) if date > today => self.day(&forecast, date, offset),
+            _ => {
+                let step = current_step(&forecast)
+                    .ok_or_else(|| json_error(&self.endpoints.forecast, "timeseries"))?;
+                step_weather(step, WeatherKind::Current)
+            }
+        }
+    }
+    /// Picks the step closest to the time, hourly for the next days and every 6 hours later.
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, MetNorwayError> {
+        let coordinates = self.coordinates(address).await?;
+        let offset = local_offset(coordinates);
+        let forecast = self.forecast(coordinates).await?;
+        let timestamp = time.timestamp(date, offset);
+        let step = closest_step(&forecast, timestamp)
+            .filter(|step| {
+                (step.time.timestamp() - timestamp).abs() <= MAX_STEP_DISTANCE_HOURS * 3600
+            })
+            .ok_or(MetNorwayError::UnsupportedDate(date))?;
+        Ok(step_weather(step, WeatherKind::Forecast)?
+            .with_time(step.time.with_timezone(&time.offset.unwrap_or(offset))))
+    }
+    /// Estimates the date from the longitude of the location, as the API returns no timezone.
+    async fn local_date(&self, address: &str) -> Result<NaiveDate, MetNorwayError> {
+        let coordinates = self.coordinates(address).await?;
+        Ok(Utc::now()
+            .with_timezone(&local_offset(coordinates))
+            .date_naive())
+    }
+    /// Returns the coordinates of the address, found with the geocoding API unless the address is
+    /// already the coordinates.
+    async fn coordinates(&self, address: &str) -> Result<Coordinates, MetNorwayError> {
+        if let Ok(coordinates) = address.parse::<Coordinates>() {
+            return Ok(coordinates);
+        }
+        let endpoint = &self.endpoints.geocoding;
+        let response = self
+            .client
+            .get(endpoint.clone())
+            .query(&[("q", address), ("format", "jsonv2"), ("limit", "1")])
+            .send()
+            .await?;
+        let places = parse::<Vec<Place>>(response).await?;
+        let place = places
+            .first()
+            .ok_or_else(|| MetNorwayError::LocationNotFound(address.to_string()))?;
+        let (Ok(lat), Ok(lon)) = (place.lat.parse(), place.lon.parse()) else {
+            return Err(json_error(endpoint, "[0].lat"));
+        };
+        Ok(Coordinates { lat, lon })
+    }
+    /// Returns the forecast of the coordinates from the cache until it expires, then revalidates
+    /// it with `If-Modified-Since`.
+    async fn forecast(
+        &self,
+        coordinates: Coordinates,
+    ) -> Result<Arc<ForecastResponse>, MetNorwayError> {
+        // The terms of service ask for at most 4 decimals, which also makes the cache effective
+        let mut url = self.endpoints.forecast.clone();
+        url.query_pairs_mut()
+            .append_pair("lat", &format!("{:.4}", coordinates.lat))
+            .append_pair("lon", &format!("{:.4}", coordinates.lon));
+        let cached = self
+            .cache
+            .lock()
+            .expect("cache lock")
+            .get(&url)
+            .map(|cached| {
+                (
+                    Arc::clone(&cached.forecast),
+                    cached.last_modified.clone(),
+                    cached.expires,
+                )
+            });
+        let mut request = self.client.get(url.clone());
+        match &cached {
+            Some((forecast, _, Some(expires))) if Utc::now() < *expires => {
+                return Ok(Arc::clone(forecast))
+            }
+            Some((_, Some(last_modified), _)) => {
+                request = request.header(IF_MODIFIED_SINCE, last_modified)
+            }
+            _ => {}
+        }
+        let response = request.send().await?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let expires = header(EXPIRES)
+            .and_then(|expires| DateTime::parse_from_rfc2822(&expires).ok())
+            .map(|expires| expires.with_timezone(&Utc));
+        let last_modified = header(LAST_MODIFIED);
+        let forecast = match cached {
+            Some((forecast, cached_last_modified, _))
+                if response.status() == StatusCode::NOT_MODIFIED =>
+            {
+                let last_modified = last_modified.or(cached_last_modified);
+                self.cache_forecast(url, &forecast, last_modified, expires);
+                return Ok(forecast);
+            }
+            _ => Arc::new(parse::<ForecastResponse>(response).await?),
+        };
+        self.cache_forecast(url, &forecast, last_modified, expires);
+        Ok(forecast)
+    }
+    fn cache_forecast(
+        &self,
+        url: Url,
+        forecast: &Arc<ForecastResponse>,
+        last_modified: Option<String>,
+        expires: Option<DateTime<Utc>>,
+    ) {
+        let cached = CachedForecast {
+            forecast: Arc::clone(forecast),
+            last_modified,
+            expires,
+        };
+        self.cache.lock().expect("cache lock").insert(url, cached);
+    }
+    /// The temperature of the day is the mean of the lowest and highest one of its steps, the
+    /// other values are taken from the step closest to the noon.
+    fn day(
+        &self,
+        forecast: &ForecastResponse,
+        date: NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Weather, MetNorwayError> {
+        let steps: Vec<_> = forecast
+            .properties
+            .timeseries
+            .iter()
+            .filter(|step| step.time.with_timezone(&offset).date_naive() == date)
+            .collect();
+        let noon = TimeOfDay {
+            time: NaiveTime::from_hms_opt(12, 0, 0).expect("Noon created"),
+            offset: None,
+        }
+        .timestamp(date, offset);
+        let step = steps
+            .iter()
+            .min_by_key(|step| (step.time.timestamp() - noon).abs())
+            .ok_or(MetNorwayError::UnsupportedDate(date))?;
+        let temperatures = steps
+            .iter()
+            .map(|step| step.data.instant.details.air_temperature);
+        let min = temperatures.clone().fold(f32::INFINITY, f32::min);
+        let max = temperatures.fold(f32::NEG_INFINITY, f32::max);
+        let mut weather = step_weather(step, WeatherKind::Forecast)?;
+        weather.temp = Temperature::from_c((min + max) / 2.0)?;
+        Ok(weather)
+    }
+}
+
+/// Returns the step of the current hour, its period summaries start at the time of the step.
+fn current_step(forecast: &ForecastResponse) -> Option<&TimeStep> {
+    let now = Utc::now();
+    let steps = &forecast.properties.timeseries;
+    steps
+        .iter()
+        .take_while(|step| step.time <= now)
+        .last()
+        .or(steps.first())
+}
+fn closest_step(forecast: &ForecastResponse, timestamp: i64) -> Option<&TimeStep> {
+    forecast
+        .properties
+        .timeseries
+        .iter()
+        .min_by_key(|step| (step.time.timestamp() - timestamp).abs())
+}
+fn step_weather(step: &TimeStep, kind: WeatherKind) -> Result<Weather, MetNorwayError> {
+    let details = &step.data.instant.details;
+    let temp = Temperature::from_c(details.air_temperature)?;
+    let symbol_code = step.symbol_code().unwrap_or_default();
+    let condition = description(symbol_code);
+    let mut weather = match kind {
+        WeatherKind::Current => Weather::current(temp, condition),
+        _ => Weather::forecast(temp, condition),
+    }
+    .with_condition_kind(condition_kind(symbol_code));
+    weather.humidity = details.relative_humidity;
+    weather.wind_speed = details.wind_speed;
+    weather.pressure = details.air_pressure_at_sea_level;
+    weather.dew_point = details
+        .dew_point_temperature
+        .map(Temperature::from_c)
+        .transpose()?;
+    Ok(weather)
+}
+/// Estimates the UTC offset of the location by the local mean time of its longitude.
+fn local_offset(coordinates: Coordinates) -> FixedOffset {
+    let minutes = (coordinates.lon * 4.0).round() as i32;
+    FixedOffset::east_opt(minutes * 60).expect("The offset of a longitude is within a day")
+}
+/// Parses the response, the errors are answered with plain text or HTML, so only the status is
+/// kept for them.
+async fn parse<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, MetNorwayError> {
+    let status = res.status();
+    if !status.is_success() {
+        let reason = status.canonical_reason().unwrap_or("Unexpected status");
+        return Err(MetNorwayError::Api(reason.to_string(), status.as_u16()));
+    }
+    res.json::<T>()
+        .await
+        .map_err(|e| MetNorwayError::HttpClient(reqwest_middleware::Error::Reqwest(e)))
+}
+fn json_error(endpoint: &Url, path: impl Into<String>) -> MetNorwayError {
+    MetNorwayError::Json(endpoint.to_string(), path.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, DurationRound, SecondsFormat};
+    use config::Config;
+    use httptest::{all_of, matchers::*, responders::*, Expectation, ServerHandle, ServerPool};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(20);
+    const USER_AGENT: &str = "weather-cli-tests/1.0 tests@example.com";
+
+    fn setup<'a>() -> (Config, ServerHandle<'a>) {
+        let server = SERVER_POOL.get_server();
+        let base_url = server.url_str("");
+        let conf = Config::builder()
+            .set_override(format!("{PROVIDER_NAME}.userAgent"), USER_AGENT)
+            .expect("User agent set")
+            .set_override(format!("{PROVIDER_NAME}.baseUrl"), base_url.clone())
+            .expect("Test baseUrl set")
+            .set_override(format!("{PROVIDER_NAME}.geocodingBaseUrl"), base_url)
+            .expect("Test geocodingBaseUrl set")
+            .build()
+            .expect("config built");
+        (conf, server)
+    }
+
+    /// A forecast with hourly steps from the current hour, the temperature rising by a degree
+    /// every hour from `start_temp`.
+    fn forecast(hours: i64, start_temp: f32, symbol_code: &str) -> Value {
+        let start = Utc::now()
+            .duration_trunc(Duration::hours(1))
+            .expect("hour truncated");
+        let timeseries: Vec<_> = (0..hours)
+            .map(|hour| {
+                let time = start + Duration::hours(hour);
+                json!({
+                    "time": time.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    "data": {
+                        "instant": { "details": {
+                            "air_pressure_at_sea_level": 1012.5,
+                            "air_temperature": start_temp + hour as f32,
+                            "cloud_area_fraction": 90.0,
+                            "relative_humidity": 71.2,
+                            "wind_from_direction": 210.0,
+                            "wind_speed": 4.3
+                        }},
+                        "next_1_hours": {
+                            "summary": { "symbol_code": symbol_code },
+                            "details": { "precipitation_amount": 0.4 }
+                        }
+                    }
+                })
+            })
+            .collect();
+        json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [10.7522, 59.9139, 12] },
+            "properties": {
+                "meta": { "updated_at": "2024-05-01T10:00:00Z", "units": {} },
+                "timeseries": timeseries
+            }
+        })
+    }
+
+    fn with_headers(body: Value, headers: &[(&'static str, String)]) -> impl Responder {
+        let mut responder = status_code(200).insert_header("Content-Type", "application/json");
+        for (name, value) in headers {
+            responder = responder.insert_header(*name, value.clone());
+        }
+        responder.body(body.to_string())
+    }
+
+    fn forecast_path() -> &'static str {
+        "/weatherapi/locationforecast/2.0/compact"
+    }
+
+    #[tokio::test]
+    async fn get_weather_current_geocoded() {
+        let (cfg, server) = setup();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/search"),
+                request::query(url_decoded(contains(("q", "Oslo")))),
+                request::headers(contains(("user-agent", USER_AGENT))),
+            ])
+            .respond_with(json_encoded(json!([{
+                "lat": "59.91273",
+                "lon": "10.746092",
+                "display_name": "Oslo, Norway"
+            }]))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", forecast_path()),
+                request::query(url_decoded(contains(("lat", "59.9127")))),
+                request::query(url_decoded(contains(("lon", "10.7461")))),
+                request::headers(contains(("user-agent", USER_AGENT))),
+            ])
+            .respond_with(json_encoded(forecast(48, 8.0, "lightrainshowers_day"))),
+        );
+
+        let provider = MetNorway::new(&cfg).expect("MetNorway created");
+        let weather = provider
+            .get_weather("Oslo", None)
+            .await
+            .expect("weather result should be ok");
+
+        assert_eq!(weather.kind, WeatherKind::Current);
+        assert_eq!(weather.temp, Temperature::from_c(8.0).unwrap());
+        assert_eq!(weather.condition, "Light rain showers");
+        assert_eq!(weather.condition_kind, ConditionKind::Rain);
+        assert_eq!(weather.humidity, Some(71.2));
+        assert_eq!(weather.wind_speed, Some(4.3));
+        assert_eq!(weather.pressure, Some(1012.5));
+    }
+
+    #[tokio::test]
+    async fn get_weather_day_mean_of_steps() {
+        let (cfg, server) = setup();
+        server.expect(
+            Expectation::matching(request::method_path("GET", forecast_path()))
+                .respond_with(json_encoded(forecast(72, 0.0, "cloudy"))),
+        );
+        let provider = MetNorway::new(&cfg).expect("MetNorway created");
+        let coordinates = Coordinates::new(59.91, 0.0).unwrap();
+        let tomorrow = Utc::now().date_naive() + chrono::Days::new(1);
+
+        let weather = provider
+            .get_weather(&coordinates.to_string(), Some(tomorrow))
+            .await
+            .expect("weather result should be ok");
+
+        // The steps of tomorrow are the whole UTC day, from 24 hours after the midnight of today
+        let first = (tomorrow.and_hms_opt(0, 0, 0).unwrap().and_utc()
+            - Utc::now().duration_trunc(Duration::hours(1)).unwrap())
+        .num_hours() as f32;
+        assert_eq!(weather.kind, WeatherKind::Forecast);
+        assert_eq!(weather.temp, Temperature::from_c(first + 11.5).unwrap());
+        assert_eq!(weather.condition_kind, ConditionKind::Cloudy);
+    }
+
+    #[tokio::test]
+    async fn get_weather_at_closest_step() {
+        let (cfg, server) = setup();
+        server.expect(
+            Expectation::matching(request::method_path("GET", forecast_path()))
+                .respond_with(json_encoded(forecast(48, 0.0, "fog"))),
+        );
+        let provider = MetNorway::new(&cfg).expect("MetNorway created");
+        let target = Utc::now().duration_trunc(Duration::hours(1)).unwrap() + Duration::hours(5);
+        let time = TimeOfDay {
+            time: target.time() + Duration::minutes(10),
+            offset: FixedOffset::east_opt(0),
+        };
+
+        let weather = provider
+            .get_weather_at("59.91,10.75", target.date_naive(), time)
+            .await
+            .expect("weather result should be ok");
+
+        assert_eq!(weather.temp, Temperature::from_c(5.0).unwrap());
+        assert_eq!(weather.condition, "Fog");
+        assert_eq!(
+            weather.time.map(|time| time.timestamp()),
+            Some(target.timestamp())
+        );
+    }
+
+    #[tokio::test]
+    async fn forecast_reused_until_expired() {
+        let (cfg, server) = setup();
+        let expires = (Utc::now() + Duration::minutes(30)).to_rfc2822();
+        server.expect(
+            Expectation::matching(request::method_path("GET", forecast_path()))
+                .times(1)
+                .respond_with(with_headers(
+                    forecast(24, 3.0, "clearsky_night"),
+                    &[("Expires", expires)],
+                )),
+        );
+        let provider = MetNorway::new(&cfg).expect("MetNorway created");
+
+        for _ in 0..2 {
+            let weather = provider
+                .get_weather("59.91,10.75", None)
+                .await
+                .expect("weather result should be ok");
+            assert_eq!(weather.condition, "Clear sky");
+        }
+    }
+
+    #[tokio::test]
+    async fn forecast_revalidated_when_expired() {
+        let (cfg, server) = setup();
+        let last_modified = "Wed, 01 May 2024 10:00:00 GMT";
+        let expired = (Utc::now() - Duration::minutes(1)).to_rfc2822();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", forecast_path()),
+                request::headers(not(contains(key("if-modified-since")))),
+            ])
+            .respond_with(with_headers(
+                forecast(24, 3.0, "snow"),
+                &[
+                    ("Expires", expired),
+                    ("Last-Modified", last_modified.to_string()),
+                ],
+            )),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", forecast_path()),
+                request::headers(contains(("if-modified-since", last_modified))),
+            ])
+            .respond_with(status_code(304)),
+        );
+        let provider = MetNorway::new(&cfg).expect("MetNorway created");
+
+        for _ in 0..2 {
+            let weather = provider
+                .get_weather("59.91,10.75", None)
+                .await
+                .expect("weather result should be ok");
+            assert_eq!(weather.condition_kind, ConditionKind::Snow);
+        }
+    }
+
+    #[tokio::test]
+    async fn location_not_found() {
+        let (cfg, server) = setup();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/search"))
+                .respond_with(json_encoded(json!([]))),
+        );
+        let provider = MetNorway::new(&cfg).expect("MetNorway created");
+
+        let err = provider
+            .get_weather("Nowhereville", None)
+            .await
+            .expect_err("location should not be found");
+        assert_error!(err, MetNorwayError::LocationNotFound(_));
+    }
+
+    #[tokio::test]
+    async fn api_error_without_identification() {
+        let (cfg, server) = setup();
+        server.expect(
+            Expectation::matching(request::method_path("GET", forecast_path()))
+                .respond_with(status_code(403).body("<html>Forbidden</html>")),
+        );
+        let provider = MetNorway::new(&cfg).expect("MetNorway created");
+
+        let err = provider
+            .get_weather("59.91,10.75", None)
+            .await
+            .expect_err("weather result should be err");
+        assert_error!(err, MetNorwayError::Api(_, 403));
+    }
+
+    #[rstest]
+    #[case(-1, 0)]
+    #[case(20, 1)]
+    #[tokio::test]
+    async fn get_weather_unsupported_date(#[case] days: i64, #[case] requests: usize) {
+        let (cfg, server) = setup();
+        server.expect(
+            Expectation::matching(request::method_path("GET", forecast_path()))
+                .times(requests)
+                .respond_with(json_encoded(forecast(24, 3.0, "fair_day"))),
+        );
+        let provider = MetNorway::new(&cfg).expect("MetNorway created");
+        let date = Utc::now().date_naive() + Duration::days(days);
+
+        let err = provider
+            .get_weather("59.91,0.0", Some(date))
+            .await
+            .expect_err("Unsupported date");
+        assert_error!(err, MetNorwayError::UnsupportedDate(_));
+    }
+
+    #[test]
+    fn new_without_user_agent() {
+        let cfg = Config::builder().build().expect("config built");
+        let err = MetNorway::new(&cfg)
+            .err()
+            .expect("provider should not be created");
+        assert_eq!(
+            err.to_string(),
+            "Configuration userAgent not found for provider met-norway"
+        );
+    }
+
+    #[rstest]
+    #[case("clearsky_day", "Clear sky", ConditionKind::Clear)]
+    #[case("fair_polartwilight", "Fair", ConditionKind::Clear)]
+    #[case("partlycloudy_night", "Partly cloudy", ConditionKind::PartlyCloudy)]
+    #[case("cloudy", "Cloudy", ConditionKind::Cloudy)]
+    #[case("fog", "Fog", ConditionKind::Fog)]
+    #[case("lightrain", "Light rain", ConditionKind::Rain)]
+    #[case("heavyrainshowers_day", "Heavy rain showers", ConditionKind::HeavyRain)]
+    #[case("rainandthunder", "Rain and thunder", ConditionKind::Thunderstorm)]
+    #[case(
+        "lightssleetshowersandthunder_day",
+        "Light sleet showers and thunder",
+        ConditionKind::Thunderstorm
+    )]
+    #[case("heavysleet", "Heavy sleet", ConditionKind::Sleet)]
+    #[case("snowshowers_night", "Snow showers", ConditionKind::Snow)]
+    #[case("tornado", "Tornado", ConditionKind::Unknown)]
+    fn symbol_code_mapping(
+        #[case] symbol_code: &str,
+        #[case] expected_description: &str,
+        #[case] expected_kind: ConditionKind,
+    ) {
+        assert_eq!(description(symbol_code), expected_description);
+        assert_eq!(condition_kind(symbol_code), expected_kind);
+    }
+}
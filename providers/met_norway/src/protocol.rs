@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use weather_abstractions::ConditionKind;
+
+/// A place found by the Nominatim search, with the coordinates as strings.
+#[derive(Deserialize)]
+pub struct Place {
+    pub lat: String,
+    pub lon: String,
+}
+
+#[derive(Deserialize)]
+pub struct ForecastResponse {
+    pub properties: Properties,
+}
+
+#[derive(Deserialize)]
+pub struct Properties {
+    pub timeseries: Vec<TimeStep>,
+}
+
+/// A step of the forecast, hourly for the next days and then every 6 hours.
+#[derive(Deserialize)]
+pub struct TimeStep {
+    pub time: DateTime<Utc>,
+    pub data: Data,
+}
+
+#[derive(Deserialize)]
+pub struct Data {
+    pub instant: Instant,
+    pub next_1_hours: Option<Period>,
+    pub next_6_hours: Option<Period>,
+    pub next_12_hours: Option<Period>,
+}
+
+#[derive(Deserialize)]
+pub struct Instant {
+    pub details: InstantDetails,
+}
+
+#[derive(Deserialize)]
+pub struct InstantDetails {
+    pub air_temperature: f32,
+    pub air_pressure_at_sea_level: Option<f32>,
+    pub relative_humidity: Option<f32>,
+    pub wind_speed: Option<f32>,
+    pub dew_point_temperature: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub struct Period {
+    pub summary: Summary,
+}
+
+#[derive(Deserialize)]
+pub struct Summary {
+    pub symbol_code: String,
+}
+
+impl TimeStep {
+    /// Returns the symbol of the shortest period following the step, the last steps have only
+    /// the longer periods.
+    pub fn symbol_code(&self) -> Option<&str> {
+        [
+            &self.data.next_1_hours,
+            &self.data.next_6_hours,
+            &self.data.next_12_hours,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|period| period.summary.symbol_code.as_str())
+        .next()
+    }
+}
+
+/// The words of the symbol codes like `lightrainshowersandthunder_day`, in the order they appear.
+const SYMBOL_WORDS: &[&str] = &[
+    "light", "heavy", "rain", "sleet", "snow", "showers", "and", "thunder",
+];
+
+/// Describes the symbol code without its variant, like `Light rain showers and thunder` for
+/// `lightrainshowersandthunder_day`. See https://api.met.no/weatherapi/weathericon/2.0/documentation
+pub fn description(symbol_code: &str) -> String {
+    let base = symbol_base(symbol_code);
+    let text = match base {
+        "clearsky" => "clear sky".to_string(),
+        "fair" => "fair".to_string(),
+        "partlycloudy" => "partly cloudy".to_string(),
+        "cloudy" => "cloudy".to_string(),
+        "fog" => "fog".to_string(),
+        _ => symbol_words(base).unwrap_or_else(|| base.to_string()),
+    };
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => "Unknown".to_string(),
+    }
+}
+
+/// Splits the symbol code into the words, if it is made of the known ones only.
+fn symbol_words(base: &str) -> Option<String> {
+    // The API misspells a couple of the codes, like lightssleetshowersandthunder
+    let base = base.replacen("lightss", "lights", 1);
+    let mut rest = base.as_str();
+    let mut words = Vec::new();
+    while !rest.is_empty() {
+        let word = SYMBOL_WORDS.iter().find(|word| rest.starts_with(**word))?;
+        words.push(*word);
+        rest = &rest[word.len()..];
+    }
+    Some(words.join(" "))
+}
+
+/// Maps the symbol code to the normalized condition.
+pub fn condition_kind(symbol_code: &str) -> ConditionKind {
+    let base = symbol_base(symbol_code);
+    match base {
+        "clearsky" | "fair" => ConditionKind::Clear,
+        "partlycloudy" => ConditionKind::PartlyCloudy,
+        "cloudy" => ConditionKind::Cloudy,
+        "fog" => ConditionKind::Fog,
+        _ if base.contains("thunder") => ConditionKind::Thunderstorm,
+        _ if base.contains("sleet") => ConditionKind::Sleet,
+        _ if base.contains("snow") => ConditionKind::Snow,
+        _ if base.starts_with("heavyrain") => ConditionKind::HeavyRain,
+        _ if base.contains("rain") => ConditionKind::Rain,
+        _ => ConditionKind::Unknown,
+    }
+}
+
+/// Strips the `_day`, `_night` or `_polartwilight` variant of the symbol code.
+fn symbol_base(symbol_code: &str) -> &str {
+    symbol_code
+        .split_once('_')
+        .map_or(symbol_code, |(base, _)| base)
+}
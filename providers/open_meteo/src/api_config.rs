@@ -0,0 +1,119 @@
+use std::error::Error;
+
+use crate::error::OpenMeteoError;
+
+use super::OpenMeteo;
+use config::{Config, ConfigError};
+use reqwest::{Client, Url};
+use reqwest_middleware::ClientBuilder;
+use reqwest_tracing::TracingMiddleware;
+use serde::{Deserialize, Serialize};
+use weather_abstractions::utils::*;
+use weather_abstractions::*;
+
+pub const PROVIDER_NAME: &str = "open-meteo";
+
+/// The settings supported by the provider, none is required as the API has no key.
+pub const SETTINGS: &[SettingDescriptor] = &[
+    SettingDescriptor::optional("baseUrl", "The base URL of the Open-Meteo forecast API"),
+    SettingDescriptor::optional("geocodingBaseUrl", "The base URL of the geocoding API"),
+    SettingDescriptor::optional(
+        "archiveBaseUrl",
+        "The base URL of the historical weather API",
+    ),
+    SettingDescriptor::optional("forecastPath", "The path of the forecast endpoint"),
+    SettingDescriptor::optional("geocodingPath", "The path of the geocoding endpoint"),
+    SettingDescriptor::optional("archivePath", "The path of the archive endpoint"),
+];
+
+generate_functions! {
+    base_url, "https://api.open-meteo.com",
+    geocoding_base_url, "https://geocoding-api.open-meteo.com",
+    archive_base_url, "https://archive-api.open-meteo.com",
+    forecast_path, "/v1/forecast",
+    geocoding_path, "/v1/search",
+    archive_path, "/v1/archive"
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ApiConfig {
+    #[serde(default = "base_url")]
+    pub base_url: String,
+    #[serde(default = "geocoding_base_url")]
+    pub geocoding_base_url: String,
+    #[serde(default = "archive_base_url")]
+    pub archive_base_url: String,
+    #[serde(default = "forecast_path")]
+    pub forecast_path: String,
+    #[serde(default = "geocoding_path")]
+    pub geocoding_path: String,
+    #[serde(default = "archive_path")]
+    pub archive_path: String,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            base_url: base_url(),
+            geocoding_base_url: geocoding_base_url(),
+            archive_base_url: archive_base_url(),
+            forecast_path: forecast_path(),
+            geocoding_path: geocoding_path(),
+            archive_path: archive_path(),
+        }
+    }
+}
+
+pub struct Endpoints {
+    pub forecast: Url,
+    pub geocoding: Url,
+    pub archive: Url,
+}
+
+impl TryFrom<ApiConfig> for Endpoints {
+    type Error = OpenMeteoError;
+    fn try_from(value: ApiConfig) -> Result<Self, Self::Error> {
+        let parse = |name: &str, url: &str| {
+            Url::parse(url).map_err(|e| {
+                OpenMeteoError::Parse(
+                    format!("{PROVIDER_NAME}/{name}"),
+                    url.to_string(),
+                    e.to_string(),
+                )
+            })
+        };
+        let base_url = parse("baseUrl", &value.base_url)?;
+        let geocoding_base_url = parse("geocodingBaseUrl", &value.geocoding_base_url)?;
+        let archive_base_url = parse("archiveBaseUrl", &value.archive_base_url)?;
+        Ok(Self {
+            forecast: build_endpoint(&base_url, &value.forecast_path),
+            geocoding: build_endpoint(&geocoding_base_url, &value.geocoding_path),
+            archive: build_endpoint(&archive_base_url, &value.archive_path),
+        })
+    }
+}
+
+impl OpenMeteo {
+    /// Builds the provider from its section of the settings, the section is optional.
+    pub fn new(cfg: &Config) -> Result<OpenMeteo, Box<dyn Error + Send + Sync>> {
+        let api_conf: ApiConfig = match cfg.get(PROVIDER_NAME) {
+            Ok(api_conf) => api_conf,
+            Err(ConfigError::NotFound(_)) => ApiConfig::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let endpoints = Endpoints::try_from(api_conf)?;
+        Ok(OpenMeteo {
+            endpoints,
+            client: ClientBuilder::new(Client::new())
+                .with(TracingMiddleware::<ProviderSpan<Provider>>::new())
+                .build(),
+        })
+    }
+}
+
+/// The provider of the request spans, the API is used without a key.
+struct Provider;
+impl ProviderSpanConfig for Provider {
+    const NAME: &'static str = PROVIDER_NAME;
+}
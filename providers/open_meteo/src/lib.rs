@@ -0,0 +1,510 @@
+mod api_config;
+mod error;
+mod protocol;
+
+use std::error::Error;
+
+use self::api_config::Endpoints;
+pub use api_config::{PROVIDER_NAME, SETTINGS};
+use chrono::{Days, NaiveDate, NaiveDateTime};
+pub use error::OpenMeteoError;
+use protocol::*;
+use reqwest::Url;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::de::DeserializeOwned;
+use weather_abstractions::*;
+
+/// The provider of the free Open-Meteo APIs, which need no API key.
+pub struct OpenMeteo {
+    endpoints: Endpoints,
+    client: ClientWithMiddleware,
+}
+/// The forecast covers 16 days, today included.
+const MAX_FORECAST_DAYS: u64 = 15;
+/// The archive is updated with a delay of a few days, the recent past days are taken from the
+/// forecast API instead.
+const RECENT_DAYS: u64 = 7;
+const CURRENT: &str = "temperature_2m,weather_code,relative_humidity_2m,apparent_temperature,\
+                       dew_point_2m,wind_speed_10m,pressure_msl";
+const DAILY: &str = "weather_code,temperature_2m_max,temperature_2m_min,\
+                     apparent_temperature_max,apparent_temperature_min,wind_speed_10m_max";
+
+use async_trait::async_trait;
+#[async_trait]
+impl WeatherProvider for OpenMeteo {
+    async fn get_weather(
+        &self,
+        address: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather(self, address, date).await?)
+    }
+    async fn get_weather_range(
+        &self,
+        address: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Weather)>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather_range(self, address, from, to).await?)
+    }
+    async fn local_date(
+        &self,
+        address: &str,
+    ) -> Result<NaiveDate, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::local_date(self, address).await?)
+    }
+}
+impl OpenMeteo {
+    async fn get_weather(
+        &self,
+        address: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<Weather, OpenMeteoError> {
+        let today = chrono::offset::Utc::now().date_naive();
+        match date {
+            Some(date) if date != today => {
+                let mut days = self.get_weather_range(address, date, date).await?;
+                days.pop()
+                    .map(|(_, weather)| weather)
+                    .ok_or_else(|| json_error(&self.endpoints.forecast, "daily.time"))
+            }
+            _ => self.current(address).await,
+        }
+    }
+    /// Requests the days older than `RECENT_DAYS` from the archive and the others from the
+    /// forecast, with a single request for each API.
+    async fn get_weather_range(
+        &self,
+        address: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Weather)>, OpenMeteoError> {
+        let today = chrono::offset::Utc::now().date_naive();
+        let min_date = NaiveDate::from_ymd_opt(1940, 1, 1).expect("Date 1940-1-1 created");
+        let max_date = today + Days::new(MAX_FORECAST_DAYS);
+        if from < min_date {
+            return Err(OpenMeteoError::UnsupportedDate(from));
+        }
+        if to > max_date {
+            return Err(OpenMeteoError::UnsupportedDate(to));
+        }
+        let coordinates = self.coordinates(address).await?;
+        let recent = today - Days::new(RECENT_DAYS);
+        let mut days = Vec::new();
+        if from < recent {
+            let end = to.min(recent - Days::new(1));
+            let endpoint = &self.endpoints.archive;
+            days.extend(self.daily(endpoint, coordinates, from, end).await?);
+        }
+        if to >= recent {
+            let start = from.max(recent);
+            let endpoint = &self.endpoints.forecast;
+            days.extend(self.daily(endpoint, coordinates, start, to).await?);
+        }
+        Ok(days)
+    }
+    /// Takes the date from the local time of the location in the current weather response.
+    async fn local_date(&self, address: &str) -> Result<NaiveDate, OpenMeteoError> {
+        let coordinates = self.coordinates(address).await?;
+        let resp = self.current_response(coordinates).await?;
+        local_time(&resp.current.time)
+            .map(|time| time.date())
+            .ok_or_else(|| json_error(&self.endpoints.forecast, "current.time"))
+    }
+    /// Returns the coordinates of the address, found with the geocoding API unless the address is
+    /// already the coordinates.
+    async fn coordinates(&self, address: &str) -> Result<Coordinates, OpenMeteoError> {
+        if let Ok(coordinates) = address.parse::<Coordinates>() {
+            return Ok(coordinates);
+        }
+        let response = self
+            .client
+            .get(self.endpoints.geocoding.clone())
+            .query(&[("name", address), ("count", "1"), ("format", "json")])
+            .send()
+            .await?;
+        let mut resp = parse::<GeocodingResponse>(response).await?;
+        let place = resp
+            .results
+            .pop()
+            .ok_or_else(|| OpenMeteoError::LocationNotFound(address.to_string()))?;
+        Ok(Coordinates {
+            lat: place.latitude,
+            lon: place.longitude,
+        })
+    }
+    fn default_request_builder(
+        &self,
+        endpoint: &Url,
+        coordinates: Coordinates,
+    ) -> reqwest_middleware::RequestBuilder {
+        self.client.get(endpoint.clone()).query(&[
+            ("latitude", coordinates.lat.to_string()),
+            ("longitude", coordinates.lon.to_string()),
+            ("wind_speed_unit", "ms".to_string()),
+            ("timezone", "auto".to_string()),
+        ])
+    }
+    async fn current_response(
+        &self,
+        coordinates: Coordinates,
+    ) -> Result<CurrentResponse, OpenMeteoError> {
+        let response = self
+            .default_request_builder(&self.endpoints.forecast, coordinates)
+            .query(&[("current", CURRENT)])
+            .send()
+            .await?;
+        parse::<CurrentResponse>(response).await
+    }
+    async fn current(&self, address: &str) -> Result<Weather, OpenMeteoError> {
+        let coordinates = self.coordinates(address).await?;
+        let current = self.current_response(coordinates).await?.current;
+        let (condition, condition_kind) = condition(current.weather_code);
+        let mut weather = Weather::current(Temperature::from_c(current.temperature_2m)?, condition)
+            .with_condition_kind(condition_kind);
+        weather.humidity = current.relative_humidity_2m;
+        weather.wind_speed = current.wind_speed_10m;
+        weather.pressure = current.pressure_msl;
+        weather.feels_like = current
+            .apparent_temperature
+            .map(Temperature::from_c)
+            .transpose()?;
+        weather.dew_point = current.dew_point_2m.map(Temperature::from_c).transpose()?;
+        Ok(weather)
+    }
+    /// Requests the daily values of the days from the forecast or the archive API, the
+    /// temperature of a day is the mean of its minimum and maximum.
+    async fn daily(
+        &self,
+        endpoint: &Url,
+        coordinates: Coordinates,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Weather)>, OpenMeteoError> {
+        let today = chrono::offset::Utc::now().date_naive();
+        let response = self
+            .default_request_builder(endpoint, coordinates)
+            .query(&[
+                ("daily", DAILY.to_string()),
+                ("start_date", from.format("%Y-%m-%d").to_string()),
+                ("end_date", to.format("%Y-%m-%d").to_string()),
+            ])
+            .send()
+            .await?;
+        let daily = parse::<DailyResponse>(response).await?.daily;
+        let value = |values: &[Option<f32>], index: usize| values.get(index).copied().flatten();
+        let mut days = Vec::new();
+        for (index, date) in daily.time.iter().copied().enumerate() {
+            let (Some(max), Some(min)) = (
+                value(&daily.temperature_2m_max, index),
+                value(&daily.temperature_2m_min, index),
+            ) else {
+                return Err(json_error(endpoint, format!("daily.temperature_2m/{date}")));
+            };
+            let temp = Temperature::from_c((max + min) / 2.0)?;
+            let code = daily.weather_code.get(index).copied().flatten();
+            let (condition, condition_kind) = condition(code);
+            let weather = match date < today {
+                true => Weather::history(temp, condition),
+                false => Weather::forecast(temp, condition),
+            };
+            let mut weather = weather.with_condition_kind(condition_kind);
+            weather.wind_speed = value(&daily.wind_speed_10m_max, index);
+            if let (Some(max), Some(min)) = (
+                value(&daily.apparent_temperature_max, index),
+                value(&daily.apparent_temperature_min, index),
+            ) {
+                weather.feels_like = Some(Temperature::from_c((max + min) / 2.0)?);
+            }
+            days.push((date, weather));
+        }
+        Ok(days)
+    }
+}
+
+/// Parses the response, the errors are answered with the reason and the status code.
+async fn parse<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, OpenMeteoError> {
+    let code = res.status().as_u16();
+    let resp_or_error = crate::utils::parse::<T, ErrorResponse>(res)
+        .await
+        .map_err(reqwest_middleware::Error::Reqwest)?;
+    resp_or_error.map_err(|e| OpenMeteoError::Api(e.reason, code))
+}
+fn local_time(time: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").ok()
+}
+fn json_error(endpoint: &Url, path: impl Into<String>) -> OpenMeteoError {
+    OpenMeteoError::Json(endpoint.to_string(), path.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::Config;
+    use httptest::{all_of, matchers::*, responders::*, Expectation, ServerHandle, ServerPool};
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(20);
+
+    fn setup<'a>() -> (Config, ServerHandle<'a>) {
+        let server = SERVER_POOL.get_server();
+        let base_url = server.url_str("");
+        let mut builder = Config::builder();
+        for name in ["baseUrl", "geocodingBaseUrl", "archiveBaseUrl"] {
+            builder = builder
+                .set_override(format!("{PROVIDER_NAME}.{name}"), base_url.clone())
+                .expect("Test base URL set");
+        }
+        (builder.build().expect("config built"), server)
+    }
+
+    fn expect_geocoding(server: &ServerHandle, name: &str, body: Value) {
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1/search"),
+                request::query(url_decoded(contains(("name", name.to_string())))),
+            ])
+            .respond_with(json_encoded(body)),
+        );
+    }
+
+    fn lviv() -> Value {
+        json!({
+            "results": [{
+                "id": 702550,
+                "name": "Lviv",
+                "latitude": 49.83826,
+                "longitude": 24.02324,
+                "country": "Ukraine",
+                "timezone": "Europe/Kyiv"
+            }],
+            "generationtime_ms": 0.5
+        })
+    }
+
+    fn daily(dates: &[NaiveDate], max: f32, min: f32, code: u8) -> Value {
+        let count = dates.len();
+        json!({
+            "latitude": 49.84,
+            "longitude": 24.02,
+            "utc_offset_seconds": 10800,
+            "daily": {
+                "time": dates,
+                "weather_code": vec![code; count],
+                "temperature_2m_max": vec![max; count],
+                "temperature_2m_min": vec![min; count],
+                "apparent_temperature_max": vec![max - 1.0; count],
+                "apparent_temperature_min": vec![min - 1.0; count],
+                "wind_speed_10m_max": vec![5.5; count],
+            }
+        })
+    }
+
+    fn days_from_today(days: i64) -> NaiveDate {
+        chrono::offset::Utc::now().date_naive() + chrono::Duration::days(days)
+    }
+
+    #[tokio::test]
+    async fn get_weather_current_geocoded() {
+        let (cfg, server) = setup();
+        expect_geocoding(&server, "Lviv", lviv());
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1/forecast"),
+                request::query(url_decoded(contains(("latitude", "49.83826")))),
+                request::query(url_decoded(contains(("longitude", "24.02324")))),
+                request::query(url_decoded(contains(key("current")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "utc_offset_seconds": 10800,
+                "current": {
+                    "time": "2024-05-01T12:15",
+                    "interval": 900,
+                    "temperature_2m": 18.4,
+                    "weather_code": 61,
+                    "relative_humidity_2m": 72,
+                    "apparent_temperature": 17.1,
+                    "dew_point_2m": 13.2,
+                    "wind_speed_10m": 3.4,
+                    "pressure_msl": 1013.2
+                }
+            }))),
+        );
+
+        let provider = OpenMeteo::new(&cfg).expect("OpenMeteo created");
+        let weather = provider
+            .get_weather("Lviv", None)
+            .await
+            .expect("weather result should be ok");
+
+        assert_eq!(weather.kind, WeatherKind::Current);
+        assert_eq!(weather.temp, Temperature::from_c(18.4).unwrap());
+        assert_eq!(weather.condition, "Slight rain");
+        assert_eq!(weather.condition_kind, ConditionKind::Rain);
+        assert_eq!(weather.humidity, Some(72.0));
+        assert_eq!(weather.wind_speed, Some(3.4));
+        assert_eq!(weather.pressure, Some(1013.2));
+        assert_eq!(weather.feels_like, Temperature::from_c(17.1).ok());
+    }
+
+    #[rstest]
+    #[case(3, "/v1/forecast", WeatherKind::Forecast)]
+    #[case(15, "/v1/forecast", WeatherKind::Forecast)]
+    #[case(-2, "/v1/forecast", WeatherKind::History)]
+    #[case(-30, "/v1/archive", WeatherKind::History)]
+    #[tokio::test]
+    async fn get_weather_daily(
+        #[case] days: i64,
+        #[case] path: &str,
+        #[case] expected_kind: WeatherKind,
+    ) {
+        let (cfg, server) = setup();
+        let date = days_from_today(days);
+        let date_param = date.format("%Y-%m-%d").to_string();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", path.to_string()),
+                request::query(url_decoded(contains(("latitude", "50.45")))),
+                request::query(url_decoded(contains(("start_date", date_param.clone())))),
+                request::query(url_decoded(contains(("end_date", date_param)))),
+            ])
+            .respond_with(json_encoded(daily(&[date], 20.0, 10.0, 3))),
+        );
+
+        let provider = OpenMeteo::new(&cfg).expect("OpenMeteo created");
+        let weather = provider
+            .get_weather("50.45,30.52", Some(date))
+            .await
+            .expect("weather result should be ok");
+
+        assert_eq!(weather.kind, expected_kind);
+        assert_eq!(weather.temp, Temperature::from_c(15.0).unwrap());
+        assert_eq!(weather.condition, "Overcast");
+        assert_eq!(weather.condition_kind, ConditionKind::Cloudy);
+        assert_eq!(weather.feels_like, Temperature::from_c(14.0).ok());
+        assert_eq!(weather.wind_speed, Some(5.5));
+    }
+
+    #[tokio::test]
+    async fn get_weather_range_split_between_archive_and_forecast() {
+        let (cfg, server) = setup();
+        let from = days_from_today(-9);
+        let to = days_from_today(1);
+        let archive_days: Vec<_> = from.iter_days().take(2).collect();
+        let forecast_days: Vec<_> = days_from_today(-7).iter_days().take(9).collect();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/v1/archive"))
+                .respond_with(json_encoded(daily(&archive_days, 4.0, 0.0, 71))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/v1/forecast"))
+                .respond_with(json_encoded(daily(&forecast_days, 8.0, 2.0, 0))),
+        );
+
+        let provider = OpenMeteo::new(&cfg).expect("OpenMeteo created");
+        let days = provider
+            .get_weather_range("50.45,30.52", from, to)
+            .await
+            .expect("weather result should be ok");
+
+        let dates: Vec<_> = days.iter().map(|(date, _)| *date).collect();
+        let expected: Vec<_> = from.iter_days().take(11).collect();
+        assert_eq!(dates, expected);
+        assert_eq!(days[0].1.condition_kind, ConditionKind::Snow);
+        assert_eq!(days[10].1.condition_kind, ConditionKind::Clear);
+    }
+
+    #[tokio::test]
+    async fn local_date_of_location() {
+        let (cfg, server) = setup();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/v1/forecast")).respond_with(
+                json_encoded(json!({
+                    "utc_offset_seconds": 46800,
+                    "current": { "time": "2024-01-02T00:30", "temperature_2m": 21.0 }
+                })),
+            ),
+        );
+
+        let provider = OpenMeteo::new(&cfg).expect("OpenMeteo created");
+        let date = provider
+            .local_date("-36.85,174.76")
+            .await
+            .expect("local date should be ok");
+        assert_eq!(date, NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+    }
+
+    #[tokio::test]
+    async fn location_not_found() {
+        let (cfg, server) = setup();
+        expect_geocoding(&server, "Nowhereville", json!({ "generationtime_ms": 0.3 }));
+
+        let provider = OpenMeteo::new(&cfg).expect("OpenMeteo created");
+        let err = provider
+            .get_weather("Nowhereville", None)
+            .await
+            .expect_err("location should not be found");
+        assert_error!(err, OpenMeteoError::LocationNotFound(_));
+    }
+
+    #[tokio::test]
+    async fn api_error_with_reason() {
+        let (cfg, server) = setup();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/v1/forecast")).respond_with(
+                status_code(400).body(
+                    json!({ "error": true, "reason": "Latitude must be in range of -90 to 90°." })
+                        .to_string(),
+                ),
+            ),
+        );
+
+        let provider = OpenMeteo::new(&cfg).expect("OpenMeteo created");
+        let err = provider
+            .get_weather("50.45,30.52", None)
+            .await
+            .expect_err("weather result should be err");
+        assert_error!(err, OpenMeteoError::Api(_, 400));
+    }
+
+    #[rstest]
+    #[case(days_from_today(16))]
+    #[case(days_from_today(365))]
+    #[case(NaiveDate::from_ymd_opt(1939, 12, 31).unwrap())]
+    #[tokio::test]
+    async fn get_weather_unsupported_date(#[case] date: NaiveDate) {
+        let (cfg, _server) = setup();
+        let provider = OpenMeteo::new(&cfg).expect("OpenMeteo created");
+
+        let err = provider
+            .get_weather("50.45,30.52", Some(date))
+            .await
+            .expect_err("Unsupported date");
+        assert_error!(err, OpenMeteoError::UnsupportedDate(_));
+    }
+
+    #[test]
+    fn new_without_settings_section() {
+        let cfg = Config::builder().build().expect("config built");
+        let provider = OpenMeteo::new(&cfg).expect("OpenMeteo created");
+        assert_eq!(
+            provider.endpoints.forecast.as_str(),
+            "https://api.open-meteo.com/v1/forecast"
+        );
+    }
+
+    #[rstest]
+    #[case(0, ConditionKind::Clear)]
+    #[case(2, ConditionKind::PartlyCloudy)]
+    #[case(48, ConditionKind::Fog)]
+    #[case(55, ConditionKind::Drizzle)]
+    #[case(66, ConditionKind::Sleet)]
+    #[case(82, ConditionKind::HeavyRain)]
+    #[case(86, ConditionKind::Snow)]
+    #[case(99, ConditionKind::Thunderstorm)]
+    #[case(42, ConditionKind::Unknown)]
+    fn condition_kind_from_code(#[case] code: u8, #[case] expected: ConditionKind) {
+        assert_eq!(condition(Some(code)).1, expected);
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use weather_abstractions::ConditionKind;
+
+#[derive(Deserialize)]
+pub struct ErrorResponse {
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct GeocodingResponse {
+    /// Missing when no place matches the name
+    #[serde(default)]
+    pub results: Vec<Place>,
+}
+
+#[derive(Deserialize)]
+pub struct Place {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Deserialize)]
+pub struct CurrentResponse {
+    pub current: Current,
+}
+
+#[derive(Deserialize)]
+pub struct Current {
+    /// The local time of the location like 2024-05-01T12:15
+    pub time: String,
+    pub temperature_2m: f32,
+    pub weather_code: Option<u8>,
+    pub relative_humidity_2m: Option<f32>,
+    pub apparent_temperature: Option<f32>,
+    pub dew_point_2m: Option<f32>,
+    pub wind_speed_10m: Option<f32>,
+    /// The pressure reduced to the mean sea level, as returned by the other providers
+    pub pressure_msl: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub struct DailyResponse {
+    pub daily: Daily,
+}
+
+/// The daily values by column, the values of a day share the index of its date. The values are
+/// `null` for the days the model has no data for.
+#[derive(Deserialize)]
+pub struct Daily {
+    pub time: Vec<NaiveDate>,
+    pub weather_code: Vec<Option<u8>>,
+    pub temperature_2m_max: Vec<Option<f32>>,
+    pub temperature_2m_min: Vec<Option<f32>>,
+    #[serde(default)]
+    pub apparent_temperature_max: Vec<Option<f32>>,
+    #[serde(default)]
+    pub apparent_temperature_min: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_speed_10m_max: Vec<Option<f32>>,
+}
+
+/// Maps the WMO weather interpretation code to its description and the normalized condition.
+/// See https://open-meteo.com/en/docs
+pub fn condition(code: Option<u8>) -> (&'static str, ConditionKind) {
+    match code {
+        Some(0) => ("Clear sky", ConditionKind::Clear),
+        Some(1) => ("Mainly clear", ConditionKind::Clear),
+        Some(2) => ("Partly cloudy", ConditionKind::PartlyCloudy),
+        Some(3) => ("Overcast", ConditionKind::Cloudy),
+        Some(45) => ("Fog", ConditionKind::Fog),
+        Some(48) => ("Depositing rime fog", ConditionKind::Fog),
+        Some(51) => ("Light drizzle", ConditionKind::Drizzle),
+        Some(53) => ("Moderate drizzle", ConditionKind::Drizzle),
+        Some(55) => ("Dense drizzle", ConditionKind::Drizzle),
+        Some(56 | 57) => ("Freezing drizzle", ConditionKind::Sleet),
+        Some(61) => ("Slight rain", ConditionKind::Rain),
+        Some(63) => ("Moderate rain", ConditionKind::Rain),
+        Some(65) => ("Heavy rain", ConditionKind::HeavyRain),
+        Some(66 | 67) => ("Freezing rain", ConditionKind::Sleet),
+        Some(71) => ("Slight snow fall", ConditionKind::Snow),
+        Some(73) => ("Moderate snow fall", ConditionKind::Snow),
+        Some(75) => ("Heavy snow fall", ConditionKind::Snow),
+        Some(77) => ("Snow grains", ConditionKind::Snow),
+        Some(80) => ("Slight rain showers", ConditionKind::Rain),
+        Some(81) => ("Moderate rain showers", ConditionKind::Rain),
+        Some(82) => ("Violent rain showers", ConditionKind::HeavyRain),
+        Some(85 | 86) => ("Snow showers", ConditionKind::Snow),
+        Some(95) => ("Thunderstorm", ConditionKind::Thunderstorm),
+        Some(96 | 99) => ("Thunderstorm with hail", ConditionKind::Thunderstorm),
+        _ => ("Unknown", ConditionKind::Unknown),
+    }
+}
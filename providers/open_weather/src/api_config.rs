@@ -5,18 +5,42 @@ use crate::error::OpenWeatherError;
 use super::OpenWeatherMap;
 use config::Config;
 use reqwest::{Client, Url};
+use reqwest_middleware::ClientBuilder;
+use reqwest_tracing::TracingMiddleware;
 use serde::{Deserialize, Serialize};
 use weather_abstractions::utils::*;
 use weather_abstractions::*;
 
 pub const PROVIDER_NAME: &str = "open-weather";
 
+/// The settings supported by the provider.
+pub const SETTINGS: &[SettingDescriptor] = &[
+    SettingDescriptor::secret("apiKey", "The OpenWeather API key"),
+    SettingDescriptor::optional("baseUrl", "The base URL of the OpenWeather API"),
+    SettingDescriptor::optional("historyBaseUrl", "The base URL of the history API"),
+    SettingDescriptor::optional("weatherPath", "The path of the current weather endpoint"),
+    SettingDescriptor::optional("historyPath", "The path of the history endpoint"),
+    SettingDescriptor::optional("forecastPath", "The path of the daily forecast endpoint"),
+    SettingDescriptor::optional(
+        "hourlyForecastPath",
+        "The path of the 3-hour step forecast endpoint",
+    ),
+    SettingDescriptor::optional("airPollutionPath", "The path of the air pollution endpoint"),
+    SettingDescriptor::optional(
+        "oneCallPath",
+        "The path of the One Call endpoint, used for the alerts",
+    ),
+];
+
 generate_functions! {
     base_url, "https://api.openweathermap.org",
     history_base_url, "https://history.api.openweathermap.org",
     weather_path, "/data/2.5/weather",
     history_path, "/data/2.5/history/city",
-    forecast_path, "/data/2.5/forecast/daily"
+    forecast_path, "/data/2.5/forecast/daily",
+    hourly_forecast_path, "/data/2.5/forecast",
+    one_call_path, "/data/3.0/onecall",
+    air_pollution_path, "/data/2.5/air_pollution"
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -33,11 +57,20 @@ pub(crate) struct ApiConfig {
     pub history_path: String,
     #[serde(default = "forecast_path")]
     pub forecast_path: String,
+    #[serde(default = "hourly_forecast_path")]
+    pub hourly_forecast_path: String,
+    #[serde(default = "one_call_path")]
+    pub one_call_path: String,
+    #[serde(default = "air_pollution_path")]
+    pub air_pollution_path: String,
 }
 pub struct Endpoints {
     pub weather: Url,
     pub history: Url,
     pub forecast: Url,
+    pub hourly_forecast: Url,
+    pub one_call: Url,
+    pub air_pollution: Url,
 }
 
 impl TryFrom<ApiConfig> for Endpoints {
@@ -61,6 +94,9 @@ impl TryFrom<ApiConfig> for Endpoints {
             weather: build_endpoint(&base_url, &value.weather_path),
             history: build_endpoint(&history_base_url, &value.history_path),
             forecast: build_endpoint(&base_url, &value.forecast_path),
+            hourly_forecast: build_endpoint(&base_url, &value.hourly_forecast_path),
+            one_call: build_endpoint(&base_url, &value.one_call_path),
+            air_pollution: build_endpoint(&base_url, &value.air_pollution_path),
         })
     }
 }
@@ -79,7 +115,16 @@ impl OpenWeatherMap {
         Ok(OpenWeatherMap {
             api_key,
             endpoints,
-            client: Client::new(),
+            client: ClientBuilder::new(Client::new())
+                .with(TracingMiddleware::<ProviderSpan<Provider>>::new())
+                .build(),
         })
     }
 }
+
+/// The provider of the request spans, the `appid` API key is redacted from their URL.
+struct Provider;
+impl ProviderSpanConfig for Provider {
+    const NAME: &'static str = PROVIDER_NAME;
+    const SECRETS: &'static [&'static str] = &["appid"];
+}
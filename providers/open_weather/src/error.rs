@@ -7,7 +7,7 @@ pub enum OpenWeatherError {
     #[error("Unexpected temperature")]
     Temperature(#[from] TemperatureError),
     #[error("Http client request error")]
-    HttpClient(#[from] reqwest::Error),
+    HttpClient(#[from] reqwest_middleware::Error),
     #[error("Api error: {0} code: {1}")]
     Api(String, u16),
     #[error("{0} returns unexpected JSON, empty '{1}' section")]
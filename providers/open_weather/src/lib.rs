@@ -5,20 +5,22 @@ mod protocol;
 use std::error::Error;
 
 use self::api_config::Endpoints;
-pub use api_config::PROVIDER_NAME;
-use chrono::{NaiveDate, NaiveDateTime};
+pub use api_config::{PROVIDER_NAME, SETTINGS};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
 use error::OpenWeatherError;
 use protocol::*;
-use reqwest::{Client, Url};
+use reqwest::Url;
+use reqwest_middleware::ClientWithMiddleware;
 use weather_abstractions::*;
 
 pub struct OpenWeatherMap {
     api_key: String,
     endpoints: Endpoints,
-    client: Client,
+    client: ClientWithMiddleware,
 }
 const MIN_FORECAS_DAYS: i64 = 1;
 const MAX_FORECAS_DAYS: i64 = 16;
+const MAX_HOURLY_FORECAST_DAYS: i64 = 5;
 
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
@@ -31,6 +33,39 @@ impl WeatherProvider for OpenWeatherMap {
     ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
         Ok(Self::get_weather(self, address, date).await?)
     }
+    async fn local_date(
+        &self,
+        address: &str,
+    ) -> Result<NaiveDate, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::local_date(self, address).await?)
+    }
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather_at(self, address, date, time).await?)
+    }
+    async fn get_alerts(
+        &self,
+        address: &str,
+    ) -> Result<Vec<Alert>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_alerts(self, address).await?)
+    }
+    async fn get_air_quality(
+        &self,
+        address: &str,
+    ) -> Result<AirQuality, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_air_quality(self, address).await?)
+    }
+    async fn get_astronomy(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Astronomy, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_astronomy(self, address, date).await?)
+    }
 }
 impl OpenWeatherMap {
     async fn get_weather(
@@ -51,10 +86,174 @@ impl OpenWeatherMap {
         }
         self.today(address).await
     }
-    fn default_request_builder(&self, endpoint: &Url, address: &str) -> reqwest::RequestBuilder {
-        self.client
-            .get(endpoint.clone())
-            .query(&[("q", address), ("appid", self.api_key.as_str())])
+    /// Uses the 3-hour step forecast for the next days and the hourly history for the past dates.
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, OpenWeatherError> {
+        let today = chrono::offset::Utc::now().date_naive();
+        let min_date = NaiveDate::from_ymd_opt(1979, 1, 1).expect("Date 1979-1-1 created");
+        let diff_days = date.signed_duration_since(today).num_days();
+        match diff_days {
+            0..=MAX_HOURLY_FORECAST_DAYS => self.hourly_forecast(address, date, time).await,
+            _ if date < today && date > min_date => self.hourly_history(address, date, time).await,
+            _ => Err(OpenWeatherError::UnsupportedDate(date)),
+        }
+    }
+    fn default_request_builder(
+        &self,
+        endpoint: &Url,
+        address: &str,
+    ) -> reqwest_middleware::RequestBuilder {
+        let request = self.client.get(endpoint.clone());
+        let request = match address.parse::<Coordinates>() {
+            Ok(coordinates) => request.query(&[("lat", coordinates.lat), ("lon", coordinates.lon)]),
+            Err(_) => request.query(&[("q", address)]),
+        };
+        request.query(&[("appid", self.api_key.as_str())])
+    }
+    /// Shifts the time of the current weather by the timezone of the location.
+    async fn local_date(&self, address: &str) -> Result<NaiveDate, OpenWeatherError> {
+        let endpoint = self.endpoints.weather.clone();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .send()
+            .await?;
+        let resp = parse::<TodayResponse>(response).await?;
+        chrono::DateTime::from_timestamp(resp.dt + resp.timezone, 0)
+            .map(|time| time.date_naive())
+            .ok_or_else(|| json_error(&endpoint, "dt"))
+    }
+    /// Returns the coordinates of the address, taken from the current weather, for the APIs which
+    /// accept only the coordinates.
+    async fn coordinates(&self, address: &str) -> Result<String, OpenWeatherError> {
+        if let Ok(coordinates) = address.parse::<Coordinates>() {
+            return Ok(coordinates.to_string());
+        }
+        let endpoint = self.endpoints.weather.clone();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .send()
+            .await?;
+        let resp = parse::<TodayResponse>(response).await?;
+        Ok(format!("{},{}", resp.coord.lat, resp.coord.lon))
+    }
+    /// Requests the alerts from the One Call API, which requires a separate subscription.
+    async fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, OpenWeatherError> {
+        let coordinates = self.coordinates(address).await?;
+        let endpoint = self.endpoints.one_call.clone();
+        let response = self
+            .default_request_builder(&endpoint, &coordinates)
+            .query(&[("exclude", "current,minutely,hourly,daily")])
+            .send()
+            .await?;
+        let resp = parse::<OneCallResponse>(response).await?;
+        let offset =
+            offset(resp.timezone_offset).ok_or_else(|| json_error(&endpoint, "timezone_offset"))?;
+        let now = chrono::offset::Utc::now().timestamp();
+        Ok(resp
+            .alerts
+            .into_iter()
+            .filter(|alert| now < alert.end)
+            .map(|alert| Alert {
+                start: local_time(alert.start, offset),
+                end: local_time(alert.end, offset),
+                sender: alert.sender_name.filter(|sender| !sender.is_empty()),
+                ..Alert::new(alert.event, alert.description)
+            })
+            .collect())
+    }
+    /// Requests the current air pollution, the indexes are computed from the concentrations
+    /// since OpenWeather has its own index.
+    async fn get_air_quality(&self, address: &str) -> Result<AirQuality, OpenWeatherError> {
+        let coordinates = self.coordinates(address).await?;
+        let endpoint = self.endpoints.air_pollution.clone();
+        let response = self
+            .default_request_builder(&endpoint, &coordinates)
+            .send()
+            .await?;
+        let mut resp = parse::<AirPollutionResponse>(response).await?;
+        let components = resp
+            .list
+            .pop()
+            .ok_or_else(|| json_error(&endpoint, "./list"))?
+            .components;
+        Ok(AirQuality {
+            co: Some(components.co),
+            no2: Some(components.no2),
+            o3: Some(components.o3),
+            so2: Some(components.so2),
+            pm2_5: Some(components.pm2_5),
+            pm10: Some(components.pm10),
+            ..Default::default()
+        }
+        .with_computed_indexes())
+    }
+    /// Takes the sunrise and sunset from the current weather for today and from the daily forecast
+    /// for the next days. OpenWeather doesn't return the moon events with these endpoints.
+    async fn get_astronomy(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Astronomy, OpenWeatherError> {
+        let today = self.local_date(address).await?;
+        let diff_days = date.signed_duration_since(today).num_days();
+        match diff_days {
+            0 => {
+                let endpoint = self.endpoints.weather.clone();
+                let response = self
+                    .default_request_builder(&endpoint, address)
+                    .send()
+                    .await?;
+                let resp = parse::<TodayResponse>(response).await?;
+                let offset =
+                    offset(resp.timezone).ok_or_else(|| json_error(&endpoint, "timezone"))?;
+                let sys = resp.sys.ok_or_else(|| json_error(&endpoint, "sys"))?;
+                Ok(Astronomy {
+                    sunrise: local_time(sys.sunrise, offset),
+                    sunset: local_time(sys.sunset, offset),
+                    ..Default::default()
+                })
+            }
+            1..=MAX_FORECAS_DAYS => {
+                let endpoint = self.endpoints.forecast.clone();
+                let cnt = (diff_days + 1).to_string();
+                let response = self
+                    .default_request_builder(&endpoint, address)
+                    .query(&[("cnt", cnt.as_str())])
+                    .send()
+                    .await?;
+                let resp = parse::<ForecastResponse>(response).await?;
+                let offset = offset(resp.city.timezone)
+                    .ok_or_else(|| json_error(&endpoint, "./city/timezone"))?;
+                let forecast = resp
+                    .list
+                    .into_iter()
+                    .find(|forecast| {
+                        local_time(forecast.dt, offset)
+                            .is_some_and(|time| time.date_naive() == date)
+                    })
+                    .ok_or_else(|| json_error(&endpoint, "./list"))?;
+                Ok(Astronomy {
+                    sunrise: local_time(forecast.sunrise, offset),
+                    sunset: local_time(forecast.sunset, offset),
+                    ..Default::default()
+                })
+            }
+            _ => Err(OpenWeatherError::UnsupportedDate(date)),
+        }
+    }
+    /// Takes the timezone of the location from the current weather.
+    async fn timezone(&self, address: &str) -> Result<FixedOffset, OpenWeatherError> {
+        let endpoint = self.endpoints.weather.clone();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .send()
+            .await?;
+        let resp = parse::<TodayResponse>(response).await?;
+        offset(resp.timezone).ok_or_else(|| json_error(&endpoint, "timezone"))
     }
     async fn today(&self, address: &str) -> Result<Weather, OpenWeatherError> {
         let endpoint = self.endpoints.weather.clone();
@@ -70,13 +269,15 @@ impl OpenWeatherMap {
             .weather
             .pop()
             .ok_or_else(|| json_error(&endpoint, "weather"))?;
-        Ok(Weather::current(
-            Temperature::from_k(resp.main.temp)?,
-            weather.main,
-        ))
+        let condition_kind = weather.condition_kind();
+        let weather = Weather::current(Temperature::from_k(resp.main.temp)?, weather.main)
+            .with_condition_kind(condition_kind);
+        Ok(resp.main.apply(weather, Some(&resp.wind)))
     }
     async fn history(&self, address: &str, date: NaiveDate) -> Result<Weather, OpenWeatherError> {
-        let ts = NaiveDateTime::new(date, chrono::NaiveTime::default()).timestamp();
+        let ts = NaiveDateTime::new(date, chrono::NaiveTime::default())
+            .and_utc()
+            .timestamp();
         let endpoint = self.endpoints.history.clone();
         let response = self
             .default_request_builder(&endpoint, address)
@@ -94,10 +295,89 @@ impl OpenWeatherMap {
             .pop()
             .ok_or_else(|| json_error(&endpoint, "./list/[0]/weather"))?;
         let temp = Temperature::from_k(histroy.main.temp)?;
-        Ok(Weather::history(temp, weather.main))
+        let condition_kind = weather.condition_kind();
+        let weather = Weather::history(temp, weather.main).with_condition_kind(condition_kind);
+        Ok(histroy.main.apply(weather, Some(&histroy.wind)))
+    }
+    /// Picks the 3-hour step closest to the time, in the timezone of the location when the time
+    /// has no offset.
+    async fn hourly_forecast(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, OpenWeatherError> {
+        let endpoint = self.endpoints.hourly_forecast.clone();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .send()
+            .await?;
+
+        let resp = parse::<HourlyForecastResponse>(response).await?;
+        let location_offset =
+            offset(resp.city.timezone).ok_or_else(|| json_error(&endpoint, "./city/timezone"))?;
+        let target = time.timestamp(date, time.offset.unwrap_or(location_offset));
+        let mut forecast = resp
+            .list
+            .into_iter()
+            .min_by_key(|forecast| (forecast.dt - target).abs())
+            .ok_or_else(|| json_error(&endpoint, "./list"))?;
+        let slot_time = local_time(forecast.dt, location_offset)
+            .ok_or_else(|| json_error(&endpoint, "./list/[0]/dt"))?;
+        let temp = Temperature::from_k(forecast.main.temp)?;
+        let weather = forecast
+            .weather
+            .pop()
+            .ok_or_else(|| json_error(&endpoint, "./list/[0]/weather"))?;
+        let condition_kind = weather.condition_kind();
+
+        let weather = Weather::forecast(temp, weather.main)
+            .with_condition_kind(condition_kind)
+            .with_time(slot_time);
+        Ok(forecast.main.apply(weather, forecast.wind.as_ref()))
+    }
+    /// Requests the hour that starts within half an hour before the time, which is the closest
+    /// one. The timezone of the location is requested first when the time has no offset.
+    async fn hourly_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, OpenWeatherError> {
+        let offset = match time.offset {
+            Some(offset) => offset,
+            None => self.timezone(address).await?,
+        };
+        let start = time.timestamp(date, offset) - 30 * 60;
+        let endpoint = self.endpoints.history.clone();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .query(&[("start", start.to_string().as_str()), ("cnt", "1")])
+            .send()
+            .await?;
+
+        let mut resp = parse::<HistoryResponse>(response).await?;
+        let mut histroy = resp
+            .list
+            .pop()
+            .ok_or_else(|| json_error(&endpoint, "./list"))?;
+        let slot_time =
+            local_time(histroy.dt, offset).ok_or_else(|| json_error(&endpoint, "./list/[0]/dt"))?;
+        let weather = histroy
+            .weather
+            .pop()
+            .ok_or_else(|| json_error(&endpoint, "./list/[0]/weather"))?;
+        let temp = Temperature::from_k(histroy.main.temp)?;
+        let condition_kind = weather.condition_kind();
+        let weather = Weather::history(temp, weather.main)
+            .with_condition_kind(condition_kind)
+            .with_time(slot_time);
+        Ok(histroy.main.apply(weather, Some(&histroy.wind)))
     }
     async fn forecast(&self, address: &str, date: NaiveDate) -> Result<Weather, OpenWeatherError> {
-        let ts = NaiveDateTime::new(date, chrono::NaiveTime::default()).timestamp();
+        let ts = NaiveDateTime::new(date, chrono::NaiveTime::default())
+            .and_utc()
+            .timestamp();
         let endpoint = self.endpoints.forecast.clone();
         let response = self
             .default_request_builder(&endpoint, address)
@@ -116,15 +396,32 @@ impl OpenWeatherMap {
             .weather
             .pop()
             .ok_or_else(|| json_error(&endpoint, "./list/[0]/weather"))?;
+        let condition_kind = weather.condition_kind();
 
-        Ok(Weather::forecast(temp, weather.main))
+        let weather = Weather::forecast(temp, weather.main)
+            .with_condition_kind(condition_kind)
+            .with_humidity(forecast.humidity as f32)
+            .with_pressure(forecast.pressure as f32)
+            .with_wind_speed(forecast.speed);
+        Ok(match Temperature::from_k(forecast.feels_like.day) {
+            Ok(feels_like) => weather.with_feels_like(feels_like),
+            Err(_) => weather,
+        })
     }
 }
 
 async fn parse<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, OpenWeatherError> {
-    let resp_or_error = crate::utils::parse::<T, ErrorResponse>(res).await?;
+    let resp_or_error = crate::utils::parse::<T, ErrorResponse>(res)
+        .await
+        .map_err(reqwest_middleware::Error::Reqwest)?;
     resp_or_error.map_err(|e| OpenWeatherError::Api(e.message, e.cod as u16))
 }
+fn offset(timezone: i64) -> Option<FixedOffset> {
+    FixedOffset::east_opt(i32::try_from(timezone).ok()?)
+}
+fn local_time(dt: i64, offset: FixedOffset) -> Option<DateTime<FixedOffset>> {
+    DateTime::from_timestamp(dt, 0).map(|time| time.with_timezone(&offset))
+}
 fn json_error(endpoint: &Url, path: impl Into<String>) -> OpenWeatherError {
     OpenWeatherError::Json(endpoint.to_string(), path.into())
 }
@@ -134,6 +431,7 @@ mod tests {
 
     static SERVER_POOL: ServerPool = ServerPool::new(20);
     use super::*;
+    use serde_json::json;
     use std::collections::HashMap;
     use test_case::*;
 
@@ -196,9 +494,306 @@ mod tests {
         assert_eq!(weather.temp, expected_temp);
         assert_eq!(weather.condition, expected_condition);
     }
+    #[tokio::test]
+    async fn get_weather_by_coordinates() {
+        let (cfg, server) = setup("baseUrl");
+        let endpoint = endpoint_from_config(&cfg, "weatherPath");
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", endpoint),
+                request::query(url_decoded(contains(("lat", "50.45")))),
+                request::query(url_decoded(contains(("lon", "30.52")))),
+                request::query(url_decoded(not(contains(key("q"))))),
+            ])
+            .respond_with(json_encoded(current_298k_rain())),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let weather = client
+            .get_weather("50.45,30.52", None)
+            .await
+            .expect("weather result should be ok");
+        assert_eq!(weather.condition, "Rain");
+    }
+    #[tokio::test]
+    async fn get_weather_current_with_details() {
+        let (cfg, server) = setup("baseUrl");
+        let endpoint = endpoint_from_config(&cfg, "weatherPath");
+        server.expect(
+            Expectation::matching(request::method_path("GET", endpoint))
+                .respond_with(json_encoded(current_298k_rain())),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let weather = client
+            .get_weather("Zocca", None)
+            .await
+            .expect("weather result should be ok");
+        assert_eq!(weather.humidity, Some(64.0));
+        assert_eq!(weather.pressure, Some(1015.0));
+        assert_eq!(weather.wind_speed, Some(0.62));
+        assert_eq!(weather.feels_like, Some(temp_k(298.74)));
+    }
+    #[tokio::test]
+    async fn local_date_shifted_by_timezone() {
+        let (cfg, server) = setup("baseUrl");
+        let endpoint = endpoint_from_config(&cfg, "weatherPath");
+        let mut body = current_298k_rain();
+        // 2022-08-30 22:00 UTC is already the next day in the UTC+2 timezone
+        body["dt"] = 1661896800.into();
+        body["timezone"] = 7200.into();
+        server.expect(
+            Expectation::matching(request::method_path("GET", endpoint))
+                .respond_with(json_encoded(body)),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let date = WeatherProvider::local_date(&client, "Kyiv")
+            .await
+            .expect("local date should be ok");
+        assert_eq!(Some(date), self::date(2022, 8, 31));
+    }
+    #[tokio::test]
+    async fn get_weather_at_closest_3_hour_step() {
+        let (cfg, server) = setup("baseUrl");
+        let endpoint = endpoint_from_config(&cfg, "hourlyForecastPath");
+        let date = date_plus_days(1).unwrap();
+        let time: TimeOfDay = "15:00".parse().expect("valid time");
+        let offset = FixedOffset::east_opt(7200).unwrap();
+        let target = time.timestamp(date, offset);
+        let step = |dt: i64, temp: f32| {
+            let mut main = current_298k_rain()["main"].clone();
+            main["temp"] = temp.into();
+            json!({
+                "dt": dt,
+                "main": main,
+                "weather": [{ "id": 800, "main": "Clear", "description": "clear sky", "icon": "01d" }]
+            })
+        };
+        let body = json!({
+            "list": [
+                step(target - 3 * 3600, 290.0),
+                step(target + 3600, 295.0),
+                step(target + 4 * 3600, 300.0)
+            ],
+            "city": { "name": "Kyiv", "timezone": 7200 }
+        });
+        server.expect(
+            Expectation::matching(request::method_path("GET", endpoint))
+                .respond_with(json_encoded(body)),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let weather = client
+            .get_weather_at("Kyiv", date, time)
+            .await
+            .expect("weather at time should be ok");
+
+        assert_eq!(weather.kind, WeatherKind::Forecast);
+        assert_eq!(weather.temp, temp_k(295.0));
+        let expected_time = date.and_hms_opt(16, 0, 0).unwrap();
+        assert_eq!(weather.time.map(|t| t.naive_local()), Some(expected_time));
+        assert_eq!(weather.time.map(|t| *t.offset()), Some(offset));
+    }
+    #[tokio::test]
+    async fn get_weather_at_past_hour_in_location_timezone() {
+        let server = SERVER_POOL.get_server();
+        let cfg = Config::builder()
+            .set_override(format!("{PROVIDER_NAME}.apiKey"), "some-api-key")
+            .and_then(|b| b.set_override(format!("{PROVIDER_NAME}.baseUrl"), server.url_str("")))
+            .and_then(|b| {
+                b.set_override(
+                    format!("{PROVIDER_NAME}.historyBaseUrl"),
+                    server.url_str(""),
+                )
+            })
+            .and_then(|b| b.build())
+            .expect("config built");
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                endpoint_from_config(&cfg, "weatherPath"),
+            ))
+            .respond_with(json_encoded(current_298k_rain())),
+        );
+        // 15:00 in the UTC+2 timezone of the location is 13:00 UTC, minus half an hour
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", endpoint_from_config(&cfg, "historyPath")),
+                request::query(url_decoded(contains(("start", "1325421000")))),
+                request::query(url_decoded(contains(("cnt", "1")))),
+            ])
+            .respond_with(json_encoded(history_310k_clear())),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let weather = client
+            .get_weather_at(
+                "Kyiv",
+                date(2012, 1, 1).unwrap(),
+                "15:00".parse().expect("valid time"),
+            )
+            .await
+            .expect("weather at time should be ok");
+
+        assert_eq!(weather.kind, WeatherKind::History);
+        assert_eq!(weather.temp, temp_k(320.75));
+        let expected_time = DateTime::parse_from_rfc3339("2020-01-07T10:00:00+02:00").unwrap();
+        assert_eq!(weather.time, Some(expected_time));
+    }
+    #[tokio::test]
+    async fn get_air_quality_by_coordinates() {
+        let (cfg, server) = setup("baseUrl");
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", endpoint_from_config(&cfg, "airPollutionPath")),
+                request::query(url_decoded(contains(("lat", "50.45")))),
+                request::query(url_decoded(contains(("lon", "30.52")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "coord": { "lon": 30.52, "lat": 50.45 },
+                "list": [{
+                    "dt": 1606147200,
+                    "main": { "aqi": 2 },
+                    "components": {
+                        "co": 203.609,
+                        "no": 0.0,
+                        "no2": 0.396,
+                        "o3": 75.102,
+                        "so2": 0.648,
+                        "pm2_5": 23.253,
+                        "pm10": 92.214,
+                        "nh3": 0.117
+                    }
+                }]
+            }))),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let air = WeatherProvider::get_air_quality(&client, "50.45,30.52")
+            .await
+            .expect("air quality should be ok");
+
+        assert_eq!(air.pm2_5, Some(23.253));
+        assert_eq!(air.co, Some(203.609));
+        // PM10 of 92.214 μg/m³ is in the US EPA band 2 and the UK DEFRA band 9
+        assert_eq!(air.us_epa_index, Some(2));
+        assert_eq!(air.gb_defra_index, Some(9));
+    }
+    #[tokio::test]
+    async fn get_alerts_by_coordinates_of_address() {
+        let (cfg, server) = setup("baseUrl");
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", endpoint_from_config(&cfg, "weatherPath")),
+                request::query(url_decoded(contains(("q", "Zocca")))),
+            ])
+            .respond_with(json_encoded(current_298k_rain())),
+        );
+        let alert = |event: &str, end: i64| {
+            json!({
+                "sender_name": "NWS Tulsa",
+                "event": event,
+                "start": 1684952747,
+                "end": end,
+                "description": "...HEAT ADVISORY REMAINS IN EFFECT...",
+                "tags": ["Extreme temperature value"]
+            })
+        };
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", endpoint_from_config(&cfg, "oneCallPath")),
+                request::query(url_decoded(contains(("lat", "44.34")))),
+                request::query(url_decoded(contains(("lon", "10.99")))),
+                request::query(url_decoded(contains(key("exclude")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "timezone_offset": 7200,
+                "alerts": [alert("Heat Advisory", 32503680000), alert("Wind Advisory", 1684988747)]
+            }))),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let alerts = WeatherProvider::get_alerts(&client, "Zocca")
+            .await
+            .expect("alerts should be ok");
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].event, "Heat Advisory");
+        assert_eq!(alerts[0].sender.as_deref(), Some("NWS Tulsa"));
+        let start = DateTime::parse_from_rfc3339("2023-05-24T20:25:47+02:00").unwrap();
+        assert_eq!(alerts[0].start, Some(start));
+        assert_eq!(alerts[0].start.map(|t| *t.offset()), Some(*start.offset()));
+    }
     use config::Config;
-    use httptest::{matchers::*, responders::*, Expectation, ServerHandle, ServerPool};
+    use httptest::{all_of, matchers::*, responders::*, Expectation, ServerHandle, ServerPool};
     use rstest::rstest;
+    #[tokio::test]
+    async fn get_astronomy_from_current_weather_in_local_time() {
+        let (cfg, server) = setup("baseUrl");
+        let endpoint = endpoint_from_config(&cfg, "weatherPath");
+        server.expect(
+            Expectation::matching(request::method_path("GET", endpoint))
+                .times(2)
+                .respond_with(json_encoded(current_298k_rain())),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let astronomy =
+            WeatherProvider::get_astronomy(&client, "Zocca", date(2022, 8, 30).unwrap())
+                .await
+                .expect("astronomy should be ok");
+
+        assert_eq!(
+            astronomy.sunrise,
+            DateTime::parse_from_rfc3339("2022-08-30T06:36:27+02:00").ok()
+        );
+        assert_eq!(
+            astronomy.sunset,
+            DateTime::parse_from_rfc3339("2022-08-30T19:57:28+02:00").ok()
+        );
+        assert_eq!(astronomy.moon_phase, None);
+    }
+    #[tokio::test]
+    async fn get_astronomy_from_daily_forecast() {
+        let (cfg, server) = setup("baseUrl");
+        let weather_endpoint = endpoint_from_config(&cfg, "weatherPath");
+        let forecast_endpoint = endpoint_from_config(&cfg, "forecastPath");
+        let mut body = forecast_295k_rain();
+        let mut day = body["list"][0].clone();
+        // 2022-08-30 and 2022-09-01 at noon UTC, the current weather is on 2022-08-30
+        body["list"][0]["dt"] = 1661860800.into();
+        day["dt"] = 1662033600.into();
+        day["sunrise"] = 1662009000.into();
+        day["sunset"] = 1662056700.into();
+        body["list"] = json!([body["list"][0].clone(), day]);
+        server.expect(
+            Expectation::matching(request::method_path("GET", weather_endpoint))
+                .respond_with(json_encoded(current_298k_rain())),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", forecast_endpoint),
+                request::query(url_decoded(contains(("cnt", "3")))),
+            ])
+            .respond_with(json_encoded(body)),
+        );
+
+        let client = OpenWeatherMap::new(&cfg).expect("WeatherApi created");
+        let astronomy =
+            WeatherProvider::get_astronomy(&client, "London", date(2022, 9, 1).unwrap())
+                .await
+                .expect("astronomy should be ok");
+
+        assert_eq!(
+            astronomy.sunrise,
+            DateTime::parse_from_rfc3339("2022-09-01T06:10:00+01:00").ok()
+        );
+        assert_eq!(
+            astronomy.sunset,
+            DateTime::parse_from_rfc3339("2022-09-01T19:25:00+01:00").ok()
+        );
+    }
     fn setup<'a>(base_url_conf_name: &str) -> (Config, ServerHandle<'a>) {
         let api_key = "some-api-key";
         let server = SERVER_POOL.get_server();
@@ -230,6 +825,26 @@ mod tests {
         assert_error!(err, OpenWeatherError::UnsupportedDate(_))
     }
 
+    #[rstest]
+    #[case(800, ConditionKind::Clear)]
+    #[case(801, ConditionKind::PartlyCloudy)]
+    #[case(804, ConditionKind::Cloudy)]
+    #[case(741, ConditionKind::Fog)]
+    #[case(301, ConditionKind::Drizzle)]
+    #[case(500, ConditionKind::Rain)]
+    #[case(502, ConditionKind::HeavyRain)]
+    #[case(511, ConditionKind::Sleet)]
+    #[case(601, ConditionKind::Snow)]
+    #[case(211, ConditionKind::Thunderstorm)]
+    #[case(42, ConditionKind::Unknown)]
+    fn condition_kind_from_id(#[case] id: i64, #[case] expected: ConditionKind) {
+        let weather = TheWeather {
+            id,
+            main: String::new(),
+        };
+        assert_eq!(weather.condition_kind(), expected);
+    }
+
     fn endpoint_from_config(config: &Config, name: &str) -> String {
         let api_conf: crate::api_config::ApiConfig = config
             .get(PROVIDER_NAME)
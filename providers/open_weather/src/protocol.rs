@@ -9,17 +9,11 @@ pub struct ErrorResponse {
 pub struct TodayResponse {
     pub coord: Coord,
     pub weather: Vec<TheWeather>,
-    pub base: String,
     pub main: Main,
-    pub visibility: i64,
     pub wind: Wind,
-    pub clouds: Clouds,
     pub dt: i64,
     pub sys: Option<Sys>,
     pub timezone: i64,
-    pub id: i64,
-    pub name: String,
-    pub cod: i64,
 }
 
 #[derive(Deserialize)]
@@ -34,45 +28,68 @@ pub struct Coord {
 pub struct TheWeather {
     pub id: i64,
     pub main: String,
-    pub description: String,
-    pub icon: String,
+}
+
+impl TheWeather {
+    /// Maps the OpenWeather condition id to the normalized condition.
+    /// See https://openweathermap.org/weather-conditions
+    pub fn condition_kind(&self) -> ConditionKind {
+        match self.id {
+            200..=232 | 771 | 781 => ConditionKind::Thunderstorm,
+            300..=321 => ConditionKind::Drizzle,
+            502..=504 | 522 | 531 => ConditionKind::HeavyRain,
+            511 | 611..=616 => ConditionKind::Sleet,
+            500..=521 => ConditionKind::Rain,
+            600..=622 => ConditionKind::Snow,
+            701..=762 => ConditionKind::Fog,
+            800 => ConditionKind::Clear,
+            801 | 802 => ConditionKind::PartlyCloudy,
+            803 | 804 => ConditionKind::Cloudy,
+            _ => ConditionKind::Unknown,
+        }
+    }
 }
 
 use serde::Deserialize;
+use weather_abstractions::{ConditionKind, Temperature, Weather};
 #[derive(Deserialize)]
 pub struct Main {
     pub temp: f32,
     pub feels_like: f32,
-    pub temp_min: f32,
-    pub temp_max: f32,
     pub pressure: i64,
     pub humidity: i64,
 }
 
-#[derive(Deserialize)]
-pub struct Wind {
-    pub speed: f32,
-    pub deg: i64,
+impl Main {
+    /// Adds the humidity, pressure, apparent temperature and the wind speed to the weather.
+    pub fn apply(&self, weather: Weather, wind: Option<&Wind>) -> Weather {
+        let weather = weather
+            .with_humidity(self.humidity as f32)
+            .with_pressure(self.pressure as f32);
+        let weather = match Temperature::from_k(self.feels_like) {
+            Ok(feels_like) => weather.with_feels_like(feels_like),
+            Err(_) => weather,
+        };
+        match wind {
+            Some(wind) => weather.with_wind_speed(wind.speed),
+            None => weather,
+        }
+    }
 }
 
 #[derive(Deserialize)]
-pub struct Clouds {
-    pub all: i64,
+pub struct Wind {
+    pub speed: f32,
 }
 
 #[derive(Deserialize)]
 pub struct Sys {
-    #[serde(rename = "type")]
-    pub type_field: Option<i64>,
-    pub id: Option<i64>,
-    pub country: String,
     pub sunrise: i64,
     pub sunset: i64,
 }
 
 #[derive(Deserialize)]
 pub struct HistoryResponse {
-    pub cnt: i8,
     pub list: Vec<HistoryTs>,
 }
 
@@ -80,7 +97,6 @@ pub struct HistoryResponse {
 pub struct HistoryTs {
     pub main: Main,
     pub wind: Wind,
-    pub clouds: Clouds,
     pub weather: Vec<TheWeather>,
     pub dt: i64,
 }
@@ -95,32 +111,76 @@ pub struct List {
     pub humidity: i64,
     pub weather: Vec<TheWeather>,
     pub speed: f32,
-    pub deg: i64,
-    pub gust: f32,
-    pub clouds: i64,
-    pub pop: f32,
-    pub rain: f32,
 }
 #[derive(Deserialize)]
 pub struct Temp {
     pub day: f32,
-    pub min: f32,
-    pub max: f32,
-    pub night: f32,
-    pub eve: f32,
-    pub morn: f32,
 }
 #[derive(Deserialize)]
 
 pub struct FeelsLike {
     pub day: f32,
-    pub night: f32,
-    pub eve: f32,
-    pub morn: f32,
 }
 
 #[derive(Deserialize)]
 pub struct ForecastResponse {
-    pub cnt: i8,
     pub list: Vec<List>,
+    pub city: City,
+}
+
+#[derive(Deserialize)]
+pub struct AirPollutionResponse {
+    pub list: Vec<AirPollution>,
+}
+
+#[derive(Deserialize)]
+pub struct AirPollution {
+    pub components: Components,
+}
+
+/// The concentrations in μg/m³.
+#[derive(Deserialize)]
+pub struct Components {
+    pub co: f32,
+    pub no2: f32,
+    pub o3: f32,
+    pub so2: f32,
+    pub pm2_5: f32,
+    pub pm10: f32,
+}
+
+/// The One Call response requested with only the alerts, the other sections are excluded.
+#[derive(Deserialize)]
+pub struct OneCallResponse {
+    pub timezone_offset: i64,
+    #[serde(default)]
+    pub alerts: Vec<OneCallAlert>,
+}
+
+#[derive(Deserialize)]
+pub struct OneCallAlert {
+    pub sender_name: Option<String>,
+    pub event: String,
+    pub start: i64,
+    pub end: i64,
+    pub description: String,
+}
+
+#[derive(Deserialize)]
+pub struct HourlyForecastResponse {
+    pub list: Vec<HourlyForecast>,
+    pub city: City,
+}
+
+#[derive(Deserialize)]
+pub struct HourlyForecast {
+    pub dt: i64,
+    pub main: Main,
+    pub weather: Vec<TheWeather>,
+    pub wind: Option<Wind>,
+}
+
+#[derive(Deserialize)]
+pub struct City {
+    pub timezone: i64,
 }
@@ -0,0 +1,70 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use config::{Config, ConfigError};
+
+/// The prefix of the plugin executables found on the `PATH`, the rest of the file name is the
+/// name of the provider.
+pub const PLUGIN_PREFIX: &str = "weather-provider-";
+
+/// The section of the settings which lists the plugins by the name of the provider, with the path
+/// of the executable.
+pub const PLUGINS_SECTION: &str = "plugins";
+
+/// Returns the plugins found on the `PATH` and listed in the settings, by the name of the
+/// provider. A plugin listed in the settings takes precedence over the one found on the `PATH`.
+pub fn plugins(cfg: &Config) -> BTreeMap<String, PathBuf> {
+    let mut plugins = env::var_os("PATH")
+        .map(|path| discover(&path))
+        .unwrap_or_default();
+    match cfg.get::<HashMap<String, PathBuf>>(PLUGINS_SECTION) {
+        Ok(listed) => plugins.extend(listed),
+        Err(ConfigError::NotFound(_)) => {}
+        Err(e) => tracing::warn!("The plugins in the settings are ignored: {e}"),
+    }
+    plugins
+}
+
+/// Finds the executables named `weather-provider-<name>` in the directories of the search path,
+/// the first one wins when the name is found in several directories like with the shell.
+pub fn discover(search_path: &OsStr) -> BTreeMap<String, PathBuf> {
+    let mut plugins = BTreeMap::new();
+    for dir in env::split_paths(search_path) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if let Some(name) = plugin_name(&path).filter(|_| is_executable(&path)) {
+                plugins.entry(name).or_insert(path);
+            }
+        }
+    }
+    plugins
+}
+
+fn plugin_name(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let name = file_name.strip_prefix(PLUGIN_PREFIX)?;
+    let name = name
+        .strip_suffix(env::consts::EXE_SUFFIX)
+        .filter(|_| !env::consts::EXE_SUFFIX.is_empty())
+        .unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file())
+}
@@ -0,0 +1,24 @@
+use std::{io, path::PathBuf, process::ExitStatus};
+
+use thiserror::Error;
+use weather_abstractions::TemperatureError;
+
+#[derive(Debug, Error)]
+pub enum ProcessError {
+    #[error("Unexpected temperature")]
+    Temperature(#[from] TemperatureError),
+    #[error("Unable to run the plugin {0}")]
+    Spawn(PathBuf, #[source] io::Error),
+    #[error("Unable to exchange with the plugin {0}")]
+    Io(PathBuf, #[source] io::Error),
+    #[error("The plugin {0} didn't answer in {1} seconds")]
+    Timeout(PathBuf, u64),
+    #[error("The plugin {0} exited with {1}: {2}")]
+    Exit(PathBuf, ExitStatus, String),
+    #[error("The plugin {0} returns unexpected JSON")]
+    Json(PathBuf, #[source] serde_json::Error),
+    #[error("Plugin error: {0}")]
+    Plugin(String),
+    #[error("Configuration of the plugin {0} can't be read")]
+    Configuration(String, #[source] config::ConfigError),
+}
@@ -0,0 +1,390 @@
+//! Out-of-process weather providers, the plugins.
+//!
+//! A plugin is an executable, written in any language, which is run for every request. It reads
+//! a single JSON request from its standard input:
+//!
+//! ```json
+//! {"version": 1, "method": "get_weather", "params": {"address": "London", "date": "2024-05-01"}, "settings": {"apiKey": "..."}}
+//! ```
+//!
+//! and writes a single JSON response to its standard output, either `{"result": ...}` or
+//! `{"error": {"message": "...", "code": "unsupported"}}`, then exits with the status 0. The
+//! `settings` are the values of the provider section of the settings, like for the built-in
+//! providers. The methods:
+//!
+//! * `get_weather` with the `address` and an optional `date`, returns a weather
+//! * `get_weather_at` with the `address`, the `date` and the `time` like `15:00` or
+//!   `15:00+02:00`, returns a weather with the `time` of the hourly slot
+//! * `local_date` with the `address`, returns the current date at the location like `2024-05-01`
+//! * `get_alerts` with the `address`, returns a list of alerts
+//! * `verify` without parameters, checks the settings and returns anything
+//!
+//! A weather is an object with the `kind` (`current`, `forecast` or `history`), the `temp_c`, the
+//! `condition` and optionally the `condition_kind`, `time`, `humidity`, `wind_speed` in meters per
+//! second, `pressure` in hectopascals, `dew_point_c`, `feels_like_c` and `alerts`. An alert has
+//! the `event`, the `description` and optionally the `severity`, `area`, `start`, `end` and
+//! `sender`, the times in RFC 3339.
+//!
+//! Only `get_weather` is required, the other methods can answer with the `unsupported` error code
+//! and the provider falls back to the default behavior of the `WeatherProvider` trait.
+mod discovery;
+mod error;
+mod protocol;
+
+use std::{error::Error, path::PathBuf, process::Stdio, time::Duration};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use config::{Config, ConfigError};
+pub use discovery::{discover, plugins, PLUGINS_SECTION, PLUGIN_PREFIX};
+pub use error::ProcessError;
+use protocol::*;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use tokio::{io::AsyncWriteExt, process::Command};
+use tracing::Instrument;
+use weather_abstractions::*;
+
+/// The time a plugin has to answer a request before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A weather provider implemented by a plugin executable, see the crate documentation for the
+/// protocol.
+pub struct ProcessProvider {
+    program: PathBuf,
+    settings: serde_json::Value,
+}
+
+impl ProcessProvider {
+    /// Creates the provider of the plugin, with the settings of the `name` section.
+    pub fn new(
+        name: &str,
+        program: impl Into<PathBuf>,
+        cfg: &Config,
+    ) -> Result<Self, ProcessError> {
+        let settings = match cfg.get::<serde_json::Value>(name) {
+            Ok(settings) => settings,
+            Err(ConfigError::NotFound(_)) => serde_json::Value::Object(Default::default()),
+            Err(e) => return Err(ProcessError::Configuration(name.to_string(), e)),
+        };
+        Ok(Self {
+            program: program.into(),
+            settings,
+        })
+    }
+
+    async fn get_weather(
+        &self,
+        address: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<Weather, ProcessError> {
+        let params = Params {
+            date,
+            ..Params::address(address)
+        };
+        let record: WeatherRecord = self.call("get_weather", params).await??;
+        Ok(record.try_into()?)
+    }
+
+    /// Falls back to the weather of the whole day when the plugin has no hourly data.
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, ProcessError> {
+        let params = Params {
+            date: Some(date),
+            time: Some(time_param(time)),
+            ..Params::address(address)
+        };
+        match self.call::<WeatherRecord>("get_weather_at", params).await? {
+            Ok(record) => Ok(record.try_into()?),
+            Err(e) if e.is_unsupported() => self.get_weather(address, Some(date)).await,
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn local_date(&self, address: &str) -> Result<NaiveDate, ProcessError> {
+        match self.call("local_date", Params::address(address)).await? {
+            Ok(date) => Ok(date),
+            Err(e) if e.is_unsupported() => Ok(estimated_local_date(address)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Runs the plugin with the request and reads its response, the outer error is the failure
+    /// to run the plugin and the inner one the error answered by the plugin.
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: Params<'_>,
+    ) -> Result<Result<T, PluginError>, ProcessError> {
+        let span = tracing::info_span!(
+            "plugin request",
+            plugin = %self.program.display(),
+            method = method,
+        );
+        let request = Request {
+            version: PROTOCOL_VERSION,
+            method,
+            params,
+            settings: &self.settings,
+        };
+        let mut request = serde_json::to_vec(&request).expect("The request is serializable");
+        request.push(b'\n');
+        let output = self.run(&request).instrument(span).await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(ProcessError::Exit(
+                self.program.clone(),
+                output.status,
+                stderr,
+            ));
+        }
+        let response = serde_json::from_slice::<Response<T>>(&output.stdout)
+            .map_err(|e| ProcessError::Json(self.program.clone(), e))?;
+        Ok(match response {
+            Response::Result(result) => Ok(result),
+            Response::Error(error) => Err(error),
+        })
+    }
+
+    async fn run(&self, request: &[u8]) -> Result<std::process::Output, ProcessError> {
+        let io_error = |e| ProcessError::Io(self.program.clone(), e);
+        let mut child = Command::new(&self.program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| ProcessError::Spawn(self.program.clone(), e))?;
+        let mut stdin = child.stdin.take().expect("The standard input is piped");
+        // A plugin may answer without reading the request, like with an error
+        match stdin.write_all(request).await {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(io_error(e)),
+            _ => drop(stdin),
+        }
+        tokio::time::timeout(TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| ProcessError::Timeout(self.program.clone(), TIMEOUT.as_secs()))?
+            .map_err(io_error)
+    }
+}
+
+impl From<PluginError> for ProcessError {
+    fn from(error: PluginError) -> Self {
+        ProcessError::Plugin(error.message)
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for ProcessProvider {
+    async fn get_weather(
+        &self,
+        address: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather(self, address, date).await?)
+    }
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather_at(self, address, date, time).await?)
+    }
+    async fn local_date(
+        &self,
+        address: &str,
+    ) -> Result<NaiveDate, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::local_date(self, address).await?)
+    }
+    async fn get_alerts(
+        &self,
+        address: &str,
+    ) -> Result<Vec<Alert>, Box<dyn Error + Send + Sync + 'static>> {
+        match self
+            .call::<Vec<AlertRecord>>("get_alerts", Params::address(address))
+            .await?
+        {
+            Ok(alerts) => Ok(alerts.into_iter().map(Alert::from).collect()),
+            Err(e) if e.is_unsupported() => Err(Box::new(
+                weather_abstractions::Error::NotSupportedFeature("alerts"),
+            )),
+            Err(e) => Err(Box::new(ProcessError::from(e))),
+        }
+    }
+    /// Falls back to the current weather in London when the plugin has no own verification.
+    async fn verify(&self) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        match self.call::<IgnoredAny>("verify", Params::default()).await? {
+            Ok(_) => Ok(()),
+            Err(e) if e.is_unsupported() => {
+                Self::get_weather(self, "London", None).await?;
+                Ok(())
+            }
+            Err(e) => Err(Box::new(ProcessError::from(e))),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use rstest::rstest;
+    use std::{fs, os::unix::fs::PermissionsExt, path::Path, sync::OnceLock};
+
+    const CURRENT: &str = r#"{"result": {"kind": "current", "temp_c": 21.5, "condition": "Light rain", "humidity": 70, "wind_speed": 3.5, "alerts": [{"event": "Flood Warning", "description": "Stay away from rivers", "end": "2024-05-01T18:00:00+02:00"}]}}"#;
+
+    /// The plugins as shell scripts, written once before any of them runs: a script being written
+    /// can't be executed by the processes forked meanwhile.
+    const PLUGINS: &[(&str, &str)] = &[
+        (
+            "weather-provider-echo",
+            r#"cat > "$0.request"
+printf '%s' '{"result": {"kind": "forecast", "temp_c": 18.0, "condition": "Sunny", "condition_kind": "PartlyCloudy"}}'"#,
+        ),
+        (
+            "weather-provider-daily",
+            r#"case "$(cat)" in
+  *'"get_weather"'*) printf '%s' 'CURRENT' ;;
+  *) printf '%s' '{"error": {"message": "No hourly data", "code": "unsupported"}}' ;;
+esac"#,
+        ),
+        (
+            "weather-provider-failing",
+            r#"printf '%s' '{"error": {"message": "Unknown location"}}'"#,
+        ),
+        (
+            "weather-provider-crashing",
+            r#"echo "Traceback: something broke" >&2
+exit 3"#,
+        ),
+        ("weather-provider-garbage", "echo 'not json'"),
+    ];
+
+    fn plugins_dir() -> &'static Path {
+        static DIR: OnceLock<PathBuf> = OnceLock::new();
+        DIR.get_or_init(|| {
+            let dir = std::env::temp_dir().join(format!("weather-plugins-{}", std::process::id()));
+            fs::create_dir_all(&dir).expect("plugins directory created");
+            for (name, script) in PLUGINS {
+                let path = dir.join(name);
+                let script = script.replace("CURRENT", CURRENT);
+                fs::write(&path, format!("#!/bin/sh\n{script}\n")).expect("plugin written");
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+                    .expect("plugin made executable");
+            }
+            fs::write(dir.join("weather-provider-notes.txt"), "").expect("notes written");
+            fs::write(dir.join("other-tool"), "").expect("other tool written");
+            fs::set_permissions(dir.join("other-tool"), fs::Permissions::from_mode(0o755))
+                .expect("other tool made executable");
+            dir
+        })
+    }
+
+    fn provider(name: &str) -> ProcessProvider {
+        let cfg = Config::builder()
+            .set_override(format!("{name}.apiKey"), "some-api-key")
+            .expect("api key set")
+            .build()
+            .expect("config built");
+        ProcessProvider::new(
+            name,
+            plugins_dir().join(format!("{PLUGIN_PREFIX}{name}")),
+            &cfg,
+        )
+        .expect("provider created")
+    }
+
+    #[test]
+    fn discover_plugins_on_path() {
+        let other_dir = std::env::temp_dir().join("weather-plugins-missing");
+        let search_path =
+            std::env::join_paths([plugins_dir(), other_dir.as_path()]).expect("search path joined");
+
+        let plugins = discover(&search_path);
+
+        let names: Vec<&str> = plugins.keys().map(String::as_str).collect();
+        assert_eq!(names, ["crashing", "daily", "echo", "failing", "garbage"]);
+        assert_eq!(plugins["echo"], plugins_dir().join("weather-provider-echo"));
+    }
+
+    #[test]
+    fn listed_plugins_take_precedence() {
+        let cfg = Config::builder()
+            .set_override("plugins.echo", "/opt/echo")
+            .expect("plugin listed")
+            .build()
+            .expect("config built");
+
+        assert_eq!(plugins(&cfg)["echo"], PathBuf::from("/opt/echo"));
+    }
+
+    #[tokio::test]
+    async fn get_weather_sends_request_with_settings() {
+        let provider = provider("echo");
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+
+        let weather = provider
+            .get_weather("London", Some(date))
+            .await
+            .expect("weather returned");
+
+        assert_eq!(weather.kind, WeatherKind::Forecast);
+        assert_eq!(weather.temp, Temperature::Celsius(18.0));
+        assert_eq!(weather.condition_kind, ConditionKind::PartlyCloudy);
+        let request = fs::read_to_string(plugins_dir().join("weather-provider-echo.request"))
+            .expect("request saved");
+        let request: serde_json::Value = serde_json::from_str(&request).expect("request is JSON");
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "version": 1,
+                "method": "get_weather",
+                "params": {"address": "London", "date": "2024-05-01"},
+                "settings": {"apiKey": "some-api-key"}
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn unsupported_hourly_falls_back_to_daily() {
+        let provider = provider("daily");
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let time = "15:00".parse().unwrap();
+
+        let weather = provider
+            .get_weather_at("London", date, time)
+            .await
+            .expect("weather returned");
+
+        assert_eq!(weather.kind, WeatherKind::Current);
+        assert_eq!(weather.condition_kind, ConditionKind::Rain);
+        assert_eq!(weather.humidity, Some(70.0));
+        assert_eq!(weather.wind_speed, Some(3.5));
+        assert_eq!(weather.alerts.len(), 1);
+        assert_eq!(weather.alerts[0].event, "Flood Warning");
+        assert_eq!(
+            provider.local_date("51.5,0").await.expect("date estimated"),
+            estimated_local_date("51.5,0")
+        );
+    }
+
+    #[rstest]
+    #[case("failing", "Plugin error: Unknown location")]
+    #[case("crashing", "exited with exit status: 3: Traceback: something broke")]
+    #[case("garbage", "returns unexpected JSON")]
+    #[case("missing", "Unable to run the plugin")]
+    #[tokio::test]
+    async fn plugin_errors(#[case] name: &str, #[case] message: &str) {
+        let provider = provider(name);
+
+        let error = provider
+            .get_weather("London", None)
+            .await
+            .expect_err("weather should fail");
+
+        assert!(error.to_string().contains(message), "{error}");
+    }
+}
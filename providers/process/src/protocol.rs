@@ -0,0 +1,167 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use weather_abstractions::{
+    Alert, ConditionKind, Temperature, TemperatureError, TimeOfDay, Weather,
+};
+
+/// The version of the protocol, sent with every request so the plugins can reject the versions
+/// they don't know.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The request written to the standard input of the plugin.
+#[derive(Serialize)]
+pub struct Request<'a> {
+    pub version: u32,
+    /// One of `get_weather`, `get_weather_at`, `local_date`, `get_alerts` or `verify`
+    pub method: &'static str,
+    pub params: Params<'a>,
+    /// The settings of the provider, from its section of the settings
+    pub settings: &'a serde_json::Value,
+}
+
+#[derive(Serialize, Default)]
+pub struct Params<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<NaiveDate>,
+    /// The time of the day like 15:00, followed by the UTC offset when it was given, like
+    /// 15:00+02:00, the local time of the location otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+}
+
+impl<'a> Params<'a> {
+    pub fn address(address: &'a str) -> Self {
+        Self {
+            address: Some(address),
+            ..Default::default()
+        }
+    }
+}
+
+/// Formats the time of the day for the `time` parameter.
+pub fn time_param(time: TimeOfDay) -> String {
+    let hours = time.time.format("%H:%M");
+    match time.offset {
+        Some(offset) => format!("{hours}{offset}"),
+        None => hours.to_string(),
+    }
+}
+
+/// The response read from the standard output of the plugin, either `{"result": ...}` or
+/// `{"error": {...}}`.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Response<T> {
+    Result(T),
+    Error(PluginError),
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PluginError {
+    pub message: String,
+    /// `unsupported` when the plugin doesn't implement the method
+    pub code: Option<String>,
+}
+
+impl PluginError {
+    pub fn is_unsupported(&self) -> bool {
+        self.code.as_deref() == Some("unsupported")
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    History,
+    Current,
+    Forecast,
+}
+
+#[derive(Deserialize)]
+pub struct WeatherRecord {
+    pub kind: Kind,
+    pub temp_c: f32,
+    pub condition: String,
+    /// The name of a `ConditionKind` variant like `PartlyCloudy`, guessed from the condition if
+    /// missing or unknown
+    pub condition_kind: Option<String>,
+    /// The time of the hourly slot, for `get_weather_at`
+    pub time: Option<DateTime<FixedOffset>>,
+    pub humidity: Option<f32>,
+    pub wind_speed: Option<f32>,
+    pub pressure: Option<f32>,
+    pub dew_point_c: Option<f32>,
+    pub feels_like_c: Option<f32>,
+    #[serde(default)]
+    pub alerts: Vec<AlertRecord>,
+}
+
+impl TryFrom<WeatherRecord> for Weather {
+    type Error = TemperatureError;
+
+    fn try_from(record: WeatherRecord) -> Result<Self, Self::Error> {
+        let temp = Temperature::from_c(record.temp_c)?;
+        let mut weather = match record.kind {
+            Kind::History => Weather::history(temp, record.condition),
+            Kind::Current => Weather::current(temp, record.condition),
+            Kind::Forecast => Weather::forecast(temp, record.condition),
+        };
+        if let Some(kind) = record.condition_kind.as_deref().and_then(condition_kind) {
+            weather = weather.with_condition_kind(kind);
+        }
+        if let Some(time) = record.time {
+            weather = weather.with_time(time);
+        }
+        weather.humidity = record.humidity;
+        weather.wind_speed = record.wind_speed;
+        weather.pressure = record.pressure;
+        weather.dew_point = record.dew_point_c.map(Temperature::from_c).transpose()?;
+        weather.feels_like = record.feels_like_c.map(Temperature::from_c).transpose()?;
+        Ok(weather.with_alerts(record.alerts.into_iter().map(Alert::from).collect()))
+    }
+}
+
+fn condition_kind(name: &str) -> Option<ConditionKind> {
+    Some(match name {
+        "Clear" => ConditionKind::Clear,
+        "PartlyCloudy" => ConditionKind::PartlyCloudy,
+        "Cloudy" => ConditionKind::Cloudy,
+        "Fog" => ConditionKind::Fog,
+        "Drizzle" => ConditionKind::Drizzle,
+        "Rain" => ConditionKind::Rain,
+        "HeavyRain" => ConditionKind::HeavyRain,
+        "Thunderstorm" => ConditionKind::Thunderstorm,
+        "Snow" => ConditionKind::Snow,
+        "Sleet" => ConditionKind::Sleet,
+        "Unknown" => ConditionKind::Unknown,
+        _ => return None,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct AlertRecord {
+    pub event: String,
+    pub severity: Option<String>,
+    pub area: Option<String>,
+    pub start: Option<DateTime<FixedOffset>>,
+    pub end: Option<DateTime<FixedOffset>>,
+    #[serde(default)]
+    pub description: String,
+    pub sender: Option<String>,
+}
+
+impl From<AlertRecord> for Alert {
+    fn from(record: AlertRecord) -> Self {
+        Alert {
+            event: record.event,
+            severity: record.severity,
+            area: record.area,
+            start: record.start,
+            end: record.end,
+            description: record.description,
+            sender: record.sender,
+        }
+    }
+}
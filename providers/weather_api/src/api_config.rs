@@ -1,15 +1,28 @@
 use crate::{generate_functions, utils::build_endpoint, WeatherApiError};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use weather_abstractions::SettingDescriptor;
 
 pub const PROVIDER_NAME: &str = "weather-api";
 
+/// The settings supported by the provider.
+pub const SETTINGS: &[SettingDescriptor] = &[
+    SettingDescriptor::secret("apiKey", "The WeatherAPI key"),
+    SettingDescriptor::optional("baseUrl", "The base URL of the WeatherAPI"),
+    SettingDescriptor::optional("currentPath", "The path of the current weather endpoint"),
+    SettingDescriptor::optional("historyPath", "The path of the history endpoint"),
+    SettingDescriptor::optional("forecastPath", "The path of the forecast endpoint"),
+    SettingDescriptor::optional("futurePath", "The path of the future endpoint"),
+    SettingDescriptor::optional("astronomyPath", "The path of the astronomy endpoint"),
+];
+
 generate_functions! {
     base_url, "http://api.weatherapi.com",
     current_path, "/v1/current.json",
     history_path, "/v1/history.json",
     forecast_path, "/v1/forecast.json",
-    future_path, "/v1/future.json"
+    future_path, "/v1/future.json",
+    astronomy_path, "/v1/astronomy.json"
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -26,6 +39,8 @@ pub struct ApiConfig {
     pub forecast_path: String,
     #[serde(default = "future_path")]
     pub future_path: String,
+    #[serde(default = "astronomy_path")]
+    pub astronomy_path: String,
 }
 
 pub struct Endpoints {
@@ -33,6 +48,7 @@ pub struct Endpoints {
     pub history: Url,
     pub forecast: Url,
     pub future: Url,
+    pub astronomy: Url,
 }
 
 impl TryFrom<ApiConfig> for Endpoints {
@@ -50,6 +66,7 @@ impl TryFrom<ApiConfig> for Endpoints {
             history: build_endpoint(&base_url, &value.history_path),
             forecast: build_endpoint(&base_url, &value.forecast_path),
             future: build_endpoint(&base_url, &value.future_path),
+            astronomy: build_endpoint(&base_url, &value.astronomy_path),
         })
     }
 }
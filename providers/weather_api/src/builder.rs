@@ -4,6 +4,7 @@ use config::Config;
 use reqwest_middleware::ClientBuilder;
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use reqwest_tracing::TracingMiddleware;
+use weather_abstractions::utils::{ProviderSpan, ProviderSpanConfig};
 
 use crate::WeatherApiError;
 
@@ -27,7 +28,7 @@ impl WeatherApiBuilder {
 
         let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
         let client = ClientBuilder::new(reqwest::Client::new())
-            .with(TracingMiddleware::default())
+            .with(TracingMiddleware::<ProviderSpan<Provider>>::new())
             .with(RetryTransientMiddleware::new_with_policy(retry_policy))
             .build();
         Ok(WeatherApi {
@@ -37,3 +38,10 @@ impl WeatherApiBuilder {
         })
     }
 }
+
+/// The provider of the request spans, with the `key` parameter redacted.
+struct Provider;
+impl ProviderSpanConfig for Provider {
+    const NAME: &'static str = PROVIDER_NAME;
+    const SECRETS: &'static [&'static str] = &["key"];
+}
@@ -3,10 +3,10 @@ pub mod builder;
 mod error;
 mod protocol;
 
-use std::error::Error;
+use std::{collections::HashMap, error::Error};
 
-pub use api_config::PROVIDER_NAME;
-use chrono::NaiveDate;
+pub use api_config::{PROVIDER_NAME, SETTINGS};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Timelike};
 pub use error::WeatherApiError;
 use protocol::*;
 use reqwest_middleware::ClientWithMiddleware;
@@ -21,6 +21,8 @@ const MIN_FORECAS_DAYS: i64 = 1;
 const MAX_FORECAS_DAYS: i64 = 14;
 const MIN_FUTURE_DAYS: i64 = 15;
 const MAX_FUTURE_DAYS: i64 = 300;
+/// The maximum number of days returned by a single history request with `end_dt`.
+const MAX_HISTORY_RANGE_DAYS: u64 = 30;
 
 use async_trait::async_trait;
 #[async_trait]
@@ -32,6 +34,47 @@ impl WeatherProvider for WeatherApi {
     ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
         Ok(Self::get_weather(self, address, date).await?)
     }
+    async fn get_weather_range(
+        &self,
+        address: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Weather)>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather_range(self, address, from, to).await?)
+    }
+    async fn local_date(
+        &self,
+        address: &str,
+    ) -> Result<NaiveDate, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::local_date(self, address).await?)
+    }
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_weather_at(self, address, date, time).await?)
+    }
+    async fn get_alerts(
+        &self,
+        address: &str,
+    ) -> Result<Vec<Alert>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_alerts(self, address).await?)
+    }
+    async fn get_air_quality(
+        &self,
+        address: &str,
+    ) -> Result<AirQuality, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_air_quality(self, address).await?)
+    }
+    async fn get_astronomy(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Astronomy, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(Self::get_astronomy(self, address, date).await?)
+    }
 }
 impl WeatherApi {
     async fn get_weather(
@@ -41,7 +84,7 @@ impl WeatherApi {
     ) -> Result<Weather, WeatherApiError> {
         if let Some(date) = date {
             let today = chrono::offset::Utc::now().date_naive();
-            let min_date = NaiveDate::from_ymd_opt(2010, 1, 1).expect("Date 2010-1-1 created");
+            let min_date = min_history_date();
             let dif_days = date.signed_duration_since(today).num_days();
             return match dif_days {
                 0 => self.current(address).await,
@@ -53,6 +96,119 @@ impl WeatherApi {
         }
         self.current(address).await
     }
+    /// Requests the past days of the range by up to `MAX_HISTORY_RANGE_DAYS` days with the
+    /// `end_dt` parameter, and the other days one by one. The days missing in a range response,
+    /// for example when the plan doesn't support `end_dt`, are requested one by one as well.
+    async fn get_weather_range(
+        &self,
+        address: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Weather)>, WeatherApiError> {
+        let today = chrono::offset::Utc::now().date_naive();
+        let mut days = Vec::new();
+        let mut start = from;
+        while start <= to {
+            if start >= today || start <= min_history_date() {
+                days.push((start, self.get_weather(address, Some(start)).await?));
+                start = start + chrono::Days::new(1);
+                continue;
+            }
+            let end = to
+                .min(start + chrono::Days::new(MAX_HISTORY_RANGE_DAYS - 1))
+                .min(today - chrono::Days::new(1));
+            let mut range = self.history_range(address, start, end).await?;
+            for date in start.iter_days().take_while(|date| *date <= end) {
+                let weather = match range.remove(&date) {
+                    Some(weather) => weather,
+                    None => self.history(address, date).await?,
+                };
+                days.push((date, weather));
+            }
+            start = end + chrono::Days::new(1);
+        }
+        Ok(days)
+    }
+    /// Requests the hours of the day and picks the one closest to the time. The request is limited
+    /// to the hour with the `hour` parameter when the time is in the local time of the location.
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        time: TimeOfDay,
+    ) -> Result<Weather, WeatherApiError> {
+        let today = chrono::offset::Utc::now().date_naive();
+        let dif_days = date.signed_duration_since(today).num_days();
+        let (endpoint, kind) = match dif_days {
+            0..=MAX_FORECAS_DAYS => (&self.endpoints.forecast, WeatherKind::Forecast),
+            MIN_FUTURE_DAYS..=MAX_FUTURE_DAYS => (&self.endpoints.future, WeatherKind::Forecast),
+            _ if date < today && date > min_history_date() => {
+                (&self.endpoints.history, WeatherKind::History)
+            }
+            _ => return Err(WeatherApiError::UnsupportedDate(date)),
+        };
+        let dt = date.format("%Y-%m-%d").to_string();
+        let mut request = self
+            .default_request_builder(endpoint, address)
+            .query(&[("dt", &dt)]);
+        if dif_days <= MAX_FORECAS_DAYS {
+            request = request.query(&[("days", (dif_days + 1).max(1))]);
+        }
+        if time.offset.is_none() {
+            request = request.query(&[("hour", time.time.hour())]);
+        }
+        let resp = parse::<ForecastResponse>(request.send().await?).await?;
+        let json_error = || {
+            WeatherApiError::JSON(
+                endpoint.path().to_string(),
+                "./forecast.forecastday.hour".to_string(),
+            )
+        };
+        let slots = resp
+            .forecast
+            .forecastday
+            .into_iter()
+            .flat_map(|forecast| forecast.hour)
+            .map(|hour| Ok((hour_time(&hour).ok_or_else(json_error)?, hour)))
+            .collect::<Result<Vec<_>, WeatherApiError>>()?;
+        let local = date.and_time(time.time);
+        let (slot_time, hour) = slots
+            .into_iter()
+            .min_by_key(|(slot_time, _)| match time.offset {
+                Some(offset) => (slot_time.timestamp() - time.timestamp(date, offset)).abs(),
+                None => (slot_time.naive_local() - local).num_seconds().abs(),
+            })
+            .ok_or_else(json_error)?;
+        let temp = Temperature::from_c(hour.temp_c)?;
+        let condition_kind = hour.condition.condition_kind();
+        let weather = match kind {
+            WeatherKind::History => Weather::history(temp, hour.condition.text),
+            _ => Weather::forecast(temp, hour.condition.text),
+        };
+        Ok(hour
+            .details
+            .apply(weather)
+            .with_condition_kind(condition_kind)
+            .with_time(slot_time))
+    }
+    /// Takes the date from the local time of the location in the current weather response.
+    async fn local_date(&self, address: &str) -> Result<NaiveDate, WeatherApiError> {
+        let endpoint = self.endpoints.current.clone();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .send()
+            .await?;
+        let resp = parse::<CurrentResponse>(response).await?;
+        resp.location
+            .localtime
+            .as_deref()
+            .and_then(|localtime| localtime.get(..10))
+            .and_then(|date| date.parse::<NaiveDate>().ok())
+            .ok_or(WeatherApiError::JSON(
+                endpoint.path().to_string(),
+                "./location.localtime".to_string(),
+            ))
+    }
     async fn current(&self, address: &str) -> Result<Weather, WeatherApiError> {
         let endpoint = self.endpoints.current.clone();
         let response = self
@@ -60,10 +216,13 @@ impl WeatherApi {
             .send()
             .await?;
         let resp = parse::<CurrentResponse>(response).await?;
-        Ok(Weather::current(
+        let condition_kind = resp.current.condition.condition_kind();
+        let weather = Weather::current(
             Temperature::from_c(resp.current.temp_c)?,
             resp.current.condition.text,
-        ))
+        )
+        .with_condition_kind(condition_kind);
+        Ok(resp.current.details.apply(weather))
     }
     async fn history(
         &self,
@@ -86,10 +245,31 @@ impl WeatherApi {
                 self.endpoints.history.path().to_string(),
                 "./forecast.forecastday".to_string(),
             ))?;
-        Ok(Weather::history(
-            Temperature::from_c(forecast.day.avgtemp_c)?,
-            forecast.day.condition.text,
-        ))
+        day_weather(forecast.day, WeatherKind::History)
+    }
+    async fn history_range(
+        &self,
+        address: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<HashMap<NaiveDate, Weather>, WeatherApiError> {
+        let endpoint = self.endpoints.history.clone();
+        let dt = from.format("%Y-%m-%d").to_string();
+        let end_dt = to.format("%Y-%m-%d").to_string();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .query(&[("dt", &dt), ("end_dt", &end_dt)])
+            .send()
+            .await?;
+        let resp = parse::<HistoryResponse>(response).await?;
+        resp.forecast
+            .forecastday
+            .into_iter()
+            .filter_map(|forecast| {
+                let date = forecast.date.as_deref()?.parse::<NaiveDate>().ok()?;
+                Some(day_weather(forecast.day, WeatherKind::History).map(|weather| (date, weather)))
+            })
+            .collect()
     }
     async fn forecast(&self, address: &str, day: i64) -> Result<Weather, WeatherApiError> {
         let endpoint = self.endpoints.forecast.clone();
@@ -98,11 +278,12 @@ impl WeatherApi {
             .query(&[
                 ("days", day.to_string().as_str()),
                 ("aqi", "no"),
-                ("alerts", "no"),
+                ("alerts", "yes"),
             ])
             .send()
             .await?;
         let mut resp = parse::<ForecastResponse>(response).await?;
+        let date = chrono::offset::Utc::now().date_naive() + chrono::Duration::days(day);
         let forecast = resp
             .forecast
             .forecastday
@@ -112,10 +293,72 @@ impl WeatherApi {
                 "./forecast.forecastday".to_string(),
             ))?;
 
-        Ok(Weather::forecast(
-            Temperature::from_c(forecast.day.avgtemp_c)?,
-            forecast.day.condition.text,
-        ))
+        let date = forecast
+            .date
+            .and_then(|date| date.parse().ok())
+            .unwrap_or(date);
+        let alerts = resp
+            .alerts
+            .alert
+            .into_iter()
+            .map(AlertItem::into_alert)
+            .filter(|alert| alert.is_active_on(date))
+            .collect();
+
+        Ok(day_weather(forecast.day, WeatherKind::Forecast)?.with_alerts(alerts))
+    }
+    /// Requests the current weather with the air quality.
+    async fn get_air_quality(&self, address: &str) -> Result<AirQuality, WeatherApiError> {
+        let endpoint = self.endpoints.current.clone();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .query(&[("aqi", "yes")])
+            .send()
+            .await?;
+        let resp = parse::<CurrentResponse>(response).await?;
+        let air_quality = resp.current.air_quality.ok_or(WeatherApiError::JSON(
+            endpoint.path().to_string(),
+            "./current.air_quality".to_string(),
+        ))?;
+        Ok(AirQuality::from(air_quality).with_computed_indexes())
+    }
+    /// Requests the forecast for today with the alerts, the expired alerts are dropped.
+    async fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, WeatherApiError> {
+        let endpoint = self.endpoints.forecast.clone();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .query(&[("days", "1"), ("aqi", "no"), ("alerts", "yes")])
+            .send()
+            .await?;
+        let resp = parse::<ForecastResponse>(response).await?;
+        let now = chrono::offset::Utc::now();
+        Ok(resp
+            .alerts
+            .alert
+            .into_iter()
+            .map(AlertItem::into_alert)
+            .filter(|alert| alert.end.is_none_or(|end| now < end))
+            .collect())
+    }
+    /// Requests the sun and moon events of the date, the times are local to the location.
+    async fn get_astronomy(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Astronomy, WeatherApiError> {
+        let endpoint = self.endpoints.astronomy.clone();
+        let dt = date.format("%Y-%m-%d").to_string();
+        let response = self
+            .default_request_builder(&endpoint, address)
+            .query(&[("dt", &dt)])
+            .send()
+            .await?;
+        let resp = parse::<AstronomyResponse>(response).await?;
+        let offset = resp.location.offset().ok_or(WeatherApiError::JSON(
+            endpoint.path().to_string(),
+            "./location.localtime_epoch".to_string(),
+        ))?;
+        Ok(resp.astronomy.astro.into_astronomy(date, offset))
     }
 
     async fn future(&self, address: &str, date: NaiveDate) -> Result<Weather, WeatherApiError> {
@@ -135,10 +378,7 @@ impl WeatherApi {
                 endpoint.path().to_string(),
                 "./forecast.forecastday".to_string(),
             ))?;
-        Ok(Weather::forecast(
-            Temperature::from_c(forecast.day.avgtemp_c)?,
-            forecast.day.condition.text,
-        ))
+        day_weather(forecast.day, WeatherKind::Forecast)
     }
 
     fn default_request_builder(
@@ -152,6 +392,39 @@ impl WeatherApi {
     }
 }
 
+/// The earliest date supported by the history endpoint, exclusive.
+fn min_history_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2010, 1, 1).expect("Date 2010-1-1 created")
+}
+
+/// Returns the local time of the hour with the offset of the location, which is the difference
+/// between the local time and the epoch time.
+fn hour_time(hour: &Hour) -> Option<DateTime<FixedOffset>> {
+    let local = NaiveDateTime::parse_from_str(&hour.time, "%Y-%m-%d %H:%M").ok()?;
+    let offset = local.and_utc().timestamp() - hour.time_epoch;
+    let offset = FixedOffset::east_opt(i32::try_from(offset).ok()?)?;
+    offset.from_local_datetime(&local).single()
+}
+
+/// Converts the daily values with the average humidity, the daily wind is only the maximum so
+/// it is not used.
+fn day_weather(day: Day, kind: WeatherKind) -> Result<Weather, WeatherApiError> {
+    let condition_kind = day.condition.condition_kind();
+    let temp = Temperature::from_c(day.avgtemp_c)?;
+    let weather = match kind {
+        WeatherKind::History => Weather::history(temp, day.condition.text),
+        _ => Weather::forecast(temp, day.condition.text),
+    }
+    .with_condition_kind(condition_kind);
+    let mut weather = match day.avghumidity {
+        Some(humidity) => weather.with_humidity(humidity),
+        None => weather,
+    };
+    // The strongest wind of the day, the one that matters for the planning
+    weather.wind_speed = day.maxwind_kph.map(|wind| wind / 3.6);
+    Ok(weather)
+}
+
 async fn parse<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, WeatherApiError> {
     let resp_or_error = crate::utils::parse::<T, ErrorResponse>(res)
         .await
@@ -170,6 +443,7 @@ mod test {
     use super::*;
     use crate::assert_error;
     use rstest::rstest;
+    use serde_json::json;
     use test_case::*;
     static SERVER_POOL: ServerPool = ServerPool::new(20);
 
@@ -213,7 +487,7 @@ mod test {
     }
 
     use config::Config;
-    use httptest::{matchers::*, responders::*, Expectation, ServerHandle, ServerPool};
+    use httptest::{all_of, matchers::*, responders::*, Expectation, ServerHandle, ServerPool};
     fn setup<'a>() -> (Config, ServerHandle<'a>) {
         let api_key = "some-api-key";
         let server = SERVER_POOL.get_server();
@@ -295,6 +569,317 @@ mod test {
         assert_eq!(weather.condition, expected_condition);
     }
 
+    #[tokio::test]
+    async fn get_weather_range_requests_missing_days_one_by_one() {
+        let (cfg, server) = setup();
+        let path = endpoint_from_config(&cfg, "historyPath");
+        let range_json = serde_json::to_string(&history_range_2_days()).expect("serialize json");
+        let day_json = serde_json::to_string(&history_m_1c_snow()).expect("serialize json");
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", path.clone()),
+                request::query(url_decoded(contains(("dt", "2012-01-01")))),
+                request::query(url_decoded(contains(("end_dt", "2012-01-03")))),
+            ])
+            .times(1)
+            .respond_with(status_code(200).body(range_json)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", path),
+                request::query(url_decoded(contains(("dt", "2012-01-03")))),
+                request::query(url_decoded(not(contains(key("end_dt"))))),
+            ])
+            .times(1)
+            .respond_with(status_code(200).body(day_json)),
+        );
+
+        let client = WeatherApiBuilder::build(&cfg).expect("WeatherApi created");
+        let days = client
+            .get_weather_range(
+                "New York",
+                date(2012, 1, 1).unwrap(),
+                date(2012, 1, 3).unwrap(),
+            )
+            .await
+            .expect("weather range should be ok");
+
+        let days: Vec<(NaiveDate, Temperature)> = days
+            .into_iter()
+            .map(|(date, weather)| (date, weather.temp))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                (date(2012, 1, 1).unwrap(), temp_c(2.0)),
+                (date(2012, 1, 2).unwrap(), temp_c(4.5)),
+                (date(2012, 1, 3).unwrap(), temp_c(-1.0)),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("15:00", Some("15"), "2012-01-01T15:00:00-05:00", -3.0)]
+    #[case("15:00Z", None, "2012-01-01T14:00:00-05:00", -2.0)]
+    #[tokio::test]
+    async fn get_weather_at_closest_hour(
+        #[case] time: &str,
+        #[case] hour: Option<&'static str>,
+        #[case] expected_time: &str,
+        #[case] expected_temp: f32,
+    ) {
+        let (cfg, server) = setup();
+        let path = endpoint_from_config(&cfg, "historyPath");
+        let json = serde_json::to_string(&history_hours_new_york()).expect("serialize json");
+        let expectation = match hour {
+            Some(hour) => Expectation::matching(all_of![
+                request::method_path("GET", path),
+                request::query(url_decoded(contains(("dt", "2012-01-01")))),
+                request::query(url_decoded(contains(("hour", hour)))),
+            ]),
+            None => Expectation::matching(all_of![
+                request::method_path("GET", path),
+                request::query(url_decoded(contains(("dt", "2012-01-01")))),
+                request::query(url_decoded(not(contains(key("hour"))))),
+            ]),
+        };
+        server.expect(expectation.respond_with(status_code(200).body(json)));
+
+        let client = WeatherApiBuilder::build(&cfg).expect("WeatherApi created");
+        let time: TimeOfDay = time.parse().expect("valid time");
+        let weather = client
+            .get_weather_at("New York", date(2012, 1, 1).unwrap(), time)
+            .await
+            .expect("weather at time should be ok");
+
+        assert_eq!(weather.kind, WeatherKind::History);
+        assert_eq!(weather.temp, temp_c(expected_temp));
+        let expected_time = DateTime::parse_from_rfc3339(expected_time).expect("valid time");
+        assert_eq!(weather.time, Some(expected_time));
+        assert_eq!(
+            weather.time.map(|t| *t.offset()),
+            Some(*expected_time.offset())
+        );
+    }
+
+    #[tokio::test]
+    async fn get_air_quality_from_current_weather() {
+        let (cfg, server) = setup();
+        let path = endpoint_from_config(&cfg, "currentPath");
+        let mut body = current_8c_clear();
+        body["current"]["air_quality"] = json!({
+            "co": 230.3,
+            "no2": 13.5,
+            "o3": 54.3,
+            "so2": 3.1,
+            "pm2_5": 8.2,
+            "pm10": 11.4,
+            "us-epa-index": 1,
+            "gb-defra-index": 1
+        });
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", path),
+                request::query(url_decoded(contains(("aqi", "yes")))),
+            ])
+            .respond_with(json_encoded(body)),
+        );
+
+        let client = WeatherApiBuilder::build(&cfg).expect("WeatherApi created");
+        let air = WeatherProvider::get_air_quality(&client, "London")
+            .await
+            .expect("air quality should be ok");
+
+        assert_eq!(air.co, Some(230.3));
+        assert_eq!(air.pm2_5, Some(8.2));
+        assert_eq!(air.us_epa_index, Some(1));
+        assert_eq!(air.gb_defra_index, Some(1));
+    }
+
+    #[tokio::test]
+    async fn get_alerts_drops_expired_alerts() {
+        let (cfg, server) = setup();
+        let path = endpoint_from_config(&cfg, "forecastPath");
+        let mut body = forecast_3c_rainy();
+        body["alerts"] = json!({
+            "alert": [
+                {
+                    "headline": "Flood Warning issued by NWS",
+                    "severity": "Moderate",
+                    "areas": "Kyiv region",
+                    "event": "",
+                    "effective": "2023-03-05T06:00:00+02:00",
+                    "expires": "2999-01-01T00:00:00+02:00",
+                    "desc": "River flooding is expected.",
+                    "instruction": "Avoid the river banks."
+                },
+                {
+                    "headline": "Wind Advisory",
+                    "severity": "",
+                    "areas": "",
+                    "event": "Wind Advisory",
+                    "effective": "2000-01-01T00:00:00+02:00",
+                    "expires": "2000-01-02T00:00:00+02:00",
+                    "desc": "Strong wind.",
+                    "instruction": ""
+                }
+            ]
+        });
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", path),
+                request::query(url_decoded(contains(("alerts", "yes")))),
+            ])
+            .respond_with(json_encoded(body)),
+        );
+
+        let client = WeatherApiBuilder::build(&cfg).expect("WeatherApi created");
+        let alerts = WeatherProvider::get_alerts(&client, "Kyiv")
+            .await
+            .expect("alerts should be ok");
+
+        assert_eq!(alerts.len(), 1);
+        let alert = &alerts[0];
+        assert_eq!(alert.event, "Flood Warning issued by NWS");
+        assert_eq!(alert.severity.as_deref(), Some("Moderate"));
+        assert_eq!(alert.area.as_deref(), Some("Kyiv region"));
+        assert_eq!(
+            alert.description,
+            "River flooding is expected.\nAvoid the river banks."
+        );
+        assert_eq!(
+            alert.start,
+            DateTime::parse_from_rfc3339("2023-03-05T06:00:00+02:00").ok()
+        );
+        assert_eq!(alert.sender, None);
+    }
+
+    #[tokio::test]
+    async fn local_date_from_location_localtime() {
+        let (cfg, server) = setup();
+        let path = endpoint_from_config(&cfg, "currentPath");
+        let json = serde_json::to_string(&current_8c_clear()).expect("serialize json");
+        server.expect(
+            Expectation::matching(request::method_path("GET", path))
+                .respond_with(status_code(200).body(json)),
+        );
+
+        let client = WeatherApiBuilder::build(&cfg).expect("WeatherApi created");
+        let date = WeatherProvider::local_date(&client, "London")
+            .await
+            .expect("local date should be ok");
+
+        assert_eq!(Some(date), self::date(2023, 2, 6));
+    }
+
+    #[tokio::test]
+    async fn get_weather_current_with_details() {
+        let (cfg, server) = setup();
+        let path = endpoint_from_config(&cfg, "currentPath");
+        let mut body = current_8c_clear();
+        body["current"]["humidity"] = 81.into();
+        body["current"]["wind_kph"] = 18.0.into();
+        body["current"]["pressure_mb"] = 1012.0.into();
+        body["current"]["feelslike_c"] = 5.4.into();
+        server.expect(
+            Expectation::matching(request::method_path("GET", path))
+                .respond_with(json_encoded(body)),
+        );
+
+        let client = WeatherApiBuilder::build(&cfg).expect("WeatherApi created");
+        let weather = client
+            .get_weather("London", None)
+            .await
+            .expect("weather should be ok");
+
+        assert_eq!(weather.humidity, Some(81.0));
+        assert_eq!(weather.wind_speed, Some(5.0));
+        assert_eq!(weather.pressure, Some(1012.0));
+        assert_eq!(weather.feels_like, Some(temp_c(5.4)));
+        assert_eq!(weather.dew_point, None);
+        assert!(weather.comfort().dew_point.is_some());
+    }
+
+    #[tokio::test]
+    async fn get_weather_forecast_with_day_details() {
+        let (cfg, server) = setup();
+        let path = endpoint_from_config(&cfg, "forecastPath");
+        let mut body = forecast_3c_rainy();
+        let day = &mut body["forecast"]["forecastday"][0]["day"];
+        day["avghumidity"] = 90.into();
+        day["maxwind_kph"] = 36.0.into();
+        server.expect(
+            Expectation::matching(request::method_path("GET", path))
+                .respond_with(json_encoded(body)),
+        );
+
+        let client = WeatherApiBuilder::build(&cfg).expect("WeatherApi created");
+        let weather = client
+            .get_weather("London", date_plus_days(10))
+            .await
+            .expect("weather should be ok");
+
+        assert_eq!(weather.humidity, Some(90.0));
+        assert_eq!(weather.wind_speed, Some(10.0));
+    }
+
+    #[tokio::test]
+    async fn get_astronomy_in_local_time() {
+        let (cfg, server) = setup();
+        let path = endpoint_from_config(&cfg, "astronomyPath");
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", path),
+                request::query(url_decoded(contains(("dt", "2023-06-21")))),
+            ])
+            .respond_with(json_encoded(astronomy_kyiv())),
+        );
+
+        let client = WeatherApiBuilder::build(&cfg).expect("WeatherApi created");
+        let astronomy = WeatherProvider::get_astronomy(&client, "Kyiv", date(2023, 6, 21).unwrap())
+            .await
+            .expect("astronomy should be ok");
+
+        assert_eq!(
+            astronomy.sunrise,
+            DateTime::parse_from_rfc3339("2023-06-21T04:47:00+03:00").ok()
+        );
+        assert_eq!(
+            astronomy.sunset,
+            DateTime::parse_from_rfc3339("2023-06-21T21:13:00+03:00").ok()
+        );
+        assert_eq!(
+            astronomy.day_length(),
+            Some(chrono::Duration::minutes(16 * 60 + 26))
+        );
+        assert_eq!(astronomy.moonrise, None);
+        assert_eq!(
+            astronomy.moonset,
+            DateTime::parse_from_rfc3339("2023-06-21T00:24:00+03:00").ok()
+        );
+        assert_eq!(astronomy.moon_phase, Some(MoonPhase::WaxingCrescent));
+        assert_eq!(astronomy.moon_illumination, Some(12.0));
+    }
+
+    #[rstest]
+    #[case(1000, ConditionKind::Clear)]
+    #[case(1003, ConditionKind::PartlyCloudy)]
+    #[case(1009, ConditionKind::Cloudy)]
+    #[case(1135, ConditionKind::Fog)]
+    #[case(1063, ConditionKind::Rain)]
+    #[case(1195, ConditionKind::HeavyRain)]
+    #[case(1207, ConditionKind::Sleet)]
+    #[case(1066, ConditionKind::Snow)]
+    #[case(1276, ConditionKind::Thunderstorm)]
+    #[case(42, ConditionKind::Unknown)]
+    fn condition_kind_from_code(#[case] code: i64, #[case] expected: ConditionKind) {
+        let condition = Condition {
+            text: String::new(),
+            code,
+        };
+        assert_eq!(condition.condition_kind(), expected);
+    }
+
     fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
         NaiveDate::from_ymd_opt(year, month, day)
     }
@@ -327,6 +912,30 @@ mod test {
     mod test_case {
         use serde_json::{json, Value};
 
+        pub fn astronomy_kyiv() -> Value {
+            json!({
+                "location": {
+                    "name": "Kyiv",
+                    "region": "Kyiv",
+                    "country": "Ukraine",
+                    "lat": 50.45,
+                    "lon": 30.52,
+                    "localtime_epoch": 1687341540,
+                    "localtime": "2023-06-21 12:59"
+                },
+                "astronomy": {
+                    "astro": {
+                        "sunrise": "04:47 AM",
+                        "sunset": "09:13 PM",
+                        "moonrise": "No moonrise",
+                        "moonset": "12:24 AM",
+                        "moon_phase": "Waxing Crescent",
+                        "moon_illumination": "12"
+                    }
+                }
+            })
+        }
+
         pub fn forecast_3c_rainy() -> Value {
             json!({
                 "location": {
@@ -382,6 +991,89 @@ mod test {
                 }
             })
         }
+        pub fn history_hours_new_york() -> Value {
+            let hour = |time: &str, time_epoch: i64, temp_c: f32| {
+                json!({
+                    "time_epoch": time_epoch,
+                    "time": time,
+                    "temp_c": temp_c,
+                    "condition": {
+                        "text": "Snow",
+                        "icon": "https://www.example.com/snow.png",
+                        "code": 1066
+                    }
+                })
+            };
+            json!({
+                "location": {
+                    "name": "New York",
+                    "region": "New York",
+                    "country": "United States",
+                    "lat": 40.71,
+                    "lon": -74.01
+                },
+                "forecast": {
+                    "forecastday": [
+                        {
+                            "date": "2012-01-01",
+                            "date_epoch": 1325376000,
+                            "day": {
+                                "avgtemp_c": -1.0,
+                                "condition": {
+                                    "text": "Snow",
+                                    "icon": "https://www.example.com/snow.png",
+                                    "code": 1066
+                                }
+                            },
+                            "hour": [
+                                hour("2012-01-01 14:00", 1325444400, -2.0),
+                                hour("2012-01-01 15:00", 1325448000, -3.0),
+                                hour("2012-01-01 16:00", 1325451600, -4.0)
+                            ]
+                        }
+                    ]
+                }
+            })
+        }
+        pub fn history_range_2_days() -> Value {
+            json!({
+                "location": {
+                    "name": "New York",
+                    "region": "New York",
+                    "country": "United States",
+                    "lat": 40.71,
+                    "lon": -74.01
+                },
+                "forecast": {
+                    "forecastday": [
+                        {
+                            "date": "2012-01-01",
+                            "date_epoch": 1325376000,
+                            "day": {
+                                "avgtemp_c": 2.0,
+                                "condition": {
+                                    "text": "Overcast",
+                                    "icon": "https://www.example.com/overcast.png",
+                                    "code": 1009
+                                }
+                            }
+                        },
+                        {
+                            "date": "2012-01-02",
+                            "date_epoch": 1325462400,
+                            "day": {
+                                "avgtemp_c": 4.5,
+                                "condition": {
+                                    "text": "Light rain",
+                                    "icon": "https://www.example.com/rain.png",
+                                    "code": 1183
+                                }
+                            }
+                        }
+                    ]
+                }
+            })
+        }
         pub fn current_8c_clear() -> Value {
             json!(
                 {
@@ -1,4 +1,8 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use serde::Deserialize;
+use weather_abstractions::{
+    AirQuality, Alert, Astronomy, ConditionKind, MoonPhase, Temperature, Weather,
+};
 
 #[derive(Deserialize)]
 pub struct CurrentResponse {
@@ -8,37 +12,169 @@ pub struct CurrentResponse {
 
 #[derive(Deserialize)]
 pub struct Location {
-    pub name: String,
-    pub region: String,
-    pub country: String,
-    pub lat: f32,
-    pub lon: f32,
+    /// The local date and time at the location, for example: 2023-02-06 17:42
+    pub localtime: Option<String>,
+    pub localtime_epoch: Option<i64>,
+}
+
+impl Location {
+    /// The UTC offset of the location, which is the difference between the local time and the
+    /// epoch time, rounded to 15 minutes as the local time has no seconds.
+    pub fn offset(&self) -> Option<FixedOffset> {
+        let local =
+            NaiveDateTime::parse_from_str(self.localtime.as_deref()?, "%Y-%m-%d %H:%M").ok()?;
+        let offset = local.and_utc().timestamp() - self.localtime_epoch?;
+        let offset = (offset as f64 / 900.0).round() as i32 * 900;
+        FixedOffset::east_opt(offset)
+    }
 }
 
 #[derive(Deserialize)]
 pub struct Current {
-    pub last_updated: String,
     pub temp_c: f32,
     pub condition: Condition,
+    /// Only returned with the `aqi=yes` parameter
+    pub air_quality: Option<CurrentAirQuality>,
+    #[serde(flatten)]
+    pub details: Details,
+}
+
+/// The details returned with the current weather and the hours.
+#[derive(Deserialize, Default)]
+pub struct Details {
+    pub humidity: Option<f32>,
+    pub wind_kph: Option<f32>,
+    pub pressure_mb: Option<f32>,
+    pub dewpoint_c: Option<f32>,
+    pub feelslike_c: Option<f32>,
+}
+
+impl Details {
+    /// Adds the returned details to the weather, the wind speed is converted to m/s.
+    pub fn apply(self, mut weather: Weather) -> Weather {
+        weather.humidity = self.humidity;
+        weather.wind_speed = self.wind_kph.map(|wind| wind / 3.6);
+        weather.pressure = self.pressure_mb;
+        weather.dew_point = self.dewpoint_c.and_then(|c| Temperature::from_c(c).ok());
+        weather.feels_like = self.feelslike_c.and_then(|c| Temperature::from_c(c).ok());
+        weather
+    }
+}
+
+/// The concentrations in μg/m³ with the indexes.
+#[derive(Deserialize)]
+pub struct CurrentAirQuality {
+    pub co: Option<f32>,
+    pub no2: Option<f32>,
+    pub o3: Option<f32>,
+    pub so2: Option<f32>,
+    pub pm2_5: Option<f32>,
+    pub pm10: Option<f32>,
+    #[serde(rename = "us-epa-index")]
+    pub us_epa_index: Option<u8>,
+    #[serde(rename = "gb-defra-index")]
+    pub gb_defra_index: Option<u8>,
+}
+
+impl From<CurrentAirQuality> for AirQuality {
+    fn from(value: CurrentAirQuality) -> Self {
+        AirQuality {
+            co: value.co,
+            no2: value.no2,
+            o3: value.o3,
+            so2: value.so2,
+            pm2_5: value.pm2_5,
+            pm10: value.pm10,
+            us_epa_index: value.us_epa_index,
+            gb_defra_index: value.gb_defra_index,
+        }
+    }
 }
 
 #[derive(Deserialize)]
 pub struct Condition {
     pub text: String,
-    pub icon: String,
     pub code: i64,
 }
 
+impl Condition {
+    /// Maps the WeatherAPI condition code to the normalized condition.
+    /// See https://www.weatherapi.com/docs/weather_conditions.json
+    pub fn condition_kind(&self) -> ConditionKind {
+        match self.code {
+            1000 => ConditionKind::Clear,
+            1003 => ConditionKind::PartlyCloudy,
+            1006 | 1009 => ConditionKind::Cloudy,
+            1030 | 1135 | 1147 => ConditionKind::Fog,
+            1150 | 1153 => ConditionKind::Drizzle,
+            1063 | 1180 | 1183 | 1186 | 1189 | 1240 => ConditionKind::Rain,
+            1192 | 1195 | 1243 | 1246 => ConditionKind::HeavyRain,
+            1069 | 1072 | 1168 | 1171 | 1198 | 1201 | 1204 | 1207 | 1237 | 1249 | 1252 | 1261
+            | 1264 => ConditionKind::Sleet,
+            1066 | 1114 | 1117 | 1210..=1225 | 1255 | 1258 => ConditionKind::Snow,
+            1087 | 1273..=1282 => ConditionKind::Thunderstorm,
+            _ => ConditionKind::Unknown,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct HistoryResponse {
-    pub location: Location,
     pub forecast: Forecast,
 }
 
 #[derive(Deserialize)]
 pub struct ForecastResponse {
-    pub location: Location,
     pub forecast: Forecast,
+    /// Only returned with the `alerts=yes` parameter
+    #[serde(default)]
+    pub alerts: Alerts,
+}
+
+#[derive(Deserialize, Default)]
+pub struct Alerts {
+    #[serde(default)]
+    pub alert: Vec<AlertItem>,
+}
+
+/// The missing values are often returned as empty strings.
+#[derive(Deserialize)]
+pub struct AlertItem {
+    pub headline: Option<String>,
+    pub severity: Option<String>,
+    pub areas: Option<String>,
+    pub event: Option<String>,
+    /// The start time in the RFC 3339 format, for example: 2021-01-05T21:47:00-05:00
+    pub effective: Option<String>,
+    pub expires: Option<String>,
+    pub desc: Option<String>,
+    pub instruction: Option<String>,
+}
+
+impl AlertItem {
+    /// Converts to the alert, the event falls back to the headline and the instruction follows
+    /// the description. WeatherAPI doesn't return the sender of the alerts.
+    pub fn into_alert(self) -> Alert {
+        let non_empty = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+        let time = |value: Option<String>| {
+            non_empty(value).and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+        };
+        let event = non_empty(self.event)
+            .or(non_empty(self.headline))
+            .unwrap_or_default();
+        let description = [non_empty(self.desc), non_empty(self.instruction)]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n");
+        Alert {
+            severity: non_empty(self.severity),
+            area: non_empty(self.areas),
+            start: time(self.effective),
+            end: time(self.expires),
+            ..Alert::new(event, description)
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -48,21 +184,80 @@ pub struct Forecast {
 #[derive(Deserialize)]
 pub struct ForecastDay {
     pub date: Option<String>,
-    pub date_epoch: Option<i32>,
     pub day: Day,
+    #[serde(default)]
+    pub hour: Vec<Hour>,
+}
+
+#[derive(Deserialize)]
+pub struct Hour {
+    pub time_epoch: i64,
+    /// The local time at the location, for example: 2023-02-06 17:00
+    pub time: String,
+    pub temp_c: f32,
+    pub condition: Condition,
+    #[serde(flatten)]
+    pub details: Details,
 }
 
 #[derive(Deserialize)]
 pub struct Day {
     pub avgtemp_c: f32,
     pub condition: Condition,
+    pub avghumidity: Option<f32>,
+    pub maxwind_kph: Option<f32>,
 }
 #[derive(Deserialize)]
 pub struct FutureResponse {
-    pub location: Location,
     pub forecast: Forecast,
 }
 
+#[derive(Deserialize)]
+pub struct AstronomyResponse {
+    pub location: Location,
+    pub astronomy: AstronomyData,
+}
+#[derive(Deserialize)]
+pub struct AstronomyData {
+    pub astro: Astro,
+}
+
+/// The times are local, like `05:33 AM`, or a text like `No moonrise` when the event doesn't
+/// happen that day.
+#[derive(Deserialize)]
+pub struct Astro {
+    pub sunrise: Option<String>,
+    pub sunset: Option<String>,
+    pub moonrise: Option<String>,
+    pub moonset: Option<String>,
+    pub moon_phase: Option<String>,
+    /// A number, returned as a string by the older API versions
+    pub moon_illumination: Option<serde_json::Value>,
+}
+
+impl Astro {
+    /// Converts to the astronomy of the date at the location with the offset.
+    pub fn into_astronomy(self, date: NaiveDate, offset: FixedOffset) -> Astronomy {
+        let time = |value: Option<String>| {
+            let time = NaiveTime::parse_from_str(value?.trim(), "%I:%M %p").ok()?;
+            offset.from_local_datetime(&date.and_time(time)).single()
+        };
+        let illumination = self.moon_illumination.and_then(|value| match value {
+            serde_json::Value::Number(number) => number.as_f64(),
+            serde_json::Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        });
+        Astronomy {
+            sunrise: time(self.sunrise),
+            sunset: time(self.sunset),
+            moonrise: time(self.moonrise),
+            moonset: time(self.moonset),
+            moon_phase: self.moon_phase.as_deref().and_then(MoonPhase::from_name),
+            moon_illumination: illumination.map(|value| value as f32),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ErrorResponse {
     pub error: Error,
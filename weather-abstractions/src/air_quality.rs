@@ -0,0 +1,128 @@
+/// The concentrations of the air pollutants in μg/m³ with the air quality indexes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AirQuality {
+    /// Carbon monoxide
+    pub co: Option<f32>,
+    /// Nitrogen dioxide
+    pub no2: Option<f32>,
+    /// Ozone
+    pub o3: Option<f32>,
+    /// Sulphur dioxide
+    pub so2: Option<f32>,
+    /// Fine particles, 2.5 μm or less in diameter
+    pub pm2_5: Option<f32>,
+    /// Particles, 10 μm or less in diameter
+    pub pm10: Option<f32>,
+    /// The US EPA index, from 1 (good) to 6 (hazardous)
+    pub us_epa_index: Option<u8>,
+    /// The UK DEFRA daily air quality index, from 1 (low) to 10 (very high)
+    pub gb_defra_index: Option<u8>,
+}
+
+/// The upper bounds of the US EPA index bands for PM2.5 and PM10, in μg/m³.
+const US_EPA_PM2_5: [f32; 5] = [12.0, 35.4, 55.4, 150.4, 250.4];
+const US_EPA_PM10: [f32; 5] = [54.0, 154.0, 254.0, 354.0, 424.0];
+/// The upper bounds of the UK DEFRA index bands for PM2.5 and PM10, in μg/m³.
+const GB_DEFRA_PM2_5: [f32; 9] = [11.0, 23.0, 35.0, 41.0, 47.0, 53.0, 58.0, 64.0, 70.0];
+const GB_DEFRA_PM10: [f32; 9] = [16.0, 33.0, 50.0, 58.0, 66.0, 75.0, 83.0, 91.0, 100.0];
+
+impl AirQuality {
+    /// Fills in the missing indexes from the PM2.5 and PM10 concentrations, the highest band of
+    /// the two is the index.
+    ///
+    /// The indexes are defined for the 24-hour means, so the index of a single measurement is an
+    /// approximation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weather_abstractions::AirQuality;
+    /// let air = AirQuality {
+    ///     pm2_5: Some(20.0),
+    ///     pm10: Some(30.0),
+    ///     ..Default::default()
+    /// }
+    /// .with_computed_indexes();
+    /// assert_eq!(air.us_epa_index, Some(2));
+    /// assert_eq!(air.gb_defra_index, Some(2));
+    /// ```
+    pub fn with_computed_indexes(mut self) -> Self {
+        let index = |pm2_5_bands: &[f32], pm10_bands: &[f32]| {
+            let band = |value: Option<f32>, bands: &[f32]| {
+                value
+                    .map(|value| bands.iter().take_while(|bound| value > **bound).count() as u8 + 1)
+            };
+            band(self.pm2_5, pm2_5_bands).max(band(self.pm10, pm10_bands))
+        };
+        self.us_epa_index = self.us_epa_index.or(index(&US_EPA_PM2_5, &US_EPA_PM10));
+        self.gb_defra_index = self
+            .gb_defra_index
+            .or(index(&GB_DEFRA_PM2_5, &GB_DEFRA_PM10));
+        self
+    }
+    /// The name of the US EPA index category, for example: Moderate.
+    pub fn us_epa_category(&self) -> Option<&'static str> {
+        match self.us_epa_index? {
+            1 => Some("Good"),
+            2 => Some("Moderate"),
+            3 => Some("Unhealthy for sensitive groups"),
+            4 => Some("Unhealthy"),
+            5 => Some("Very unhealthy"),
+            6 => Some("Hazardous"),
+            _ => None,
+        }
+    }
+    /// The name of the UK DEFRA index band, for example: Low.
+    pub fn gb_defra_band(&self) -> Option<&'static str> {
+        match self.gb_defra_index? {
+            1..=3 => Some("Low"),
+            4..=6 => Some("Moderate"),
+            7..=9 => Some("High"),
+            10 => Some("Very high"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Some(5.0), None, Some(1), Some(1))]
+    #[case(Some(12.0), Some(10.0), Some(1), Some(2))]
+    #[case(Some(12.1), None, Some(2), Some(2))]
+    #[case(Some(40.0), Some(10.0), Some(3), Some(4))]
+    #[case(Some(10.0), Some(160.0), Some(3), Some(10))]
+    #[case(Some(300.0), None, Some(6), Some(10))]
+    #[case(None, None, None, None)]
+    fn computed_indexes(
+        #[case] pm2_5: Option<f32>,
+        #[case] pm10: Option<f32>,
+        #[case] us_epa_index: Option<u8>,
+        #[case] gb_defra_index: Option<u8>,
+    ) {
+        let air = AirQuality {
+            pm2_5,
+            pm10,
+            ..Default::default()
+        }
+        .with_computed_indexes();
+        assert_eq!(air.us_epa_index, us_epa_index);
+        assert_eq!(air.gb_defra_index, gb_defra_index);
+    }
+
+    #[test]
+    fn provided_indexes_are_kept() {
+        let air = AirQuality {
+            pm2_5: Some(300.0),
+            us_epa_index: Some(2),
+            ..Default::default()
+        }
+        .with_computed_indexes();
+        assert_eq!(air.us_epa_index, Some(2));
+        assert_eq!(air.us_epa_category(), Some("Moderate"));
+        assert_eq!(air.gb_defra_band(), Some("Very high"));
+    }
+}
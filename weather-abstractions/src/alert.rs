@@ -0,0 +1,89 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+
+/// A severe weather alert issued for the area of a location, like a flood or a heat warning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// The type of the event, for example: Flood Warning
+    pub event: String,
+    /// The severity as given by the issuer, for example: Moderate, Severe or Extreme
+    pub severity: Option<String>,
+    /// The description of the affected area
+    pub area: Option<String>,
+    /// The time the alert starts to be in effect
+    pub start: Option<DateTime<FixedOffset>>,
+    /// The time the alert expires
+    pub end: Option<DateTime<FixedOffset>>,
+    /// The description of the event and the advice of the issuer
+    pub description: String,
+    /// The agency which issued the alert
+    pub sender: Option<String>,
+}
+
+impl Alert {
+    /// Creates an alert with the event and the description, the other details are unknown.
+    pub fn new(event: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            event: event.into(),
+            severity: None,
+            area: None,
+            start: None,
+            end: None,
+            description: description.into(),
+            sender: None,
+        }
+    }
+    /// Whether the alert is in effect at the time, an unknown start or end doesn't limit it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{DateTime, Utc};
+    /// use weather_abstractions::Alert;
+    /// let mut alert = Alert::new("Flood Warning", "River flooding is expected");
+    /// alert.end = DateTime::parse_from_rfc3339("2024-05-01T18:00:00+02:00").ok();
+    /// let before = "2024-05-01T15:59:00Z".parse::<DateTime<Utc>>().unwrap();
+    /// let after = "2024-05-01T16:00:00Z".parse::<DateTime<Utc>>().unwrap();
+    /// assert!(alert.is_active_at(before));
+    /// assert!(!alert.is_active_at(after));
+    /// ```
+    pub fn is_active_at(&self, time: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| start <= time) && self.end.is_none_or(|end| time < end)
+    }
+    /// Whether the alert is in effect for any part of the date, in the timezone of the alert.
+    pub fn is_active_on(&self, date: NaiveDate) -> bool {
+        self.start.is_none_or(|start| start.date_naive() <= date)
+            && self.end.is_none_or(|end| date <= end.date_naive())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn alert(start: Option<&str>, end: Option<&str>) -> Alert {
+        let time = |time: &str| DateTime::parse_from_rfc3339(time).expect("valid time");
+        Alert {
+            start: start.map(time),
+            end: end.map(time),
+            ..Alert::new("Heat Advisory", "Hot temperatures")
+        }
+    }
+
+    #[rstest]
+    #[case(alert(None, None), "2024-05-01", true)]
+    #[case(alert(Some("2024-05-01T22:00:00-05:00"), None), "2024-05-01", true)]
+    #[case(alert(Some("2024-05-02T08:00:00-05:00"), None), "2024-05-01", false)]
+    #[case(alert(None, Some("2024-05-01T06:00:00-05:00")), "2024-05-01", true)]
+    #[case(alert(None, Some("2024-04-30T23:00:00-05:00")), "2024-05-01", false)]
+    fn active_on_date(#[case] alert: Alert, #[case] date: NaiveDate, #[case] expected: bool) {
+        assert_eq!(alert.is_active_on(date), expected);
+    }
+
+    #[test]
+    fn not_active_before_start() {
+        let alert = alert(Some("2024-05-01T12:00:00+00:00"), None);
+        let time = "2024-05-01T11:00:00Z".parse().unwrap();
+        assert!(!alert.is_active_at(time));
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Duration, FixedOffset};
+
+/// The sun and moon events of a day, in the local time of the location.
+///
+/// An event is `None` when it doesn't happen that day, like the moonrise on some days or the
+/// sunset during the polar day, or when the provider doesn't return it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Astronomy {
+    pub sunrise: Option<DateTime<FixedOffset>>,
+    pub sunset: Option<DateTime<FixedOffset>>,
+    pub moonrise: Option<DateTime<FixedOffset>>,
+    pub moonset: Option<DateTime<FixedOffset>>,
+    pub moon_phase: Option<MoonPhase>,
+    /// The illuminated part of the moon disk in percent
+    pub moon_illumination: Option<f32>,
+}
+
+impl Astronomy {
+    /// The time between the sunrise and the sunset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{DateTime, Duration};
+    /// use weather_abstractions::Astronomy;
+    /// let astronomy = Astronomy {
+    ///     sunrise: DateTime::parse_from_rfc3339("2024-05-01T05:33:00+01:00").ok(),
+    ///     sunset: DateTime::parse_from_rfc3339("2024-05-01T20:39:00+01:00").ok(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(astronomy.day_length(), Some(Duration::minutes(15 * 60 + 6)));
+    /// ```
+    pub fn day_length(&self) -> Option<Duration> {
+        Some(self.sunset? - self.sunrise?)
+    }
+}
+
+/// The phase of the moon, by the eighths of the lunar cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoonPhase {
+    NewMoon,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    FullMoon,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl MoonPhase {
+    const ALL: [MoonPhase; 8] = [
+        MoonPhase::NewMoon,
+        MoonPhase::WaxingCrescent,
+        MoonPhase::FirstQuarter,
+        MoonPhase::WaxingGibbous,
+        MoonPhase::FullMoon,
+        MoonPhase::WaningGibbous,
+        MoonPhase::LastQuarter,
+        MoonPhase::WaningCrescent,
+    ];
+
+    /// Returns the phase for the part of the lunar cycle passed since the new moon, from 0 to 1.
+    /// The new moon, quarters and full moon cover a sixteenth of the cycle on either side.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weather_abstractions::MoonPhase;
+    /// assert_eq!(MoonPhase::from_cycle(0.97), MoonPhase::NewMoon);
+    /// assert_eq!(MoonPhase::from_cycle(0.5), MoonPhase::FullMoon);
+    /// assert_eq!(MoonPhase::from_cycle(0.6), MoonPhase::WaningGibbous);
+    /// ```
+    pub fn from_cycle(cycle: f32) -> Self {
+        let eighth = (cycle.rem_euclid(1.0) * 8.0).round() as usize % 8;
+        Self::ALL[eighth]
+    }
+    /// Returns the phase for its name, like `Waxing Gibbous`, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|phase| phase.to_string().eq_ignore_ascii_case(name))
+            .or_else(|| {
+                name.eq_ignore_ascii_case("Third Quarter")
+                    .then_some(Self::LastQuarter)
+            })
+    }
+}
+
+impl std::fmt::Display for MoonPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MoonPhase::NewMoon => "New Moon",
+            MoonPhase::WaxingCrescent => "Waxing Crescent",
+            MoonPhase::FirstQuarter => "First Quarter",
+            MoonPhase::WaxingGibbous => "Waxing Gibbous",
+            MoonPhase::FullMoon => "Full Moon",
+            MoonPhase::WaningGibbous => "Waning Gibbous",
+            MoonPhase::LastQuarter => "Last Quarter",
+            MoonPhase::WaningCrescent => "Waning Crescent",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0.0, MoonPhase::NewMoon)]
+    #[case(0.06, MoonPhase::NewMoon)]
+    #[case(0.1, MoonPhase::WaxingCrescent)]
+    #[case(0.25, MoonPhase::FirstQuarter)]
+    #[case(0.4, MoonPhase::WaxingGibbous)]
+    #[case(0.75, MoonPhase::LastQuarter)]
+    #[case(0.85, MoonPhase::WaningCrescent)]
+    #[case(1.0, MoonPhase::NewMoon)]
+    fn moon_phase_from_cycle(#[case] cycle: f32, #[case] expected: MoonPhase) {
+        assert_eq!(MoonPhase::from_cycle(cycle), expected);
+    }
+
+    #[rstest]
+    #[case("Waxing Gibbous", Some(MoonPhase::WaxingGibbous))]
+    #[case("full moon", Some(MoonPhase::FullMoon))]
+    #[case("Third Quarter", Some(MoonPhase::LastQuarter))]
+    #[case("Blue Moon", None)]
+    fn moon_phase_from_name(#[case] name: &str, #[case] expected: Option<MoonPhase>) {
+        assert_eq!(MoonPhase::from_name(name), expected);
+    }
+
+    #[test]
+    fn day_length_without_sunset() {
+        let astronomy = Astronomy {
+            sunrise: DateTime::parse_from_rfc3339("2024-06-21T01:00:00+03:00").ok(),
+            ..Default::default()
+        };
+        assert_eq!(astronomy.day_length(), None);
+    }
+}
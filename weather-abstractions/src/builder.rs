@@ -1,7 +1,7 @@
 use std::{collections::HashMap, error::Error};
 
 use crate::Error as ProviderError;
-use crate::WeatherProvider;
+use crate::{SettingDescriptor, WeatherProvider};
 use anyhow::anyhow;
 use std::collections::hash_map::Entry;
 type ProviderBuilder = Box<
@@ -17,6 +17,7 @@ type ProviderBuilder = Box<
 pub struct ProviderManagerBuilder {
     providers: HashMap<String, Box<dyn WeatherProvider>>,
     builders: HashMap<String, ProviderBuilder>,
+    schemas: HashMap<String, &'static [SettingDescriptor]>,
 }
 
 impl ProviderManagerBuilder {
@@ -64,6 +65,28 @@ impl ProviderManagerBuilder {
         self.builders.insert(name.into(), Box::new(builder));
         self
     }
+    /// Adds the descriptors of the settings supported by the provider.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the provider.
+    /// * `schema` - The descriptors of the provider settings.
+    ///
+    /// # Example
+    /// ```
+    /// let builder = ProviderManagerBuilder::default();
+    /// let new_builder = builder.add_settings_schema("MyWeatherProvider", &[
+    ///     SettingDescriptor::secret("apiKey", "The API key"),
+    /// ]);
+    /// ```
+    #[cfg(not(doctest))]
+    pub fn add_settings_schema(
+        mut self,
+        name: impl Into<String>,
+        schema: &'static [SettingDescriptor],
+    ) -> Self {
+        self.schemas.insert(name.into(), schema);
+        self
+    }
     /// Builds a `ProviderManager` instance from the `ProviderManagerBuilder`.
     ///
     /// # Example
@@ -78,6 +101,7 @@ impl ProviderManagerBuilder {
         ProviderManager {
             providers: self.providers,
             builders: self.builders,
+            schemas: self.schemas,
         }
     }
 }
@@ -87,6 +111,7 @@ impl ProviderManagerBuilder {
 pub struct ProviderManager {
     providers: HashMap<String, Box<dyn WeatherProvider>>,
     builders: HashMap<String, ProviderBuilder>,
+    schemas: HashMap<String, &'static [SettingDescriptor]>,
 }
 impl ProviderManager {
     /// This method returns a `Vec` of all the names of the providers, both pre-existing and newly built, stored in the `ProviderManager`.
@@ -95,6 +120,11 @@ impl ProviderManager {
         list.extend(self.builders.keys().map(|key| key.as_str()));
         list
     }
+    /// This method returns the descriptors of the settings supported by the provider with the given `provider_name`.
+    /// It returns an empty slice if the provider didn't register a settings schema.
+    pub fn get_settings_schema(&self, provider_name: &str) -> &'static [SettingDescriptor] {
+        self.schemas.get(provider_name).copied().unwrap_or_default()
+    }
     /// This method checks if a provider with the given `provider_name` is supported by the `ProviderManager`.
     /// It returns an `Ok` result with an unit if the provider is supported and a `Error::NotSupport` error if not.
     pub fn is_supported(&self, provider_name: &str) -> Result<(), ProviderError> {
@@ -106,6 +136,8 @@ impl ProviderManager {
     /// This method returns a reference to the provider with the given `name` if it exists, either as a pre-existing provider or as a newly built one.
     /// If the provider does not exist in either the `providers` or `builders` `HashMap`, it returns a `Error::NotSupport` error.
     pub fn get_provider(&mut self, name: &str) -> Result<&dyn WeatherProvider, ProviderError> {
+        let span = tracing::info_span!("select provider", provider = name, built = false);
+        let _entered = span.enter();
         let entry = self.providers.entry(name.to_string());
         let provider_ref = match entry {
             Entry::Occupied(e) => e.into_mut(),
@@ -113,12 +145,20 @@ impl ProviderManager {
                 let builder = self
                     .builders
                     .remove(name)
-                    .ok_or(ProviderError::NotSupport(name.to_string()))?;
-                let provider =
-                    (builder)().map_err(|e| ProviderError::Build(name.to_string(), anyhow!(e)))?;
+                    .ok_or(ProviderError::NotSupport(name.to_string()))
+                    .inspect_err(|e| tracing::error!("{e}"))?;
+                let provider = (builder)()
+                    .map_err(|e| ProviderError::Build(name.to_string(), anyhow!(e)))
+                    .inspect_err(|e| tracing::error!("{e}"))?;
+                span.record("built", true);
                 v.insert(provider)
             }
         };
         Ok(&**provider_ref)
     }
+    /// This method returns a reference to the provider with the given `name` if it was already built, without building it.
+    /// Unlike `get_provider` it takes a shared reference, so the built providers can be used by the concurrent requests.
+    pub fn get_built_provider(&self, name: &str) -> Option<&dyn WeatherProvider> {
+        self.providers.get(name).map(|provider| &**provider)
+    }
 }
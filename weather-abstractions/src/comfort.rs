@@ -0,0 +1,341 @@
+//! The comfort metrics derived from the temperature, humidity and wind.
+//!
+//! The temperatures are in Celsius, the relative humidity in percent, the absolute humidity in
+//! grams per cubic meter and the wind speed in meters per second. The functions return `None`
+//! outside the validity range of their formula.
+
+use crate::{Temperature, Units, Weather};
+
+/// The Magnus formula coefficients for water, valid from -45°C to 60°C.
+const MAGNUS_A: f32 = 17.62;
+const MAGNUS_B: f32 = 243.12;
+const MAGNUS_RANGE: std::ops::RangeInclusive<f32> = -45.0..=60.0;
+/// The heat index is defined from 80°F, below it the temperature is felt as is.
+const HEAT_INDEX_MIN_F: f32 = 80.0;
+/// The wind chill is defined up to 10°C and from 4.8 km/h of wind.
+const WIND_CHILL_MAX_C: f32 = 10.0;
+const WIND_CHILL_MIN_KMH: f32 = 4.8;
+/// The humidex is reported from 20°C.
+const HUMIDEX_MIN_C: f32 = 20.0;
+
+/// The saturation vapor pressure over water in hectopascals.
+fn saturation_vapor_pressure(temp: f32) -> f32 {
+    6.112 * (MAGNUS_A * temp / (MAGNUS_B + temp)).exp()
+}
+
+fn is_valid_humidity(humidity: f32) -> bool {
+    humidity > 0.0 && humidity <= 100.0
+}
+
+/// The dew point by the Magnus formula, valid from -45°C to 60°C.
+///
+/// # Examples
+///
+/// ```
+/// use weather_abstractions::dew_point;
+/// let point = dew_point(25.0, 60.0).unwrap();
+/// assert!((point - 16.7).abs() < 0.1);
+/// assert_eq!(dew_point(25.0, 0.0), None);
+/// ```
+pub fn dew_point(temp: f32, humidity: f32) -> Option<f32> {
+    if !MAGNUS_RANGE.contains(&temp) || !is_valid_humidity(humidity) {
+        return None;
+    }
+    let gamma = (humidity / 100.0).ln() + MAGNUS_A * temp / (MAGNUS_B + temp);
+    Some(MAGNUS_B * gamma / (MAGNUS_A - gamma))
+}
+
+/// The relative humidity from the dew point, the inverse of `dew_point`.
+pub fn relative_humidity(temp: f32, dew_point: f32) -> Option<f32> {
+    if !MAGNUS_RANGE.contains(&temp) || !MAGNUS_RANGE.contains(&dew_point) || dew_point > temp {
+        return None;
+    }
+    Some(100.0 * saturation_vapor_pressure(dew_point) / saturation_vapor_pressure(temp))
+}
+
+/// The mass of the water vapor in a cubic meter of air, valid from -45°C to 60°C.
+///
+/// # Examples
+///
+/// ```
+/// use weather_abstractions::absolute_humidity;
+/// let absolute = absolute_humidity(20.0, 50.0).unwrap();
+/// assert!((absolute - 8.6).abs() < 0.1);
+/// ```
+pub fn absolute_humidity(temp: f32, humidity: f32) -> Option<f32> {
+    if !MAGNUS_RANGE.contains(&temp) || !is_valid_humidity(humidity) {
+        return None;
+    }
+    let vapor_pressure = humidity / 100.0 * saturation_vapor_pressure(temp);
+    Some(216.7 * vapor_pressure / (273.15 + temp))
+}
+
+/// The relative humidity from the absolute humidity, the inverse of `absolute_humidity`.
+/// `None` when the air would be over saturated.
+pub fn relative_from_absolute_humidity(temp: f32, absolute: f32) -> Option<f32> {
+    if !MAGNUS_RANGE.contains(&temp) || absolute <= 0.0 {
+        return None;
+    }
+    let humidity = 100.0 * absolute * (273.15 + temp) / (216.7 * saturation_vapor_pressure(temp));
+    is_valid_humidity(humidity).then_some(humidity)
+}
+
+/// The heat index by the NWS algorithm: the Rothfusz regression with its adjustments for the
+/// low and high humidity, and the simple formula when the index is below 80°F. Defined from
+/// 26.7°C (80°F).
+///
+/// # Examples
+///
+/// ```
+/// use weather_abstractions::heat_index;
+/// // 90°F at 70% is 106°F in the NWS heat index chart
+/// let index = heat_index(32.22, 70.0).unwrap();
+/// assert!((index - 41.1).abs() < 0.3);
+/// assert_eq!(heat_index(20.0, 70.0), None);
+/// ```
+pub fn heat_index(temp: f32, humidity: f32) -> Option<f32> {
+    let t = temp * 1.8 + 32.0;
+    if t < HEAT_INDEX_MIN_F || !is_valid_humidity(humidity) {
+        return None;
+    }
+    let rh = humidity;
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let index = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut index = -42.379 + 2.049_015 * t + 10.143_331 * rh
+            - 0.224_755_4 * t * rh
+            - 0.006_837_83 * t * t
+            - 0.054_817_17 * rh * rh
+            + 0.001_228_74 * t * t * rh
+            + 0.000_852_82 * t * rh * rh
+            - 0.000_001_99 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            index += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+        }
+        index
+    };
+    Some((index - 32.0) / 1.8)
+}
+
+/// The wind chill by the formula of Environment Canada and the NWS, defined up to 10°C and
+/// from 4.8 km/h (1.33 m/s) of wind.
+///
+/// # Examples
+///
+/// ```
+/// use weather_abstractions::wind_chill;
+/// // -10°C with 20 km/h of wind is -17.9°C in the wind chill chart
+/// let chill = wind_chill(-10.0, 20.0 / 3.6).unwrap();
+/// assert!((chill + 17.9).abs() < 0.1);
+/// assert_eq!(wind_chill(15.0, 10.0), None);
+/// ```
+pub fn wind_chill(temp: f32, wind_speed: f32) -> Option<f32> {
+    let wind_kmh = wind_speed * 3.6;
+    if temp > WIND_CHILL_MAX_C || wind_kmh <= WIND_CHILL_MIN_KMH {
+        return None;
+    }
+    let wind_factor = wind_kmh.powf(0.16);
+    Some(13.12 + 0.6215 * temp - 11.37 * wind_factor + 0.3965 * temp * wind_factor)
+}
+
+/// The humidex of Environment Canada from the dew point, a number felt like degrees Celsius.
+/// Defined from 20°C.
+///
+/// # Examples
+///
+/// ```
+/// use weather_abstractions::humidex;
+/// // 30°C with the dew point of 15°C is 34 in the humidex table
+/// let humidex = humidex(30.0, 15.0).unwrap();
+/// assert!((humidex - 34.0).abs() < 0.5);
+/// ```
+pub fn humidex(temp: f32, dew_point: f32) -> Option<f32> {
+    if temp < HUMIDEX_MIN_C || dew_point > temp {
+        return None;
+    }
+    let vapor_pressure = 6.11 * (5417.753 * (1.0 / 273.16 - 1.0 / (273.15 + dew_point))).exp();
+    Some(temp + 0.5555 * (vapor_pressure - 10.0))
+}
+
+/// The apparent temperature of Steadman, as used by the Australian Bureau of Meteorology,
+/// without the solar radiation, valid from -45°C to 60°C.
+///
+/// # Examples
+///
+/// ```
+/// use weather_abstractions::apparent_temperature;
+/// let apparent = apparent_temperature(30.0, 50.0, 2.0).unwrap();
+/// assert!((apparent - 31.6).abs() < 0.1);
+/// ```
+pub fn apparent_temperature(temp: f32, humidity: f32, wind_speed: f32) -> Option<f32> {
+    if !MAGNUS_RANGE.contains(&temp) || !is_valid_humidity(humidity) || wind_speed < 0.0 {
+        return None;
+    }
+    let vapor_pressure = humidity / 100.0 * 6.105 * (17.27 * temp / (237.7 + temp)).exp();
+    Some(temp + 0.33 * vapor_pressure - 0.70 * wind_speed - 4.00)
+}
+
+/// The comfort metrics of the weather, the ones returned by the provider are preferred over the
+/// computed ones. A metric is `None` when its inputs are missing or out of its validity range.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comfort {
+    pub dew_point: Option<Temperature>,
+    /// In grams per cubic meter
+    pub absolute_humidity: Option<f32>,
+    pub heat_index: Option<Temperature>,
+    pub wind_chill: Option<Temperature>,
+    /// A number felt like degrees Celsius
+    pub humidex: Option<f32>,
+    pub apparent_temperature: Option<Temperature>,
+}
+
+impl Weather {
+    /// Returns the comfort metrics, computing those the provider doesn't supply from the
+    /// temperature, humidity and wind speed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weather_abstractions::{Temperature, Units, Weather};
+    /// let temp = Temperature::from_c(-10.0).unwrap();
+    /// let weather = Weather::current(temp, "Clear")
+    ///     .with_humidity(80.0)
+    ///     .with_wind_speed(5.0);
+    /// let comfort = weather.comfort();
+    /// assert!(comfort.wind_chill.is_some());
+    /// assert_eq!(comfort.heat_index, None);
+    /// ```
+    pub fn comfort(&self) -> Comfort {
+        let temp = self.temp.to_value(Units::Metric);
+        let celsius = |value: f32| Temperature::Celsius(value);
+        let dew_point = self
+            .dew_point
+            .map(|dew_point| dew_point.to_value(Units::Metric))
+            .or_else(|| dew_point(temp, self.humidity?));
+        Comfort {
+            dew_point: dew_point.map(celsius),
+            absolute_humidity: self
+                .humidity
+                .and_then(|humidity| absolute_humidity(temp, humidity)),
+            heat_index: self
+                .humidity
+                .and_then(|humidity| heat_index(temp, humidity))
+                .map(celsius),
+            wind_chill: self
+                .wind_speed
+                .and_then(|wind_speed| wind_chill(temp, wind_speed))
+                .map(celsius),
+            humidex: dew_point.and_then(|dew_point| humidex(temp, dew_point)),
+            apparent_temperature: self.feels_like.or_else(|| {
+                apparent_temperature(temp, self.humidity?, self.wind_speed?).map(celsius)
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn assert_close(actual: Option<f32>, expected: f32, tolerance: f32) {
+        let actual = actual.expect("the value is in the validity range");
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} is not {expected} ± {tolerance}"
+        );
+    }
+
+    #[rstest]
+    #[case(20.0, 50.0, 9.3)]
+    #[case(30.0, 80.0, 26.2)]
+    #[case(-10.0, 90.0, -11.4)]
+    fn dew_point_and_back(#[case] temp: f32, #[case] humidity: f32, #[case] expected: f32) {
+        let dew_point = dew_point(temp, humidity);
+        assert_close(dew_point, expected, 0.1);
+        assert_close(relative_humidity(temp, dew_point.unwrap()), humidity, 0.01);
+    }
+
+    #[test]
+    fn absolute_humidity_and_back() {
+        assert_close(absolute_humidity(30.0, 100.0), 30.4, 0.2);
+        assert_close(relative_from_absolute_humidity(30.0, 15.2), 50.0, 0.5);
+        assert_eq!(relative_from_absolute_humidity(30.0, 40.0), None);
+    }
+
+    // The values of the NWS heat index chart in Fahrenheit
+    #[rstest]
+    #[case(80.0, 40.0, 80.0)]
+    #[case(90.0, 50.0, 95.0)]
+    #[case(96.0, 65.0, 121.0)]
+    #[case(100.0, 40.0, 109.0)]
+    #[case(86.0, 90.0, 105.0)]
+    fn heat_index_matches_nws_chart(
+        #[case] fahrenheit: f32,
+        #[case] humidity: f32,
+        #[case] expected: f32,
+    ) {
+        let celsius = |fahrenheit: f32| (fahrenheit - 32.0) / 1.8;
+        assert_close(
+            heat_index(celsius(fahrenheit), humidity),
+            celsius(expected),
+            0.6,
+        );
+    }
+
+    #[test]
+    fn heat_index_only_when_warm() {
+        assert_eq!(heat_index(26.0, 90.0), None);
+        assert_eq!(heat_index(30.0, 0.0), None);
+    }
+
+    // The values of the Environment Canada wind chill chart
+    #[rstest]
+    #[case(0.0, 10.0, -3.3)]
+    #[case(-20.0, 30.0, -32.6)]
+    #[case(-40.0, 60.0, -64.2)]
+    fn wind_chill_matches_chart(#[case] temp: f32, #[case] wind_kmh: f32, #[case] expected: f32) {
+        assert_close(wind_chill(temp, wind_kmh / 3.6), expected, 0.2);
+    }
+
+    #[test]
+    fn wind_chill_only_when_cold_and_windy() {
+        assert_eq!(wind_chill(11.0, 10.0), None);
+        assert_eq!(wind_chill(0.0, 1.0), None);
+    }
+
+    #[test]
+    fn humidex_only_when_warm() {
+        assert_close(humidex(35.0, 25.0), 47.3, 0.1);
+        assert_eq!(humidex(15.0, 10.0), None);
+        assert_eq!(humidex(25.0, 26.0), None);
+    }
+
+    #[test]
+    fn comfort_prefers_provider_values() {
+        let weather = Weather::current(Temperature::Celsius(32.0), "Clear")
+            .with_humidity(60.0)
+            .with_wind_speed(3.0)
+            .with_dew_point(Temperature::Celsius(20.0))
+            .with_feels_like(Temperature::Celsius(37.0));
+        let comfort = weather.comfort();
+
+        assert_eq!(comfort.dew_point, Some(Temperature::Celsius(20.0)));
+        assert_eq!(
+            comfort.apparent_temperature,
+            Some(Temperature::Celsius(37.0))
+        );
+        assert_close(comfort.humidex, humidex(32.0, 20.0).unwrap(), 0.0);
+        assert!(comfort.heat_index.is_some());
+        assert_eq!(comfort.wind_chill, None);
+    }
+
+    #[test]
+    fn comfort_without_humidity_and_wind() {
+        let weather = Weather::current(Temperature::Celsius(32.0), "Clear");
+        assert_eq!(weather.comfort(), Comfort::default());
+    }
+}
@@ -0,0 +1,380 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, Weekday};
+use thiserror::Error;
+
+use crate::WeatherProvider;
+
+/// The accepted forms of the date, listed when the date can't be parsed.
+pub const DATE_FORMS: &str = "YYYY-MM-DD, an ISO week date like 2024-W18-3 or 2024-W18, \
+    today, tomorrow, yesterday, a number of days or weeks like +3d or -1w, \
+    a weekday name like friday, next monday or last sunday, \
+    optionally with a time of the day like 2024-05-01T15:00, tomorrow 15:00 or 15:00+02:00";
+
+/// A date given by the user, either exact or relative to the current date at the location.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use weather_abstractions::DateSpec;
+///
+/// let today = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(); // Wednesday
+/// let spec: DateSpec = "next monday".parse().unwrap();
+///
+/// assert_eq!(spec.resolve(today), NaiveDate::from_ymd_opt(2024, 5, 6).unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateSpec {
+    /// An exact calendar date
+    Date(NaiveDate),
+    /// The number of days after today, negative for the past days
+    Days(i64),
+    /// The weekday near today
+    Weekday(Weekday, WeekdayOffset),
+}
+
+/// Which occurrence of the weekday is meant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeekdayOffset {
+    /// Today or the upcoming one, for example: friday
+    This,
+    /// The upcoming one after today, for example: next friday
+    Next,
+    /// The previous one before today, for example: last friday
+    Last,
+}
+
+#[derive(Error, Debug, PartialEq)]
+#[error("Not valid date '{0}', expected {DATE_FORMS}")]
+pub struct DateSpecError(pub String);
+
+impl DateSpec {
+    /// Whether the date depends on the current date at the location.
+    pub fn is_relative(&self) -> bool {
+        !matches!(self, DateSpec::Date(_))
+    }
+    /// Returns the calendar date at the location, the current date at the location is requested
+    /// from the provider only for the relative dates.
+    pub async fn resolve_for(
+        self,
+        provider: &dyn WeatherProvider,
+        address: &str,
+    ) -> Result<NaiveDate, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        match self {
+            DateSpec::Date(date) => Ok(date),
+            _ => Ok(self.resolve(provider.local_date(address).await?)),
+        }
+    }
+    /// Returns the calendar date for the given current date.
+    pub fn resolve(self, today: NaiveDate) -> NaiveDate {
+        match self {
+            DateSpec::Date(date) => date,
+            DateSpec::Days(days) => today + Duration::days(days),
+            DateSpec::Weekday(weekday, offset) => {
+                let ahead = (i64::from(weekday.num_days_from_monday())
+                    - i64::from(today.weekday().num_days_from_monday()))
+                .rem_euclid(7);
+                let days = match offset {
+                    WeekdayOffset::This => ahead,
+                    WeekdayOffset::Next if ahead == 0 => 7,
+                    WeekdayOffset::Next => ahead,
+                    WeekdayOffset::Last if ahead == 0 => -7,
+                    WeekdayOffset::Last => ahead - 7,
+                };
+                today + Duration::days(days)
+            }
+        }
+    }
+}
+
+impl FromStr for DateSpec {
+    type Err = DateSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_lowercase();
+        let error = || DateSpecError(s.to_string());
+        if let Ok(date) = value.parse::<NaiveDate>() {
+            return Ok(DateSpec::Date(date));
+        }
+        if let Some(date) = parse_iso_week_date(&value) {
+            return Ok(DateSpec::Date(date));
+        }
+        match value.as_str() {
+            "today" => return Ok(DateSpec::Days(0)),
+            "tomorrow" => return Ok(DateSpec::Days(1)),
+            "yesterday" => return Ok(DateSpec::Days(-1)),
+            _ => {}
+        }
+        if let Some(sign) = value.chars().next().filter(|c| *c == '+' || *c == '-') {
+            let rest = &value[1..];
+            let unit = rest.chars().last().ok_or_else(error)?;
+            let number = &rest[..rest.len() - unit.len_utf8()];
+            let number: i64 = number.parse::<u16>().map_err(|_| error())?.into();
+            let days = match unit {
+                'd' => number,
+                'w' => number * 7,
+                _ => return Err(error()),
+            };
+            return Ok(DateSpec::Days(if sign == '-' { -days } else { days }));
+        }
+        let words: Vec<&str> = value.split_whitespace().collect();
+        let (offset, weekday) = match words.as_slice() {
+            [weekday] => (WeekdayOffset::This, weekday),
+            ["next", weekday] => (WeekdayOffset::Next, weekday),
+            ["last", weekday] => (WeekdayOffset::Last, weekday),
+            _ => return Err(error()),
+        };
+        let weekday = weekday.parse::<Weekday>().map_err(|_| error())?;
+        Ok(DateSpec::Weekday(weekday, offset))
+    }
+}
+
+/// The time of the day requested with the date, in the local time of the location unless the UTC
+/// offset is given.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{FixedOffset, NaiveTime};
+/// use weather_abstractions::TimeOfDay;
+///
+/// let time: TimeOfDay = "15:00+02:00".parse().unwrap();
+///
+/// assert_eq!(time.time, NaiveTime::from_hms_opt(15, 0, 0).unwrap());
+/// assert_eq!(time.offset, FixedOffset::east_opt(2 * 3600));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeOfDay {
+    /// The time of the day
+    pub time: NaiveTime,
+    /// The UTC offset of the time, the local time of the location if not given
+    pub offset: Option<FixedOffset>,
+}
+
+impl TimeOfDay {
+    /// Returns the UTC timestamp of the time on the date, the offset of the location is used
+    /// when the time has no own offset.
+    pub fn timestamp(self, date: NaiveDate, location_offset: FixedOffset) -> i64 {
+        let offset = self.offset.unwrap_or(location_offset);
+        date.and_time(self.time).and_utc().timestamp() - i64::from(offset.local_minus_utc())
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = DateSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || DateSpecError(s.to_string());
+        let value = s.trim();
+        let (time, offset) = match value.strip_suffix(['z', 'Z']) {
+            Some(time) => (time, Some(FixedOffset::east_opt(0).expect("UTC offset"))),
+            None => match value.find(['+', '-']) {
+                Some(i) => (
+                    &value[..i],
+                    Some(parse_offset(&value[i..]).ok_or_else(error)?),
+                ),
+                None => (value, None),
+            },
+        };
+        let time = NaiveTime::parse_from_str(time, "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+            .map_err(|_| error())?;
+        Ok(TimeOfDay { time, offset })
+    }
+}
+
+/// A date with an optional time of the day given by the user.
+///
+/// The time follows the date after `T`, like `2024-05-01T15:00`, or is separated by a space
+/// on either side of the date, like `tomorrow 15:00` or `15:00 friday`. A time alone is today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTimeSpec {
+    /// The date, exact or relative
+    pub date: DateSpec,
+    /// The time of the day, the whole day if not given
+    pub time: Option<TimeOfDay>,
+}
+
+impl FromStr for DateTimeSpec {
+    type Err = DateSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || DateSpecError(s.to_string());
+        let value = s.trim();
+        if let Some((date, time)) = value.split_once('T') {
+            if let (Ok(date), Ok(time)) = (date.parse(), time.parse()) {
+                return Ok(DateTimeSpec {
+                    date,
+                    time: Some(time),
+                });
+            }
+        }
+        let words: Vec<&str> = value.split_whitespace().collect();
+        let time_at = |i: usize| words.get(i).and_then(|word| word.parse::<TimeOfDay>().ok());
+        let (date, time) = match (time_at(0), words.len().checked_sub(1).and_then(time_at)) {
+            (Some(time), _) => (words[1..].join(" "), Some(time)),
+            (None, Some(time)) => (words[..words.len() - 1].join(" "), Some(time)),
+            (None, None) => (value.to_string(), None),
+        };
+        let date = match date.as_str() {
+            "" if time.is_some() => DateSpec::Days(0),
+            date => date.parse().map_err(|_| error())?,
+        };
+        Ok(DateTimeSpec { date, time })
+    }
+}
+
+fn parse_offset(value: &str) -> Option<FixedOffset> {
+    let sign = if value.starts_with('-') { -1 } else { 1 };
+    let digits: String = value[1..].chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Parses the ISO 8601 week date, `2024-w18-3`, or the week, `2024-w18`, which is its Monday.
+fn parse_iso_week_date(value: &str) -> Option<NaiveDate> {
+    let mut parts = value.split('-');
+    let year = parts.next()?.parse().ok()?;
+    let week = parts.next()?.strip_prefix('w')?.parse().ok()?;
+    let weekday = match parts.next() {
+        Some(day) => Weekday::try_from(day.parse::<u8>().ok()?.checked_sub(1)?).ok()?,
+        None => Weekday::Mon,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    NaiveDate::from_isoywd_opt(year, week, weekday)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[rstest]
+    #[case("2024-05-01", date(2024, 5, 1))]
+    #[case("2024-W18-3", date(2024, 5, 1))]
+    #[case("2024-w18", date(2024, 4, 29))]
+    #[case("2020-W53-7", date(2021, 1, 3))]
+    #[case("today", date(2024, 5, 1))]
+    #[case(" Tomorrow ", date(2024, 5, 2))]
+    #[case("yesterday", date(2024, 4, 30))]
+    #[case("+3d", date(2024, 5, 4))]
+    #[case("-1w", date(2024, 4, 24))]
+    #[case("+0d", date(2024, 5, 1))]
+    #[case("wednesday", date(2024, 5, 1))]
+    #[case("friday", date(2024, 5, 3))]
+    #[case("Mon", date(2024, 5, 6))]
+    #[case("next wednesday", date(2024, 5, 8))]
+    #[case("next monday", date(2024, 5, 6))]
+    #[case("last wednesday", date(2024, 4, 24))]
+    #[case("last friday", date(2024, 4, 26))]
+    fn resolve_date_spec(#[case] value: &str, #[case] expected: NaiveDate) {
+        let today = date(2024, 5, 1);
+        let spec: DateSpec = value
+            .parse()
+            .unwrap_or_else(|_| panic!("'{value}' should be parsed"));
+        assert_eq!(spec.resolve(today), expected);
+    }
+
+    #[rstest]
+    #[case("2024-13-01")]
+    #[case("2024-W54-1")]
+    #[case("2024-W18-8")]
+    #[case("3d")]
+    #[case("+3m")]
+    #[case("+d")]
+    #[case("+3д")]
+    #[case("-99999d")]
+    #[case("next")]
+    #[case("next year")]
+    #[case("this friday evening")]
+    #[case("")]
+    fn parse_date_spec_err(#[case] value: &str) {
+        let error = value.parse::<DateSpec>().expect_err("should be not valid");
+        assert_eq!(error, DateSpecError(value.to_string()));
+        assert!(error.to_string().contains("next monday"));
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[rstest]
+    #[case("2024-05-01T15:00", date(2024, 5, 1), time(15, 0), None)]
+    #[case("2024-05-01T15:00Z", date(2024, 5, 1), time(15, 0), Some(0))]
+    #[case("2024-05-01T09:30:00+02:00", date(2024, 5, 1), time(9, 30), Some(7200))]
+    #[case("2024-W18-3T15:00-0530", date(2024, 5, 1), time(15, 0), Some(-19800))]
+    #[case("tomorrow 15:00", date(2024, 5, 2), time(15, 0), None)]
+    #[case("15:00 next monday", date(2024, 5, 6), time(15, 0), None)]
+    #[case("Tuesday 7:05+03", date(2024, 5, 7), time(7, 5), Some(10800))]
+    #[case("15:00", date(2024, 5, 1), time(15, 0), None)]
+    fn resolve_date_time_spec(
+        #[case] value: &str,
+        #[case] expected_date: NaiveDate,
+        #[case] expected_time: NaiveTime,
+        #[case] expected_offset: Option<i32>,
+    ) {
+        let spec: DateTimeSpec = value
+            .parse()
+            .unwrap_or_else(|_| panic!("'{value}' should be parsed"));
+        let time = spec.time.expect("time of the day");
+        assert_eq!(spec.date.resolve(date(2024, 5, 1)), expected_date);
+        assert_eq!(time.time, expected_time);
+        assert_eq!(time.offset, expected_offset.and_then(FixedOffset::east_opt));
+    }
+
+    #[rstest]
+    #[case("2024-05-01T25:00")]
+    #[case("2024-05-01T15:00+2")]
+    #[case("2024-05-01T15:00+02:75")]
+    #[case("tomorrow at 15:00")]
+    #[case("15:00 16:00")]
+    fn parse_date_time_spec_err(#[case] value: &str) {
+        let error = value
+            .parse::<DateTimeSpec>()
+            .expect_err("should be not valid");
+        assert_eq!(error, DateSpecError(value.to_string()));
+    }
+
+    #[test]
+    fn date_time_spec_without_time() {
+        let spec: DateTimeSpec = "next friday".parse().unwrap();
+        assert_eq!(spec.time, None);
+        assert_eq!(spec.date.resolve(date(2024, 5, 1)), date(2024, 5, 3));
+    }
+
+    #[test]
+    fn time_of_day_timestamp() {
+        let local: TimeOfDay = "15:00".parse().unwrap();
+        let utc: TimeOfDay = "15:00Z".parse().unwrap();
+        let kyiv = FixedOffset::east_opt(3 * 3600).unwrap();
+        // 2024-05-01 12:00 UTC
+        assert_eq!(local.timestamp(date(2024, 5, 1), kyiv), 1714564800);
+        assert_eq!(utc.timestamp(date(2024, 5, 1), kyiv), 1714575600);
+    }
+
+    #[test]
+    fn only_exact_date_is_not_relative() {
+        assert!(!DateSpec::Date(date(2024, 5, 1)).is_relative());
+        assert!(DateSpec::Days(0).is_relative());
+    }
+}
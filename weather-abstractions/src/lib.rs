@@ -1,16 +1,30 @@
+mod air_quality;
+mod alert;
+mod astronomy;
 mod builder;
+mod comfort;
+mod date_spec;
 mod models;
+mod schema;
+mod solar;
 pub mod utils;
 use std::error::Error as StdError;
 
+pub use air_quality::*;
+pub use alert::*;
+pub use astronomy::*;
 pub use builder::*;
-use chrono::NaiveDate;
+use chrono::{Duration, NaiveDate, Utc};
+pub use comfort::*;
+pub use date_spec::*;
 pub use models::*;
+pub use schema::*;
+pub use solar::*;
 
 use async_trait::async_trait;
 /// This trait defines the interface for a Weather Provider.
 #[async_trait]
-pub trait WeatherProvider {
+pub trait WeatherProvider: Send + Sync {
     /// This method returns weather information for a given location and date.
     ///
     /// # Arguments
@@ -26,6 +40,98 @@ pub trait WeatherProvider {
         address: &str,
         date: Option<NaiveDate>,
     ) -> Result<Weather, Box<dyn StdError + Send + Sync + 'static>>;
+    /// This method returns the weather for the time of the day at the location, taken from the
+    /// hourly slot closest to the time, with the time of the slot in `Weather::time`.
+    ///
+    /// The default implementation, for the providers without the hourly data, returns the
+    /// weather for the whole day without the time.
+    async fn get_weather_at(
+        &self,
+        address: &str,
+        date: NaiveDate,
+        _time: TimeOfDay,
+    ) -> Result<Weather, Box<dyn StdError + Send + Sync + 'static>> {
+        self.get_weather(address, Some(date)).await
+    }
+    /// This method returns the weather for every day of the range, both dates included.
+    ///
+    /// The default implementation requests the days one by one with `get_weather`, the providers
+    /// able to return several days in a single request override it.
+    ///
+    /// # Returns
+    ///
+    /// The weather with its date for every day of the range, ordered by the date.
+    async fn get_weather_range(
+        &self,
+        address: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Weather)>, Box<dyn StdError + Send + Sync + 'static>> {
+        let mut days = Vec::new();
+        for date in from.iter_days().take_while(|date| *date <= to) {
+            days.push((date, self.get_weather(address, Some(date)).await?));
+        }
+        Ok(days)
+    }
+    /// This method returns the current date at the location, used to resolve the relative dates
+    /// like tomorrow.
+    ///
+    /// The default implementation estimates the date with `estimated_local_date`.
+    async fn local_date(
+        &self,
+        address: &str,
+    ) -> Result<NaiveDate, Box<dyn StdError + Send + Sync + 'static>> {
+        Ok(estimated_local_date(address))
+    }
+    /// This method returns the severe weather alerts issued for the location which have not
+    /// expired yet.
+    ///
+    /// The default implementation, for the providers without the alerts, returns
+    /// `Error::NotSupportedFeature`.
+    async fn get_alerts(
+        &self,
+        _address: &str,
+    ) -> Result<Vec<Alert>, Box<dyn StdError + Send + Sync + 'static>> {
+        Err(Box::new(Error::NotSupportedFeature("alerts")))
+    }
+    /// This method returns the current air quality at the location.
+    ///
+    /// The default implementation, for the providers without the air quality, returns
+    /// `Error::NotSupportedFeature`.
+    async fn get_air_quality(
+        &self,
+        _address: &str,
+    ) -> Result<AirQuality, Box<dyn StdError + Send + Sync + 'static>> {
+        Err(Box::new(Error::NotSupportedFeature("air quality")))
+    }
+    /// This method returns the sun and moon events of the date at the location.
+    ///
+    /// The default implementation, for the providers without the astronomy data, returns
+    /// `Error::NotSupportedFeature`.
+    async fn get_astronomy(
+        &self,
+        _address: &str,
+        _date: NaiveDate,
+    ) -> Result<Astronomy, Box<dyn StdError + Send + Sync + 'static>> {
+        Err(Box::new(Error::NotSupportedFeature("astronomy")))
+    }
+    /// This method performs a cheap request to the provider to check its settings, like the API key.
+    ///
+    /// The default implementation requests the current weather in London.
+    async fn verify(&self) -> Result<(), Box<dyn StdError + Send + Sync + 'static>> {
+        self.get_weather("London", None).await.map(|_| ())
+    }
+}
+
+/// Estimates the current date at the location from the longitude for the coordinates, by the
+/// local mean time, and returns the UTC date for the addresses.
+pub fn estimated_local_date(address: &str) -> NaiveDate {
+    let now = Utc::now();
+    let date = match address.parse::<Coordinates>() {
+        Ok(coordinates) => now + Duration::minutes((coordinates.lon * 4.0).round() as i64),
+        Err(_) => now,
+    };
+    date.date_naive()
 }
 
 use thiserror::Error;
@@ -35,4 +141,6 @@ pub enum Error {
     Build(String, anyhow::Error),
     #[error("Not spported provider {0}")]
     NotSupport(String),
+    #[error("The provider doesn't support the {0}")]
+    NotSupportedFeature(&'static str),
 }
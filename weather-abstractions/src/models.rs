@@ -1,13 +1,33 @@
+use crate::{AirQuality, Alert};
+use chrono::{DateTime, FixedOffset};
 use thiserror::Error;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 /// Struct that contains information about the weather at a certain point in time.
 pub struct Weather {
     pub kind: WeatherKind,
     pub temp: Temperature,
     pub condition: String,
+    pub condition_kind: ConditionKind,
+    /// The time of the hourly slot the weather is for, `None` for the current weather and the daily values
+    pub time: Option<DateTime<FixedOffset>>,
+    /// The severe weather alerts in effect, only filled in by the providers which return them
+    /// with the weather
+    pub alerts: Vec<Alert>,
+    /// The air quality, only filled in when it was requested with the weather
+    pub air_quality: Option<AirQuality>,
+    /// The relative humidity in percent
+    pub humidity: Option<f32>,
+    /// The wind speed in meters per second
+    pub wind_speed: Option<f32>,
+    /// The sea level pressure in hectopascals
+    pub pressure: Option<f32>,
+    /// The dew point, only filled in by the providers which return it, see `Weather::comfort`
+    pub dew_point: Option<Temperature>,
+    /// The apparent temperature, only filled in by the providers which return it
+    pub feels_like: Option<Temperature>,
 }
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// Enum that contains the different kinds of weather information available.
 pub enum WeatherKind {
     History,
@@ -15,6 +35,75 @@ pub enum WeatherKind {
     Forecast,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Enum that contains the normalized weather conditions, independent of the provider wording.
+pub enum ConditionKind {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    HeavyRain,
+    Thunderstorm,
+    Snow,
+    Sleet,
+    Unknown,
+}
+
+impl ConditionKind {
+    /// Whether it rains, including the drizzle and the thunderstorm.
+    pub fn is_rain(self) -> bool {
+        matches!(
+            self,
+            ConditionKind::Drizzle
+                | ConditionKind::Rain
+                | ConditionKind::HeavyRain
+                | ConditionKind::Thunderstorm
+        )
+    }
+    /// Guesses the normalized condition from a free-form condition description.
+    ///
+    /// Providers that expose condition codes should prefer mapping the codes, this function is
+    /// the fallback for plain text descriptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weather_abstractions::ConditionKind;
+    ///
+    /// assert_eq!(ConditionKind::from_description("Patchy light rain"), ConditionKind::Rain);
+    /// assert_eq!(ConditionKind::from_description("Sunny"), ConditionKind::Clear);
+    /// ```
+    pub fn from_description(description: &str) -> Self {
+        let text = description.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| text.contains(w));
+        if has(&["thunder", "storm"]) {
+            ConditionKind::Thunderstorm
+        } else if has(&["sleet", "ice pellets", "freezing"]) {
+            ConditionKind::Sleet
+        } else if has(&["snow", "blizzard"]) {
+            ConditionKind::Snow
+        } else if has(&["heavy rain", "torrential"]) {
+            ConditionKind::HeavyRain
+        } else if has(&["drizzle"]) {
+            ConditionKind::Drizzle
+        } else if has(&["rain", "shower"]) {
+            ConditionKind::Rain
+        } else if has(&["fog", "mist", "haze", "smoke", "dust", "sand"]) {
+            ConditionKind::Fog
+        } else if has(&["partly", "few clouds", "scattered"]) {
+            ConditionKind::PartlyCloudy
+        } else if has(&["cloud", "overcast"]) {
+            ConditionKind::Cloudy
+        } else if has(&["clear", "sunny", "fair"]) {
+            ConditionKind::Clear
+        } else {
+            ConditionKind::Unknown
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 /// Enum that represents a temperature in different scales.
 pub enum Temperature {
@@ -33,6 +122,64 @@ pub enum Units {
     SI,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Struct that represents a geographic location in decimal degrees.
+pub struct Coordinates {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CoordinatesError {
+    #[error("Coordinates '{0}' must be in the 'latitude,longitude' format")]
+    Format(String),
+    #[error("The latitude {0} must be between -90 and 90")]
+    Latitude(f64),
+    #[error("The longitude {0} must be between -180 and 180")]
+    Longitude(f64),
+}
+
+impl Coordinates {
+    /// Creates a `Coordinates` instance, checking the latitude and longitude ranges.
+    pub fn new(lat: f64, lon: f64) -> Result<Self, CoordinatesError> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(CoordinatesError::Latitude(lat));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(CoordinatesError::Longitude(lon));
+        }
+        Ok(Self { lat, lon })
+    }
+}
+
+impl std::str::FromStr for Coordinates {
+    type Err = CoordinatesError;
+    /// Parses coordinates in the `latitude,longitude` format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weather_abstractions::Coordinates;
+    ///
+    /// let kyiv: Coordinates = "50.45, 30.52".parse().unwrap();
+    /// assert_eq!(kyiv, Coordinates { lat: 50.45, lon: 30.52 });
+    /// assert!("Kyiv, UA".parse::<Coordinates>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format_error = || CoordinatesError::Format(s.to_string());
+        let (lat, lon) = s.split_once(',').ok_or_else(format_error)?;
+        let lat = lat.trim().parse().map_err(|_| format_error())?;
+        let lon = lon.trim().parse().map_err(|_| format_error())?;
+        Self::new(lat, lon)
+    }
+}
+
+impl std::fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.lat, self.lon)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum TemperatureError {
     #[error("The Kelvin temperature scale {0} must be greater than 0°K")]
@@ -78,6 +225,30 @@ impl Temperature {
         }
         Ok(Temperature::Kelvin(kelvin))
     }
+    /// Converts the temperature value to the desired units.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weather_abstractions::{Temperature, Units};
+    ///
+    /// let temperature = Temperature::Celsius(100.0);
+    ///
+    /// assert_eq!(temperature.to_value(Units::Imperial), 212.0);
+    /// ```
+    pub fn to_value(self, units: Units) -> f32 {
+        match (self, units) {
+            (Temperature::Kelvin(k), Units::Imperial) => (k - K_ZERO_C) * 9.0 / 5.0 + 32.0,
+            (Temperature::Kelvin(k), Units::Metric) => k - K_ZERO_C,
+            (Temperature::Kelvin(k), Units::SI) => k,
+            (Temperature::Celsius(c), Units::Imperial) => (c * 1.8) + 32.0,
+            (Temperature::Celsius(c), Units::Metric) => c,
+            (Temperature::Celsius(c), Units::SI) => c + K_ZERO_C,
+            (Temperature::Fahrenheit(f), Units::Imperial) => f,
+            (Temperature::Fahrenheit(f), Units::Metric) => (f - 32.0) * 5.0 / 9.0,
+            (Temperature::Fahrenheit(f), Units::SI) => (f + K_ZERO_F) * 5.0 / 9.0,
+        }
+    }
     /// Converts the temperature value to a string representation based on the desired units.
     ///
     /// # Arguments
@@ -99,45 +270,12 @@ impl Temperature {
     /// assert_eq!(string_value, "0.0°C");
     /// ```
     pub fn to_string_value(self, units: Units) -> String {
-        match self {
-            Temperature::Kelvin(k) => match units {
-                Units::Imperial => {
-                    let f = (k - K_ZERO_C) * 9.0 / 5.0 + 32.0;
-                    format!("{:.1}°F", f)
-                }
-                Units::Metric => {
-                    let c = k - K_ZERO_C;
-                    format!("{:.1}°C", c)
-                }
-                Units::SI => format!("{:.1}°K", k),
-            },
-            Temperature::Celsius(c) => match units {
-                Units::Imperial => {
-                    let f = (c * 1.8) + 32.0;
-                    format!("{:.1}°F", f)
-                }
-                Units::Metric => {
-                    format!("{:.1}°C", c)
-                }
-                Units::SI => {
-                    let k = c + K_ZERO_C;
-                    format!("{:.1}°K", k)
-                }
-            },
-            Temperature::Fahrenheit(f) => match units {
-                Units::Imperial => {
-                    format!("{:.1}°F", f)
-                }
-                Units::Metric => {
-                    let c = (f - 32.0) * 5.0 / 9.0;
-                    format!("{:.1}°C", c)
-                }
-                Units::SI => {
-                    let k = (f + K_ZERO_F) * 5.0 / 9.0;
-                    format!("{:.1}°K", k)
-                }
-            },
-        }
+        let symbol = match units {
+            Units::Imperial => "°F",
+            Units::Metric => "°C",
+            Units::SI => "°K",
+        };
+        format!("{:.1}{symbol}", self.to_value(units))
     }
 }
 impl Weather {
@@ -154,10 +292,20 @@ impl Weather {
     /// assert_eq!(weather.condition, "Sunny");
     /// ```
     pub fn history(temp: Temperature, condition: impl Into<String>) -> Self {
+        let condition = condition.into();
         Self {
             kind: WeatherKind::History,
             temp,
-            condition: condition.into(),
+            condition_kind: ConditionKind::from_description(&condition),
+            condition,
+            time: None,
+            alerts: Vec::new(),
+            air_quality: None,
+            humidity: None,
+            wind_speed: None,
+            pressure: None,
+            dew_point: None,
+            feels_like: None,
         }
     }
     /// Creates a new `Weather` instance with type `WeatherKind::Current` and specified temperature and weather condition.
@@ -173,10 +321,20 @@ impl Weather {
     /// assert_eq!(weather.condition, "Sunny");
     /// ```
     pub fn current(temp: Temperature, condition: impl Into<String>) -> Self {
+        let condition = condition.into();
         Self {
             kind: WeatherKind::Current,
             temp,
-            condition: condition.into(),
+            condition_kind: ConditionKind::from_description(&condition),
+            condition,
+            time: None,
+            alerts: Vec::new(),
+            air_quality: None,
+            humidity: None,
+            wind_speed: None,
+            pressure: None,
+            dew_point: None,
+            feels_like: None,
         }
     }
     /// Creates a new `Weather` instance with type `WeatherKind::Forecast` and specified temperature and weather condition.
@@ -192,12 +350,77 @@ impl Weather {
     /// assert_eq!(weather.condition, "Sunny");
     /// ```
     pub fn forecast(temp: Temperature, condition: impl Into<String>) -> Self {
+        let condition = condition.into();
         Self {
             kind: WeatherKind::Forecast,
             temp,
-            condition: condition.into(),
+            condition_kind: ConditionKind::from_description(&condition),
+            condition,
+            time: None,
+            alerts: Vec::new(),
+            air_quality: None,
+            humidity: None,
+            wind_speed: None,
+            pressure: None,
+            dew_point: None,
+            feels_like: None,
         }
     }
+    /// Replaces the normalized condition guessed from the description,
+    /// used by providers which expose their own condition codes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weather_abstractions::{ConditionKind, Temperature, Weather};
+    /// let temp = Temperature::from_c(22.0).unwrap();
+    /// let weather = Weather::current(temp, "Mist").with_condition_kind(ConditionKind::Fog);
+    /// assert_eq!(weather.condition_kind, ConditionKind::Fog);
+    /// ```
+    pub fn with_condition_kind(mut self, condition_kind: ConditionKind) -> Self {
+        self.condition_kind = condition_kind;
+        self
+    }
+    /// Sets the time of the hourly slot the weather is for, in the local time of the location.
+    pub fn with_time(mut self, time: DateTime<FixedOffset>) -> Self {
+        self.time = Some(time);
+        self
+    }
+    /// Sets the severe weather alerts in effect for the weather.
+    pub fn with_alerts(mut self, alerts: Vec<Alert>) -> Self {
+        self.alerts = alerts;
+        self
+    }
+    /// Sets the air quality at the time of the weather.
+    pub fn with_air_quality(mut self, air_quality: AirQuality) -> Self {
+        self.air_quality = Some(air_quality);
+        self
+    }
+    /// Sets the relative humidity in percent.
+    pub fn with_humidity(mut self, humidity: f32) -> Self {
+        self.humidity = Some(humidity);
+        self
+    }
+    /// Sets the wind speed in meters per second.
+    pub fn with_wind_speed(mut self, wind_speed: f32) -> Self {
+        self.wind_speed = Some(wind_speed);
+        self
+    }
+    /// Sets the sea level pressure in hectopascals.
+    pub fn with_pressure(mut self, pressure: f32) -> Self {
+        self.pressure = Some(pressure);
+        self
+    }
+    /// Sets the dew point returned by the provider.
+    pub fn with_dew_point(mut self, dew_point: Temperature) -> Self {
+        self.dew_point = Some(dew_point);
+        self
+    }
+    /// Sets the apparent temperature returned by the provider.
+    pub fn with_feels_like(mut self, feels_like: Temperature) -> Self {
+        self.feels_like = Some(feels_like);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -220,7 +443,7 @@ mod tests {
     #[case(20.0)]
     #[case(f32::MAX)]
     fn test_temperature_from_c_positive(#[case] val: f32) {
-        Temperature::from_c(val).expect(&format!("{} valid value for Celcius", val));
+        Temperature::from_c(val).unwrap_or_else(|_| panic!("{} valid value for Celcius", val));
     }
     #[rstest]
     #[case(-0.001)]
@@ -234,7 +457,7 @@ mod tests {
     #[case(373.15)]
     #[case(f32::MAX)]
     fn test_temperature_from_k_positive(#[case] val: f32) {
-        Temperature::from_k(val).expect(&format!("{} is a valid value for Kelvin", val));
+        Temperature::from_k(val).unwrap_or_else(|_| panic!("{} is a valid value for Kelvin", val));
     }
 
     #[rstest]
@@ -251,7 +474,8 @@ mod tests {
     #[case(212.0)]
     #[case(f32::MAX)]
     fn test_temperature_from_f_positive(#[case] val: f32) {
-        Temperature::from_f(val).expect(&format!("{} is a valid value for Fahrenheit", val));
+        Temperature::from_f(val)
+            .unwrap_or_else(|_| panic!("{} is a valid value for Fahrenheit", val));
     }
 
     #[rstest]
@@ -277,6 +501,37 @@ mod tests {
         let string_value = temp.to_string_value(Units::SI);
         assert_eq!(string_value, k);
     }
+    #[rstest]
+    #[case("Sunny", ConditionKind::Clear)]
+    #[case("clear sky", ConditionKind::Clear)]
+    #[case("Partly cloudy", ConditionKind::PartlyCloudy)]
+    #[case("Overcast", ConditionKind::Cloudy)]
+    #[case("Mist", ConditionKind::Fog)]
+    #[case("Light drizzle", ConditionKind::Drizzle)]
+    #[case("Light rain shower", ConditionKind::Rain)]
+    #[case("Heavy rain at times", ConditionKind::HeavyRain)]
+    #[case("Light freezing rain", ConditionKind::Sleet)]
+    #[case("Patchy snow possible", ConditionKind::Snow)]
+    #[case("Thundery outbreaks possible", ConditionKind::Thunderstorm)]
+    #[case("Volcanic ash", ConditionKind::Unknown)]
+    fn test_condition_kind_from_description(#[case] text: &str, #[case] expected: ConditionKind) {
+        assert_eq!(ConditionKind::from_description(text), expected);
+    }
+
+    #[rstest]
+    #[case("50.45,30.52", Ok(Coordinates { lat: 50.45, lon: 30.52 }))]
+    #[case(" -33.87 , 151.21 ", Ok(Coordinates { lat: -33.87, lon: 151.21 }))]
+    #[case("Kyiv, UA", Err(CoordinatesError::Format("Kyiv, UA".to_string())))]
+    #[case("50.45", Err(CoordinatesError::Format("50.45".to_string())))]
+    #[case("91,0", Err(CoordinatesError::Latitude(91.0)))]
+    #[case("0,-181", Err(CoordinatesError::Longitude(-181.0)))]
+    fn test_coordinates_from_str(
+        #[case] s: &str,
+        #[case] expected: Result<Coordinates, CoordinatesError>,
+    ) {
+        assert_eq!(s.parse::<Coordinates>(), expected);
+    }
+
     #[rstest]
     #[case(Temperature::Kelvin(0.0), Units::Imperial, "-459.7°F")]
     #[case(Temperature::Kelvin(0.0), Units::Metric, "-273.1°C")]
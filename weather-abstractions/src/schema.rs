@@ -0,0 +1,53 @@
+/// Describes a single setting of a weather provider, stored in the settings file under
+/// `<provider>/<key>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettingDescriptor {
+    /// The key of the setting, for example: apiKey
+    pub key: &'static str,
+    /// A short human readable description of the setting
+    pub description: &'static str,
+    /// Whether the provider can't be built without the setting
+    pub required: bool,
+    /// Whether the value must be hidden from the user output
+    pub secret: bool,
+}
+
+impl SettingDescriptor {
+    /// Creates a descriptor of a required secret setting, like an API key.
+    pub const fn secret(key: &'static str, description: &'static str) -> Self {
+        Self {
+            key,
+            description,
+            required: true,
+            secret: true,
+        }
+    }
+    /// Creates a descriptor of a required setting which is not a secret, like an URL.
+    pub const fn required(key: &'static str, description: &'static str) -> Self {
+        Self {
+            key,
+            description,
+            required: true,
+            secret: false,
+        }
+    }
+    /// Creates a descriptor of an optional secret setting, like an API key which not every API
+    /// requires.
+    pub const fn optional_secret(key: &'static str, description: &'static str) -> Self {
+        Self {
+            key,
+            description,
+            required: false,
+            secret: true,
+        }
+    }
+    /// Creates a descriptor of an optional setting which has a default value.
+    pub const fn optional(key: &'static str, description: &'static str) -> Self {
+        Self {
+            key,
+            description,
+            required: false,
+            secret: false,
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use crate::{Astronomy, Coordinates, MoonPhase};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Utc};
+use std::f64::consts::PI;
+
+/// The elevation of the sun center at the sunrise and sunset, below the horizon by the
+/// atmospheric refraction and the radius of the sun disk.
+const SUNRISE_ELEVATION: f64 = -0.833;
+const CIVIL_TWILIGHT_ELEVATION: f64 = -6.0;
+const NAUTICAL_TWILIGHT_ELEVATION: f64 = -12.0;
+/// The golden hour is when the sun is low, from 4° below to 6° above the horizon, the blue hour
+/// is the twilight before or after it, from 6° to 4° below the horizon.
+const GOLDEN_HOUR_ELEVATIONS: (f64, f64) = (-4.0, 6.0);
+const BLUE_HOUR_ELEVATIONS: (f64, f64) = (-6.0, -4.0);
+/// The mean length of the lunar cycle in days and the Julian day of a known new moon, on
+/// 2000-01-06 at 18:14 UTC.
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+const NEW_MOON_JULIAN_DAY: f64 = 2_451_550.26;
+
+/// The time between two sun elevations, like the golden hour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Period {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+}
+
+/// The sun events of a day calculated from the coordinates, without any provider.
+///
+/// The calculation follows the NOAA solar calculator, based on the Astronomical Algorithms of
+/// Jean Meeus, and is accurate to about a minute between the polar circles. An event is `None`
+/// when the sun doesn't cross its elevation that day, like the sunset during the polar day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolarDay {
+    pub solar_noon: DateTime<FixedOffset>,
+    pub sunrise: Option<DateTime<FixedOffset>>,
+    pub sunset: Option<DateTime<FixedOffset>>,
+    pub civil_dawn: Option<DateTime<FixedOffset>>,
+    pub civil_dusk: Option<DateTime<FixedOffset>>,
+    pub nautical_dawn: Option<DateTime<FixedOffset>>,
+    pub nautical_dusk: Option<DateTime<FixedOffset>>,
+    /// The golden hour after the sunrise, or the whole low sun day when the sun doesn't rise
+    /// above the golden hour
+    pub morning_golden_hour: Option<Period>,
+    pub evening_golden_hour: Option<Period>,
+    pub morning_blue_hour: Option<Period>,
+    pub evening_blue_hour: Option<Period>,
+    /// The moon phase and illumination at the solar noon, approximated by the mean lunar cycle
+    pub moon_phase: MoonPhase,
+    pub moon_illumination: f32,
+}
+
+impl SolarDay {
+    /// Calculates the sun events of the date at the coordinates, the times are in the offset.
+    /// The day is the one around the solar noon closest to the noon of the date in the offset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{FixedOffset, NaiveDate};
+    /// use weather_abstractions::{Coordinates, SolarDay};
+    /// let london = Coordinates::new(51.5074, -0.1278).unwrap();
+    /// let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+    /// let day = SolarDay::new(&london, date, FixedOffset::east_opt(0).unwrap());
+    /// assert_eq!(day.sunrise.unwrap().format("%H:%M").to_string(), "08:04");
+    /// assert_eq!(day.sunset.unwrap().format("%H:%M").to_string(), "15:53");
+    /// ```
+    pub fn new(coordinates: &Coordinates, date: NaiveDate, offset: FixedOffset) -> Self {
+        let local_noon = date.and_time(NaiveTime::from_hms_opt(12, 0, 0).expect("valid noon"));
+        let local_noon = (local_noon - Duration::seconds(offset.local_minus_utc().into()))
+            .and_utc()
+            .timestamp() as f64;
+        let sun = Sun {
+            lat: coordinates.lat,
+            lon: coordinates.lon,
+        };
+        let noon = sun.solar_noon(local_noon);
+        let time =
+            |timestamp: Option<f64>| timestamp.map(|timestamp| local_time(timestamp, offset));
+        let period = |start: Option<f64>, end: Option<f64>| {
+            Some(Period {
+                start: local_time(start?, offset),
+                end: local_time(end?, offset),
+            })
+        };
+        let (golden_morning, golden_evening) = sun.periods(noon, GOLDEN_HOUR_ELEVATIONS);
+        let (blue_morning, blue_evening) = sun.periods(noon, BLUE_HOUR_ELEVATIONS);
+        let cycle = moon_cycle(noon);
+        Self {
+            solar_noon: local_time(noon, offset),
+            sunrise: time(sun.crossing(noon, SUNRISE_ELEVATION, true)),
+            sunset: time(sun.crossing(noon, SUNRISE_ELEVATION, false)),
+            civil_dawn: time(sun.crossing(noon, CIVIL_TWILIGHT_ELEVATION, true)),
+            civil_dusk: time(sun.crossing(noon, CIVIL_TWILIGHT_ELEVATION, false)),
+            nautical_dawn: time(sun.crossing(noon, NAUTICAL_TWILIGHT_ELEVATION, true)),
+            nautical_dusk: time(sun.crossing(noon, NAUTICAL_TWILIGHT_ELEVATION, false)),
+            morning_golden_hour: golden_morning.and_then(|(start, end)| period(start, end)),
+            evening_golden_hour: golden_evening.and_then(|(start, end)| period(start, end)),
+            morning_blue_hour: blue_morning.and_then(|(start, end)| period(start, end)),
+            evening_blue_hour: blue_evening.and_then(|(start, end)| period(start, end)),
+            moon_phase: MoonPhase::from_cycle(cycle as f32),
+            moon_illumination: ((1.0 - (2.0 * PI * cycle).cos()) / 2.0 * 100.0) as f32,
+        }
+    }
+    /// The sunrise, sunset and moon phase as the astronomy returned by the providers, without
+    /// the moonrise and moonset.
+    pub fn astronomy(&self) -> Astronomy {
+        Astronomy {
+            sunrise: self.sunrise,
+            sunset: self.sunset,
+            moon_phase: Some(self.moon_phase),
+            moon_illumination: Some(self.moon_illumination),
+            ..Default::default()
+        }
+    }
+}
+
+/// The position of the sun at a time: the declination and the hour angle, in degrees.
+struct Position {
+    declination: f64,
+    hour_angle: f64,
+}
+
+struct Sun {
+    lat: f64,
+    lon: f64,
+}
+
+impl Sun {
+    fn position(&self, timestamp: f64) -> Position {
+        let julian_century = (julian_day(timestamp) - 2451545.0) / 36525.0;
+        let t = julian_century;
+        let mean_longitude = (280.46646 + t * (36000.76983 + t * 0.0003032)).rem_euclid(360.0);
+        let mean_anomaly = 357.52911 + t * (35999.05029 - 0.0001537 * t);
+        let eccentricity = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+        let m = mean_anomaly.to_radians();
+        let center = m.sin() * (1.914602 - t * (0.004817 + 0.000014 * t))
+            + (2.0 * m).sin() * (0.019993 - 0.000101 * t)
+            + (3.0 * m).sin() * 0.000289;
+        let omega = (125.04 - 1934.136 * t).to_radians();
+        let apparent_longitude =
+            (mean_longitude + center - 0.00569 - 0.00478 * omega.sin()).to_radians();
+        let mean_obliquity =
+            23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60.0) / 60.0;
+        let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+        let declination = (obliquity.sin() * apparent_longitude.sin()).asin();
+
+        let y = (obliquity / 2.0).tan().powi(2);
+        let l = mean_longitude.to_radians();
+        let e = eccentricity;
+        let equation_of_time = 4.0
+            * (y * (2.0 * l).sin() - 2.0 * e * m.sin() + 4.0 * e * y * m.sin() * (2.0 * l).cos()
+                - 0.5 * y * y * (4.0 * l).sin()
+                - 1.25 * e * e * (2.0 * m).sin())
+            .to_degrees();
+        let minutes = timestamp.rem_euclid(86400.0) / 60.0;
+        let true_solar_time = minutes + equation_of_time + 4.0 * self.lon;
+        Position {
+            declination: declination.to_degrees(),
+            hour_angle: normalize_degrees(true_solar_time / 4.0 - 180.0),
+        }
+    }
+    /// Returns the time when the hour angle is 0, the closest to the time.
+    fn solar_noon(&self, timestamp: f64) -> f64 {
+        (0..3).fold(timestamp, |timestamp, _| {
+            timestamp - self.position(timestamp).hour_angle * 240.0
+        })
+    }
+    /// Returns the time when the sun crosses the elevation before or after the solar noon, the
+    /// hour angle of the crossing is refined at the time of the previous estimate.
+    fn crossing(&self, noon: f64, elevation: f64, rising: bool) -> Option<f64> {
+        let mut timestamp = noon;
+        for _ in 0..4 {
+            let position = self.position(timestamp);
+            let hour_angle = self.crossing_hour_angle(position.declination, elevation)?;
+            let target = if rising { -hour_angle } else { hour_angle };
+            timestamp -= normalize_degrees(position.hour_angle - target) * 240.0;
+        }
+        Some(timestamp)
+    }
+    /// The hour angle when the sun is at the elevation, `None` when the sun is always above or
+    /// always below it.
+    fn crossing_hour_angle(&self, declination: f64, elevation: f64) -> Option<f64> {
+        let (lat, declination) = (self.lat.to_radians(), declination.to_radians());
+        let cos = (elevation.to_radians().sin() - lat.sin() * declination.sin())
+            / (lat.cos() * declination.cos());
+        (-1.0..=1.0).contains(&cos).then(|| cos.acos().to_degrees())
+    }
+    /// Returns the morning and evening periods when the sun is between the elevations. When the
+    /// sun doesn't rise above the higher elevation, the morning period lasts until the sun goes
+    /// below the lower one again and there is no evening period.
+    #[allow(clippy::type_complexity)]
+    fn periods(
+        &self,
+        noon: f64,
+        (low, high): (f64, f64),
+    ) -> (
+        Option<(Option<f64>, Option<f64>)>,
+        Option<(Option<f64>, Option<f64>)>,
+    ) {
+        let declination = self.position(noon).declination;
+        let noon_elevation = 90.0 - (self.lat - declination).abs();
+        if noon_elevation <= low {
+            return (None, None);
+        }
+        let start = self.crossing(noon, low, true);
+        let end = self.crossing(noon, low, false);
+        if noon_elevation < high {
+            return (Some((start, end)), None);
+        }
+        (
+            Some((start, self.crossing(noon, high, true))),
+            Some((self.crossing(noon, high, false), end)),
+        )
+    }
+}
+
+fn julian_day(timestamp: f64) -> f64 {
+    timestamp / 86_400.0 + 2_440_587.5
+}
+
+/// The part of the lunar cycle passed since the new moon, from 0 to 1.
+fn moon_cycle(timestamp: f64) -> f64 {
+    ((julian_day(timestamp) - NEW_MOON_JULIAN_DAY) / SYNODIC_MONTH).rem_euclid(1.0)
+}
+
+/// Normalizes the angle to the range from -180 to 180 degrees.
+fn normalize_degrees(degrees: f64) -> f64 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+fn local_time(timestamp: f64, offset: FixedOffset) -> DateTime<FixedOffset> {
+    DateTime::<Utc>::from_timestamp(timestamp.round() as i64, 0)
+        .expect("timestamp in range")
+        .with_timezone(&offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn hours(offset: i32) -> FixedOffset {
+        FixedOffset::east_opt(offset * 3600).unwrap()
+    }
+
+    fn assert_near(actual: Option<DateTime<FixedOffset>>, expected: &str) {
+        let expected = DateTime::parse_from_rfc3339(expected).expect("valid time");
+        let actual = actual.expect("the event happens");
+        let diff = (actual - expected).num_seconds().abs();
+        assert!(diff <= 120, "{actual} differs from {expected} by {diff}s");
+    }
+
+    // The published almanac values, rounded to the minute
+    #[rstest]
+    #[case(51.5074, -0.1278, date(2024, 6, 21), 1, "2024-06-21T04:43:00+01:00", "2024-06-21T21:21:00+01:00")]
+    #[case(51.5074, -0.1278, date(2024, 12, 21), 0, "2024-12-21T08:04:00+00:00", "2024-12-21T15:53:00+00:00")]
+    #[case(40.7128, -74.0060, date(2024, 6, 20), -4, "2024-06-20T05:25:00-04:00", "2024-06-20T20:31:00-04:00")]
+    #[case(-33.8688, 151.2093, date(2024, 6, 21), 10, "2024-06-21T07:00:00+10:00", "2024-06-21T16:54:00+10:00")]
+    #[case(
+        0.0,
+        0.0,
+        date(2024, 3, 20),
+        0,
+        "2024-03-20T06:04:00+00:00",
+        "2024-03-20T18:11:00+00:00"
+    )]
+    fn sunrise_and_sunset_match_almanac(
+        #[case] lat: f64,
+        #[case] lon: f64,
+        #[case] date: NaiveDate,
+        #[case] offset: i32,
+        #[case] sunrise: &str,
+        #[case] sunset: &str,
+    ) {
+        let coordinates = Coordinates::new(lat, lon).unwrap();
+        let solar_day = SolarDay::new(&coordinates, date, hours(offset));
+        assert_near(solar_day.sunrise, sunrise);
+        assert_near(solar_day.sunset, sunset);
+    }
+
+    #[test]
+    fn twilight_and_noon_match_almanac() {
+        let london = Coordinates::new(51.5074, -0.1278).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        let solar_day = SolarDay::new(&london, date, hours(0));
+        assert_near(Some(solar_day.solar_noon), "2024-12-21T11:58:00+00:00");
+        assert_near(solar_day.civil_dawn, "2024-12-21T07:24:00+00:00");
+        assert_near(solar_day.civil_dusk, "2024-12-21T16:33:00+00:00");
+        assert_near(solar_day.nautical_dawn, "2024-12-21T06:40:00+00:00");
+        assert_near(solar_day.nautical_dusk, "2024-12-21T17:17:00+00:00");
+    }
+
+    #[test]
+    fn golden_and_blue_hours_around_sunrise_and_sunset() {
+        let london = Coordinates::new(51.5074, -0.1278).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let solar_day = SolarDay::new(&london, date, hours(1));
+        let morning_golden = solar_day.morning_golden_hour.expect("morning golden hour");
+        let evening_golden = solar_day.evening_golden_hour.expect("evening golden hour");
+        let morning_blue = solar_day.morning_blue_hour.expect("morning blue hour");
+        let evening_blue = solar_day.evening_blue_hour.expect("evening blue hour");
+        let sunrise = solar_day.sunrise.unwrap();
+        let sunset = solar_day.sunset.unwrap();
+
+        assert_eq!(morning_blue.start, solar_day.civil_dawn.unwrap());
+        assert_eq!(morning_blue.end, morning_golden.start);
+        assert!(morning_golden.start < sunrise && sunrise < morning_golden.end);
+        assert!(evening_golden.start < sunset && sunset < evening_golden.end);
+        assert_eq!(evening_golden.end, evening_blue.start);
+        assert_eq!(evening_blue.end, solar_day.civil_dusk.unwrap());
+    }
+
+    #[test]
+    fn no_sunset_in_polar_day_and_no_sunrise_in_polar_night() {
+        let tromso = Coordinates::new(69.6492, 18.9553).unwrap();
+        let summer = SolarDay::new(
+            &tromso,
+            NaiveDate::from_ymd_opt(2024, 6, 21).unwrap(),
+            hours(2),
+        );
+        assert_eq!(summer.sunrise, None);
+        assert_eq!(summer.sunset, None);
+        assert_eq!(summer.astronomy().day_length(), None);
+
+        let winter = SolarDay::new(
+            &tromso,
+            NaiveDate::from_ymd_opt(2024, 12, 21).unwrap(),
+            hours(1),
+        );
+        assert_eq!(winter.sunrise, None);
+        assert_eq!(winter.evening_golden_hour, None);
+        // The sun stays low, so the civil twilight and the golden hour last all the short day
+        assert!(winter.civil_dawn.is_some());
+        assert!(winter.morning_golden_hour.is_some());
+    }
+
+    #[rstest]
+    // A full moon on 2024-04-23 and a new moon on 2024-05-08
+    #[case(date(2024, 4, 23), MoonPhase::FullMoon)]
+    #[case(date(2024, 5, 8), MoonPhase::NewMoon)]
+    #[case(date(2024, 5, 15), MoonPhase::FirstQuarter)]
+    fn moon_phase_from_mean_cycle(#[case] date: NaiveDate, #[case] expected: MoonPhase) {
+        let coordinates = Coordinates::new(0.0, 0.0).unwrap();
+        let solar_day = SolarDay::new(&coordinates, date, hours(0));
+        assert_eq!(solar_day.moon_phase, expected);
+    }
+}
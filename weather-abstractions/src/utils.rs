@@ -1,5 +1,10 @@
-use reqwest::{Error, Response, Url};
+use std::marker::PhantomData;
+
+use reqwest::{Error, Request, Response, Url};
+use reqwest_tracing::{default_on_request_end, reqwest_otel_span, ReqwestOtelSpanBackend};
 use serde::de::DeserializeOwned;
+use task_local_extensions::Extensions;
+use tracing::Span;
 
 /// Parses the response from an HTTP request using the `reqwest` library.
 ///
@@ -97,6 +102,100 @@ pub fn build_endpoint(base: &Url, path: &str) -> Url {
     endpoint
 }
 
+/// Replaces the values of the secret query parameters of a URL, like the API keys, so the URL
+/// can be logged or traced.
+///
+/// # Arguments
+///
+/// * `url` - A reference to the URL of the request.
+/// * `secrets` - The names of the query parameters to redact.
+///
+/// # Returns
+///
+/// A `Url` with the values of the secret parameters replaced by `REDACTED`.
+///
+/// # Example
+///
+/// ```
+/// use reqwest::Url;
+/// use weather_abstractions::utils::redacted_url;
+///
+/// let url = Url::parse("https://api.weatherapi.com/v1/current.json?q=Paris&key=secret").unwrap();
+///
+/// assert_eq!(
+///     redacted_url(&url, &["key"]).as_str(),
+///     "https://api.weatherapi.com/v1/current.json?q=Paris&key=REDACTED"
+/// );
+/// ```
+pub fn redacted_url(url: &Url, secrets: &[&str]) -> Url {
+    let mut redacted = url.clone();
+    if url.query().is_some() {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let value = if secrets.contains(&name.as_ref()) {
+                    "REDACTED".to_string()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    let _ = redacted.set_password(None);
+    redacted
+}
+
+/// The provider of the requests traced by `ProviderSpan`.
+pub trait ProviderSpanConfig {
+    /// The name of the provider, recorded in the `provider` attribute of the spans.
+    const NAME: &'static str;
+    /// The query parameters redacted from the URL, like the API key, see `redacted_url`.
+    const SECRETS: &'static [&'static str] = &[];
+}
+
+/// The span of a request to the API of a provider, for the `TracingMiddleware`, with the
+/// provider, the endpoint and the URL without the secrets.
+///
+/// # Example
+///
+/// ```
+/// use reqwest_tracing::TracingMiddleware;
+/// use weather_abstractions::utils::{ProviderSpan, ProviderSpanConfig};
+///
+/// struct Api;
+/// impl ProviderSpanConfig for Api {
+///     const NAME: &'static str = "my-provider";
+///     const SECRETS: &'static [&'static str] = &["key"];
+/// }
+///
+/// let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+///     .with(TracingMiddleware::<ProviderSpan<Api>>::new())
+///     .build();
+/// ```
+pub struct ProviderSpan<P>(PhantomData<fn() -> P>);
+
+impl<P: ProviderSpanConfig> ReqwestOtelSpanBackend for ProviderSpan<P> {
+    fn on_request_start(req: &Request, _: &mut Extensions) -> Span {
+        let endpoint = req.url().path();
+        reqwest_otel_span!(
+            name = format!("{} {endpoint}", req.method()),
+            req,
+            provider = P::NAME,
+            endpoint = endpoint,
+            http.url = %redacted_url(req.url(), P::SECRETS)
+        )
+    }
+    fn on_request_end(
+        span: &Span,
+        outcome: &reqwest_middleware::Result<Response>,
+        _: &mut Extensions,
+    ) {
+        default_on_request_end(span, outcome)
+    }
+}
+
 /// Defines a set of functions that take no arguments and return a string.
 /// The functions are generated based on the parameters passed to the macro.
 ///
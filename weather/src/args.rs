@@ -1,5 +1,12 @@
-use chrono::NaiveDate;
-use clap::{Args, Parser, Subcommand};
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+use chrono::FixedOffset;
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+use weather_abstractions::{ConditionKind, DateSpec, DateSpecError, DateTimeSpec};
+
+use crate::icons::IconStyle;
 
 /// Weather command line interface (CLI) arguments.
 #[derive(Parser, Debug)]
@@ -10,17 +17,99 @@ use clap::{Args, Parser, Subcommand};
 pub struct WeatherCliArgs {
     #[clap(subcommand)]
     pub command: CliCommand,
+    /// The settings profile to use instead of the active one
+    #[arg(long, global = true, env = "WEATHER_PROFILE")]
+    pub profile: Option<String>,
+    /// The style of the weather condition icons
+    #[arg(long, global = true, value_enum, default_value_t = IconStyle::Emoji)]
+    pub icons: IconStyle,
+    /// The OTLP/HTTP endpoint of the OpenTelemetry collector to export the traces to, for example
+    /// http://localhost:4318
+    #[cfg(feature = "otlp")]
+    #[arg(long, global = true, env = "WEATHER_OTLP_ENDPOINT")]
+    pub otlp_endpoint: Option<url::Url>,
 }
 
 /// An enumeration of the different sub-commands available for the weather CLI.
 #[derive(Debug, Subcommand)]
 pub enum CliCommand {
+    #[command(flatten)]
+    Provider(ProviderCommand),
+    ///Manage the named settings profiles
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+    ///Save, list and remove the location aliases
+    #[command(subcommand)]
+    Location(LocationCommand),
+}
+
+impl CliCommand {
+    /// The name of the sub-command, as typed on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CliCommand::Provider(command) => command.name(),
+            CliCommand::Profile(_) => "profile",
+            CliCommand::Location(_) => "location",
+        }
+    }
+}
+
+/// The sub-commands which need the configuration and the providers, the profile and location
+/// sub-commands are executed without them.
+#[derive(Debug, Subcommand)]
+pub enum ProviderCommand {
     Configure(ConfigureArgs),
     Get(GetWeatherArgs),
+    Batch(BatchArgs),
+    History(HistoryArgs),
+    Watch(WatchArgs),
+    Alerts(AlertsArgs),
+    Air(AirArgs),
+    Sun(SunArgs),
+    Check(CheckArgs),
+    Serve(ServeArgs),
+    Exporter(ExporterArgs),
     ///Retrieve the current configuration information for all available weather providers
     Info,
-    ///Resets the settings of the application to its default values.
-    Reset,
+    ///Read, remove and list the values in the settings
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    ///Resets the settings of the application, or of a single provider, to its default values.
+    Reset(ResetArgs),
+    ///Interactively choose a provider, enter its settings and verify them
+    Setup,
+    ///Generate the shell completion script
+    ///
+    ///The saved location aliases and @groups are written into the script when it is generated,
+    ///so generate it again after saving or removing a location or a group.
+    Completions(CompletionsArgs),
+    ///Generate the man page in the roff format
+    Man,
+}
+
+impl ProviderCommand {
+    /// The name of the sub-command, as typed on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProviderCommand::Configure(_) => "configure",
+            ProviderCommand::Get(_) => "get",
+            ProviderCommand::Batch(_) => "batch",
+            ProviderCommand::History(_) => "history",
+            ProviderCommand::Watch(_) => "watch",
+            ProviderCommand::Alerts(_) => "alerts",
+            ProviderCommand::Air(_) => "air",
+            ProviderCommand::Sun(_) => "sun",
+            ProviderCommand::Check(_) => "check",
+            ProviderCommand::Serve(_) => "serve",
+            ProviderCommand::Exporter(_) => "exporter",
+            ProviderCommand::Info => "info",
+            ProviderCommand::Config(_) => "config",
+            ProviderCommand::Reset(_) => "reset",
+            ProviderCommand::Setup => "setup",
+            ProviderCommand::Completions(_) => "completions",
+            ProviderCommand::Man => "man",
+        }
+    }
 }
 
 /// Configuration arguments for the weather CLI
@@ -32,13 +121,403 @@ pub struct ConfigureArgs {
     pub key: Option<String>,
     /// The value for the specified setting key
     pub value: Option<String>,
+    /// Verify the settings with a request to the provider before saving them
+    #[arg(long)]
+    pub verify: bool,
+}
+
+/// Settings sub-commands, the settings are addressed by slash separated paths, for example: open-weather/apiKey
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    ///Print the value for the path
+    Get {
+        /// The slash separated path of the setting
+        path: String,
+    },
+    ///Remove the value, or the whole section, for the path
+    Unset {
+        /// The slash separated path of the setting
+        path: String,
+    },
+    ///List all the settings with their paths
+    List,
+}
+
+/// Profile sub-commands, each profile has its own provider choice and provider settings
+#[derive(Debug, Subcommand)]
+pub enum ProfileCommand {
+    ///List all the profiles and mark the active one
+    List,
+    ///Make the profile active
+    Use {
+        /// The name of the profile
+        name: String,
+    },
+    ///Create a new empty profile
+    Create {
+        /// The name of the profile
+        name: String,
+    },
+    ///Delete the profile and all its settings
+    Delete {
+        /// The name of the profile
+        name: String,
+    },
+}
+
+/// Location sub-commands, the saved locations are shared by all the profiles
+#[derive(Debug, Subcommand)]
+pub enum LocationCommand {
+    ///Save the location under the alias, replacing the saved one
+    Add {
+        /// The alias to use instead of the location, for example: home
+        alias: String,
+        /// The address, or the coordinates in the latitude,longitude format, for example: 50.45,30.52
+        location: String,
+        /// Make the location the default one
+        #[arg(long)]
+        default: bool,
+    },
+    ///List all the saved locations and mark the default one
+    List,
+    ///Remove the saved location
+    Remove {
+        /// The alias of the location
+        alias: String,
+    },
+    ///Make the saved location the default one, used when no address is given
+    Default {
+        /// The alias of the location
+        alias: String,
+    },
+    ///Save the group of locations, used as @name, replacing the saved one
+    Group {
+        /// The name of the group, for example: offices
+        name: String,
+        /// The aliases or the addresses of the locations in the group
+        #[arg(required = true)]
+        members: Vec<String>,
+    },
+    ///Remove the saved group, the locations of the group are kept
+    Ungroup {
+        /// The name of the group
+        name: String,
+    },
+}
+
+/// Reset arguments for the weather CLI
+#[derive(Debug, Args)]
+pub struct ResetArgs {
+    /// The name of the provider to reset, all the settings are reset if not specified
+    pub provider: Option<String>,
 }
 
 /// Retrieve the weather information
 #[derive(Debug, Args)]
 pub struct GetWeatherArgs {
-    /// A string containing the location's address    
-    pub address: String,
-    /// An optional NaiveDate representing the date to retrieve the weather information for
-    pub date: Option<NaiveDate>,
+    /// The locations' addresses, coordinates, saved aliases or @groups, optionally followed by
+    /// the date to retrieve the weather information for: YYYY-MM-DD, 2024-W18-3, today, tomorrow,
+    /// yesterday, +3d, -1w, friday, next monday or last sunday, optionally with a time of the day like
+    /// 2024-05-01T15:00 or "tomorrow 15:00". The default location if not specified
+    #[arg(value_name = "LOCATION")]
+    pub addresses: Vec<String>,
+    /// The maximum number of locations requested at the same time
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub concurrency: u16,
+    /// Add the severe weather alerts in effect to the current weather
+    #[arg(long)]
+    pub alerts: bool,
+    /// Add the air quality to the current weather
+    #[arg(long)]
+    pub air: bool,
+}
+
+impl GetWeatherArgs {
+    /// Returns the addresses and the date, which is the last argument if it is a valid date
+    /// with an optional time of the day.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DateSpecError` if the last argument looks like a date, a signed value or a
+    /// dash separated value starting with a digit, but is not a valid one.
+    pub fn addresses_and_date(&self) -> Result<(&[String], Option<DateTimeSpec>), DateSpecError> {
+        let Some((last, addresses)) = self.addresses.split_last() else {
+            return Ok((&self.addresses, None));
+        };
+        match last.parse::<DateTimeSpec>() {
+            Ok(date) => Ok((addresses, Some(date))),
+            Err(e) if looks_like_date(last) => Err(e),
+            Err(_) => Ok((&self.addresses, None)),
+        }
+    }
+}
+
+fn looks_like_date(value: &str) -> bool {
+    value.starts_with(['+', '-'])
+        || (value.starts_with(|c: char| c.is_ascii_digit())
+            && (value.contains('-') || value.contains(':'))
+            && !value.contains(','))
+}
+
+/// Retrieve the weather for every day of the date range, with the temperature and condition summary
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// The location's address, coordinates or saved alias, the default location if not specified
+    pub address: Option<String>,
+    /// The first day of the range, an exact or a relative date like -1w
+    #[arg(long, allow_hyphen_values = true)]
+    pub from: DateSpec,
+    /// The last day of the range, included, an exact or a relative date like yesterday
+    #[arg(long, allow_hyphen_values = true)]
+    pub to: DateSpec,
+}
+
+/// Retrieve the severe weather alerts which have not expired yet, the exit code is 1 when
+/// alerts are in effect and 2 on an error
+#[derive(Debug, Args)]
+pub struct AlertsArgs {
+    /// The location's address, coordinates or saved alias, the default location if not specified
+    pub address: Option<String>,
+}
+
+/// Retrieve the current air quality: the pollutant concentrations with the US EPA and UK DEFRA
+/// indexes
+#[derive(Debug, Args)]
+pub struct AirArgs {
+    /// The location's address, coordinates or saved alias, the default location if not specified
+    pub address: Option<String>,
+}
+
+/// Retrieve the sunrise, sunset and day length with the moon events, phase and illumination
+#[derive(Debug, Args)]
+pub struct SunArgs {
+    /// The location's address, coordinates or saved alias, the default location if not specified
+    pub address: Option<String>,
+    /// The date, an exact or a relative date like tomorrow or +3d, today at the location if not
+    /// specified
+    #[arg(allow_hyphen_values = true)]
+    pub date: Option<DateSpec>,
+    /// Calculate the sun events from the coordinates of the location without the provider,
+    /// with the twilight and the golden and blue hours
+    #[arg(long)]
+    pub offline: bool,
+    /// The UTC offset of the calculated times, like +02:00, the offset of the mean solar time
+    /// at the location, rounded to the hour, if not specified
+    #[arg(long, allow_hyphen_values = true, requires = "offline")]
+    pub offset: Option<FixedOffset>,
+}
+
+/// Check the weather against the rules, for the scripts: the exit code is 0 when no rule
+/// matches, 1 when a rule matches and 2 on an error
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("rules").required(true).multiple(true)))]
+pub struct CheckArgs {
+    /// The location's address, coordinates or saved alias, the default location if not specified
+    pub address: Option<String>,
+    /// The date, an exact or a relative date like tomorrow or +3d, optionally with a time of the
+    /// day like "tomorrow 15:00", the current weather if not specified
+    #[arg(allow_hyphen_values = true)]
+    pub date: Option<DateTimeSpec>,
+    /// Matches when the temperature is below the degrees Celsius
+    #[arg(long, group = "rules", allow_hyphen_values = true)]
+    pub temp_below: Option<f32>,
+    /// Matches when the temperature is above the degrees Celsius
+    #[arg(long, group = "rules", allow_hyphen_values = true)]
+    pub temp_above: Option<f32>,
+    /// Matches when it rains, including the drizzle and the thunderstorm
+    #[arg(long, group = "rules")]
+    pub rain: bool,
+    /// Matches when the wind speed is above the meters per second
+    #[arg(long, group = "rules")]
+    pub wind_above: Option<f32>,
+    /// Matches when the weather condition is one of the given, the option can be repeated
+    #[arg(long, group = "rules", value_enum)]
+    pub condition: Vec<Condition>,
+    /// Print every rule with the checked value and whether it matched
+    #[arg(long)]
+    pub explain: bool,
+}
+
+/// The weather conditions of the check rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Condition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    HeavyRain,
+    Thunderstorm,
+    Snow,
+    Sleet,
+}
+
+impl From<Condition> for ConditionKind {
+    fn from(condition: Condition) -> Self {
+        match condition {
+            Condition::Clear => ConditionKind::Clear,
+            Condition::PartlyCloudy => ConditionKind::PartlyCloudy,
+            Condition::Cloudy => ConditionKind::Cloudy,
+            Condition::Fog => ConditionKind::Fog,
+            Condition::Drizzle => ConditionKind::Drizzle,
+            Condition::Rain => ConditionKind::Rain,
+            Condition::HeavyRain => ConditionKind::HeavyRain,
+            Condition::Thunderstorm => ConditionKind::Thunderstorm,
+            Condition::Snow => ConditionKind::Snow,
+            Condition::Sleet => ConditionKind::Sleet,
+        }
+    }
+}
+
+/// Serve the weather as JSON over HTTP, so the other tools don't need the API keys of the
+/// providers, until interrupted
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// The address and port to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub bind: SocketAddr,
+    /// The maximum number of requests to the providers per second
+    #[arg(long, default_value_t = 5.0, value_parser = parse_rate)]
+    pub rate: f64,
+}
+
+/// Export the current weather of the locations as Prometheus metrics at /metrics, refreshed
+/// periodically until interrupted
+#[derive(Debug, Args)]
+pub struct ExporterArgs {
+    /// The locations' addresses, coordinates, saved aliases or @groups separated by commas without
+    /// a space, like home,London, UK, the option can be repeated. The default location if not
+    /// specified
+    #[arg(long)]
+    pub locations: Vec<String>,
+    /// The time between the refreshes, a number followed by s, m or h, for example: 10m
+    #[arg(long, default_value = "5m", value_parser = parse_interval)]
+    pub interval: Duration,
+    /// The address and port to listen on
+    #[arg(long, default_value = "127.0.0.1:9108")]
+    pub bind: SocketAddr,
+}
+
+/// Watch the current weather, redrawn in place on a terminal or appended as lines otherwise,
+/// with the changes since the previous refresh highlighted
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// The location's address, coordinates or saved alias, the default location if not specified
+    pub address: Option<String>,
+    /// The time between the refreshes, a number followed by s, m or h, for example: 10m
+    #[arg(long, default_value = "10m", value_parser = parse_interval)]
+    pub interval: Duration,
+    /// Stop after the number of refreshes, run until interrupted if not specified
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub count: Option<u32>,
+}
+
+/// Parses a positive number of seconds, minutes or hours: 30s, 10m or 1h.
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    let error =
+        || format!("The interval must be a positive number followed by s, m or h, got '{value}'");
+    let split = value.len().checked_sub(1).ok_or_else(error)?;
+    let (number, unit) = value.split_at_checked(split).ok_or_else(error)?;
+    let number: u64 = number.parse().map_err(|_| error())?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return Err(error()),
+    };
+    match number.checked_mul(multiplier) {
+        None | Some(0) => Err(error()),
+        Some(secs) => Ok(Duration::from_secs(secs)),
+    }
+}
+
+/// Retrieve the weather for every address and date row of the input, the rows are in the CSV
+/// format: address,date. An empty date is the current weather
+#[derive(Debug, Args)]
+pub struct BatchArgs {
+    /// The input file, the standard input if not specified or '-'
+    #[arg(long, short)]
+    pub input: Option<PathBuf>,
+    /// The input format, JSON Lines for the .jsonl and .ndjson input files and CSV otherwise if
+    /// not specified
+    #[arg(long, value_enum)]
+    pub input_format: Option<BatchFormat>,
+    /// The output file, the standard output if not specified
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// The output format, taken from the output file extension if not specified
+    #[arg(long, value_enum)]
+    pub format: Option<BatchFormat>,
+    /// The maximum number of rows requested at the same time
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub concurrency: u16,
+    /// The maximum number of requests to the provider per second
+    #[arg(long, default_value_t = 5.0, value_parser = parse_rate)]
+    pub rate: f64,
+    /// Keep the successful rows of the output file and retrieve only the remaining rows
+    #[arg(long, requires = "output")]
+    pub resume: bool,
+}
+
+/// The format of the batch results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BatchFormat {
+    /// Comma separated values with a header row
+    Csv,
+    /// A JSON object per line
+    Jsonl,
+}
+
+/// The lowest rate of the requests, one request every 1000 seconds.
+const MIN_RATE: f64 = 0.001;
+
+/// Parses the number of requests per second, from `MIN_RATE` so the interval between the
+/// requests stays representable.
+fn parse_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate >= MIN_RATE && rate.is_finite() => Ok(rate),
+        _ => Err(format!("The rate must be a number of at least {MIN_RATE}")),
+    }
+}
+
+/// Shell completion arguments
+#[derive(Debug, Args)]
+pub struct CompletionsArgs {
+    /// The shell to generate the completion script for
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_interval_units() {
+        assert_eq!(parse_interval("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_interval("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_interval("2h"), Ok(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn parse_interval_rejects_zero_overflow_and_invalid_values() {
+        let huge = format!("{}h", u64::MAX / 60);
+        for value in ["0m", "", "m", "10", "10d", "-1s", huge.as_str()] {
+            assert!(parse_interval(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn parse_rate_accepts_fractions() {
+        assert_eq!(parse_rate("0.5"), Ok(0.5));
+        assert_eq!(parse_rate("0.001"), Ok(0.001));
+    }
+
+    #[test]
+    fn parse_rate_rejects_tiny_and_invalid_values() {
+        for value in ["0", "-1", "1e-300", "0.0009", "inf", "NaN", "fast"] {
+            assert!(parse_rate(value).is_err(), "{value}");
+        }
+    }
 }
@@ -0,0 +1,66 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// An in-memory cache of the values which expire after the time to live.
+///
+/// The cache can be shared by the concurrent requests, the entries are guarded by a mutex which
+/// is never held across an `.await`.
+pub struct Cache<K, V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> Cache<K, V> {
+    /// Creates an empty cache with the time to live of the entries.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Returns a copy of the value for the key, if it was inserted less than the time to live ago.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let span = tracing::info_span!("cache lookup", hit = tracing::field::Empty);
+        let _entered = span.enter();
+        let entries = self.entries.lock().expect("The cache lock is poisoned");
+        let value = entries
+            .get(key)
+            .filter(|(inserted, _)| inserted.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone());
+        span.record("hit", value.is_some());
+        value
+    }
+    /// Inserts the value for the key, replacing the previous one and dropping the expired entries.
+    pub fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().expect("The cache lock is poisoned");
+        entries.retain(|_, (inserted, _)| inserted.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_returns_inserted_value() {
+        let cache = Cache::new(Duration::from_secs(60));
+        cache.insert("London", 1);
+        cache.insert("London", 2);
+
+        assert_eq!(cache.get(&"London"), Some(2));
+        assert_eq!(cache.get(&"Paris"), None);
+    }
+
+    #[test]
+    fn get_skips_expired_value() {
+        let cache = Cache::new(Duration::ZERO);
+        cache.insert("London", 1);
+
+        assert_eq!(cache.get(&"London"), None);
+    }
+}
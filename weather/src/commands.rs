@@ -1,25 +1,149 @@
+pub mod air;
+pub mod alerts;
+pub mod batch;
+pub mod check;
+pub mod completions;
+pub mod config;
 pub mod configure;
+pub mod exporter;
 pub mod get;
+pub mod history;
 pub mod info;
+pub mod location;
+pub mod man;
+pub mod profile;
 pub mod reset;
-use weather_abstractions::Weather;
+pub mod serve;
+pub mod setup;
+pub mod sun;
+pub mod watch;
+use std::{error::Error as StdError, net::SocketAddr, process::ExitCode};
 
-use self::info::Info;
-use crate::args::GetWeatherArgs;
+use chrono::{NaiveDate, Utc};
+use serde_json::Value;
+use weather_abstractions::{AirQuality, Alert, Astronomy, SolarDay, Weather};
+
+use self::{batch::BatchSummary, check::RuleCheck, history::HistorySummary, info::Info};
 
 ///Represents the result of
 ///executing a weather command.
 pub enum WeatherCommandResult {
-    ///Represents the result of successfully getting the weather for a given address
-    Weather(GetWeatherArgs, Weather),
+    ///Represents the result of successfully getting the weather for a given address. Contains
+    ///the name of the location, the requested date and the weather.
+    Weather(String, Option<NaiveDate>, Weather),
     ///Represents the result of successfully changing the weather provider. Contains the
     ///name of the new provider.
     ProviderChanged(String),
+    ///Represents the result of successfully verifying and saving the provider settings
+    ///entered in the setup. Contains the name of the configured provider.
+    SetupCompleted(String),
     ///Represents the result of successfully applying the settings.
     SettingsApplied,
     ///Represents the result of successfully resetting the settings.
     Reseted,
+    ///Represents the result of successfully resetting the settings of a single provider.
+    ///Contains the name of the provider.
+    ProviderReseted(String),
+    ///Represents the result of successfully reading a value from the settings.
+    SettingValue(Value),
+    ///Represents the result of successfully removing a value from the settings. Contains
+    ///the path of the removed value.
+    SettingUnset(String),
+    ///Represents the result of successfully listing the settings. Contains the paths and
+    ///the values, with the secret values hidden.
+    SettingsList(Vec<(String, String)>),
     ///Represents the result of successfully getting information about the current
     ///settings and weather providers.
     Info(Info),
+    ///Represents the result of successfully listing the profiles. Contains the name of
+    ///the profile in use and the names of all the profiles.
+    Profiles(String, Vec<String>),
+    ///Represents the result of successfully activating a profile. Contains the name of the profile.
+    ProfileChanged(String),
+    ///Represents the result of successfully creating a profile. Contains the name of the profile.
+    ProfileCreated(String),
+    ///Represents the result of successfully deleting a profile. Contains the name of the profile.
+    ProfileDeleted(String),
+    ///Represents the result of successfully saving a location. Contains the alias.
+    LocationAdded(String),
+    ///Represents the result of successfully listing the saved locations. Contains the alias
+    ///of the default location, the aliases with their locations and the groups with their members.
+    Locations(
+        Option<String>,
+        Vec<(String, String)>,
+        Vec<(String, Vec<String>)>,
+    ),
+    ///Represents the result of successfully removing a location. Contains the alias.
+    LocationRemoved(String),
+    ///Represents the result of successfully changing the default location. Contains the alias.
+    DefaultLocationChanged(String),
+    ///Represents the result of successfully saving a location group. Contains the name.
+    LocationGroupAdded(String),
+    ///Represents the result of successfully removing a location group. Contains the name.
+    LocationGroupRemoved(String),
+    ///Represents the result of getting the weather for several locations. Contains the requested
+    ///date and, for every location, its name with the weather or the error message.
+    WeatherTable(Option<NaiveDate>, Vec<(String, Result<Weather, String>)>),
+    ///Represents the result of successfully getting the weather for a date range. Contains the
+    ///name of the location, the weather for every day and the summary of the days.
+    History(String, Vec<(NaiveDate, Weather)>, Option<HistorySummary>),
+    ///Represents the result of successfully processing all the batch rows, some of which may
+    ///have failed. Contains the counters of the rows.
+    BatchCompleted(BatchSummary),
+    ///Represents the end of watching the weather, interrupted or after the requested number of
+    ///refreshes. Contains the name of the location.
+    WatchStopped(String),
+    ///Represents the result of successfully getting the alerts. Contains the name of the location
+    ///and the alerts which have not expired yet.
+    Alerts(String, Vec<Alert>),
+    ///Represents the result of successfully getting the air quality. Contains the name of the
+    ///location and the air quality.
+    AirQuality(String, AirQuality),
+    ///Represents the result of successfully getting the sun and moon events. Contains the name of
+    ///the location, the date and the events.
+    Astronomy(String, NaiveDate, Astronomy),
+    ///Represents the result of calculating the sun events offline. Contains the name of the
+    ///location, the date and the calculated day.
+    SolarDay(String, NaiveDate, SolarDay),
+    ///Represents the result of successfully checking the weather. Contains the name of the
+    ///location, the requested date, the outcome of every rule and whether to explain them.
+    Check(String, Option<NaiveDate>, Vec<RuleCheck>, bool),
+    ///Represents the end of serving the weather or its metrics over HTTP after an interruption.
+    ///Contains the address the server listened on.
+    ServerStopped(SocketAddr),
+    ///Represents the generated content, like a completion script or a man page, which
+    ///is written to the output as is.
+    Generated(Vec<u8>),
+}
+
+impl WeatherCommandResult {
+    /// The exit code of the process, a failure when alerts are in effect or a check rule matches
+    /// so the scripts can react to them.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            WeatherCommandResult::Alerts(_, alerts)
+                if alerts.iter().any(|alert| alert.is_active_at(Utc::now())) =>
+            {
+                ExitCode::FAILURE
+            }
+            WeatherCommandResult::Check(_, _, checks, _) if checks.iter().any(|c| c.matched) => {
+                ExitCode::FAILURE
+            }
+            _ => ExitCode::SUCCESS,
+        }
+    }
+}
+
+/// Formats the error with its root cause on a single line, for the errors shown next to the
+/// other results instead of aborting the command.
+pub fn error_message(error: &(dyn StdError + 'static)) -> String {
+    let mut root = error;
+    while let Some(source) = root.source() {
+        root = source;
+    }
+    if std::ptr::eq(root, error) {
+        error.to_string()
+    } else {
+        format!("{error}: {root}")
+    }
 }
@@ -0,0 +1,34 @@
+use super::WeatherCommandResult;
+use crate::{args::AirArgs, AppError, Settings, SettingsError};
+use anyhow::anyhow;
+use config::Config;
+use weather_abstractions::ProviderManager;
+
+/// Retrieve the current air quality of a location.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * `SettingsError::NoDefaultLocation` if no address is given and no default location is saved
+/// * An error returned by the `get_provider` method of the `ProviderManager`
+/// * An error returned by the `get_air_quality` method of the selected provider, including the
+///   providers without the air quality
+pub async fn execute(
+    args: AirArgs,
+    provider_manger: &mut ProviderManager,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let provider = provider_manger.get_provider(&provider_name)?;
+    let addresses = Vec::from_iter(args.address);
+    let (name, location) = Settings::resolve_locations(&addresses).await?.remove(0);
+    let air_quality = provider
+        .get_air_quality(&location)
+        .await
+        .map_err(|e| AppError::Provider(anyhow!(e)))?;
+    Ok(WeatherCommandResult::AirQuality(name, air_quality))
+}
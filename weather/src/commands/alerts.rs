@@ -0,0 +1,37 @@
+use super::WeatherCommandResult;
+use crate::{args::AlertsArgs, AppError, Settings, SettingsError};
+use anyhow::anyhow;
+use config::Config;
+use weather_abstractions::ProviderManager;
+
+/// Retrieve the severe weather alerts of a location which have not expired yet.
+///
+/// The alerts in effect make the process exit with the code 1 and an error with the code 2, see
+/// `WeatherCommandResult::exit_code`.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * `SettingsError::NoDefaultLocation` if no address is given and no default location is saved
+/// * An error returned by the `get_provider` method of the `ProviderManager`
+/// * An error returned by the `get_alerts` method of the selected provider, including the
+///   providers without the alerts
+pub async fn execute(
+    args: AlertsArgs,
+    provider_manger: &mut ProviderManager,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let provider = provider_manger.get_provider(&provider_name)?;
+    let addresses = Vec::from_iter(args.address);
+    let (name, location) = Settings::resolve_locations(&addresses).await?.remove(0);
+    let alerts = provider
+        .get_alerts(&location)
+        .await
+        .map_err(|e| AppError::Provider(anyhow!(e)))?;
+    Ok(WeatherCommandResult::Alerts(name, alerts))
+}
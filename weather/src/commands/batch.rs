@@ -0,0 +1,519 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use chrono::NaiveDate;
+use config::Config;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use weather_abstractions::{
+    DateSpec, DateSpecError, DateTimeSpec, ProviderManager, TimeOfDay, Units, Weather, WeatherKind,
+    WeatherProvider,
+};
+
+use super::{error_message, WeatherCommandResult};
+use crate::{
+    args::{BatchArgs, BatchFormat},
+    cache::Cache,
+    rate_limit::RateLimiter,
+    AppError, Settings, SettingsError,
+};
+
+/// How long a fetched weather is reused for the same location and date.
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// A single normalized result of the batch, a row of the output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchRecord {
+    /// The address, or the location alias, as given in the input
+    pub address: String,
+    /// The date as given in the input, empty for the current weather
+    pub date: String,
+    /// The calendar date of the weather, the relative input dates are resolved at the location
+    pub resolved_date: Option<String>,
+    /// The local time of the hourly slot in RFC 3339 format, when the input date has a time
+    pub time: Option<String>,
+    /// The provider used to retrieve the weather
+    pub provider: String,
+    /// The kind of the weather: history, current or forecast
+    pub kind: Option<String>,
+    /// The temperature in Celsius
+    pub temp_c: Option<f32>,
+    /// The condition as described by the provider
+    pub condition: Option<String>,
+    /// The normalized condition
+    pub condition_kind: Option<String>,
+    /// The error message if the weather could not be retrieved
+    pub error: Option<String>,
+}
+
+/// The counters of the processed rows, reported when the batch is completed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// The rows retrieved successfully in this run
+    pub succeeded: usize,
+    /// The rows which failed in this run
+    pub failed: usize,
+    /// The rows skipped because the output already contained them
+    pub skipped: usize,
+}
+
+/// An input row: the address and the date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    address: String,
+    date: String,
+}
+
+/// An input row in the JSON Lines format, the date is optional.
+#[derive(Deserialize)]
+struct JsonRow {
+    address: String,
+    #[serde(default)]
+    date: Option<String>,
+}
+
+/// Execute the batch command.
+///
+/// This function reads the address and date rows, in the CSV or JSON Lines format of
+/// `args.input_format` or the input file extension, the dates may be relative like yesterday,
+/// retrieves the weather for them concurrently,
+/// at most `args.concurrency` at a time and `args.rate` requests per second, and writes a record
+/// for every row as soon as it is retrieved. The weather already retrieved for a location and
+/// date is reused from the cache. A row which failed is written with its error and doesn't stop
+/// the batch.
+///
+/// With `args.resume` the rows of the successful records of the output file are skipped and the
+/// failed ones are retried. The new records are appended to the output file, so the finished
+/// rows are never lost, even when the run is interrupted again.
+///
+/// # Arguments
+///
+/// * `args` - The arguments for the batch command.
+/// * `provider_manger` - The manager for all available weather providers.
+/// * `cfg` - The configuration that includes the currently set provider.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * An error returned by the `get_provider` method of the `ProviderManager`
+/// * `AppError::Output` if the input, or the output file to resume, could not be read or parsed,
+///   or the output could not be written
+pub async fn execute(
+    args: BatchArgs,
+    provider_manger: &mut ProviderManager,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let provider = provider_manger.get_provider(&provider_name)?;
+    let locations = Settings::locations().await?;
+    let format = args
+        .format
+        .or_else(|| args.output.as_deref().map(format_of))
+        .unwrap_or(BatchFormat::Jsonl);
+
+    let input_format = args
+        .input_format
+        .unwrap_or_else(|| input_format_of(args.input.as_deref()));
+    let rows = read_rows(args.input.as_deref(), input_format)?;
+    let kept = match (&args.output, args.resume) {
+        (Some(output), true) if output.exists() => read_records(output, format)?
+            .into_iter()
+            .filter(|record| record.error.is_none())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let done: HashSet<(&str, &str)> = kept
+        .iter()
+        .map(|record| (record.address.as_str(), record.date.as_str()))
+        .collect();
+    let (skipped, rows): (Vec<Row>, Vec<Row>) = rows
+        .into_iter()
+        .partition(|row| done.contains(&(row.address.as_str(), row.date.as_str())));
+
+    let (output, headers): (Box<dyn Write>, bool) = match &args.output {
+        Some(output) if args.resume => {
+            let file = OpenOptions::new().create(true).append(true).open(output)?;
+            // The CSV header is already written to a file which is not empty
+            let empty = file.metadata()?.len() == 0;
+            (Box::new(BufWriter::new(file)), empty)
+        }
+        Some(output) => (Box::new(BufWriter::new(File::create(output)?)), true),
+        None => (Box::new(io::stdout().lock()), true),
+    };
+    let mut writer = RecordWriter::new(output, format, headers);
+
+    let fetcher = &Fetcher {
+        provider,
+        weather: Cache::new(CACHE_TTL),
+        current: Cache::new(CACHE_TTL),
+        local_dates: Cache::new(CACHE_TTL),
+        limiter: RateLimiter::per_second(args.rate),
+        locations,
+    };
+    let provider_name = provider_name.as_str();
+    let mut records = stream::iter(rows)
+        .map(|row| async move {
+            let weather = fetcher.fetch(&row).await;
+            to_record(row, provider_name, weather)
+        })
+        .buffer_unordered(args.concurrency.into());
+
+    let mut summary = BatchSummary {
+        skipped: skipped.len(),
+        ..Default::default()
+    };
+    while let Some(record) = records.next().await {
+        match record.error {
+            None => summary.succeeded += 1,
+            Some(_) => summary.failed += 1,
+        }
+        writer.write(&record)?;
+    }
+    Ok(WeatherCommandResult::BatchCompleted(summary))
+}
+
+/// Retrieves the weather for the rows, shared by the concurrent requests.
+struct Fetcher<'a> {
+    provider: &'a dyn WeatherProvider,
+    weather: Cache<(String, NaiveDate, Option<TimeOfDay>), Weather>,
+    current: Cache<String, Weather>,
+    local_dates: Cache<String, NaiveDate>,
+    limiter: RateLimiter,
+    locations: BTreeMap<String, String>,
+}
+
+impl Fetcher<'_> {
+    /// Retrieves the weather for the row, from the cache when the same location, date and time
+    /// was already retrieved. Returns the resolved date for the relative and exact dates.
+    async fn fetch(&self, row: &Row) -> Result<(Option<NaiveDate>, Weather), String> {
+        let location = self.locations.get(&row.address).unwrap_or(&row.address);
+        let (date, time) = match row.date.as_str() {
+            "" => (None, None),
+            date => {
+                let DateTimeSpec { date, time } =
+                    date.parse().map_err(|e: DateSpecError| e.to_string())?;
+                (Some(self.resolve(date, location).await?), time)
+            }
+        };
+        let cached = match date {
+            Some(date) => self.weather.get(&(location.clone(), date, time)),
+            None => self.current.get(location),
+        };
+        if let Some(weather) = cached {
+            return Ok((date, weather));
+        }
+        self.limiter.acquire().await;
+        let weather = match (date, time) {
+            (Some(date), Some(time)) => self.provider.get_weather_at(location, date, time).await,
+            _ => self.provider.get_weather(location, date).await,
+        }
+        .map_err(|e| error_message(e.as_ref()))?;
+        match date {
+            Some(date) => self
+                .weather
+                .insert((location.clone(), date, time), weather.clone()),
+            None => self.current.insert(location.clone(), weather.clone()),
+        }
+        Ok((date, weather))
+    }
+
+    /// Resolves the date at the location, the current date of the location is cached.
+    async fn resolve(&self, date: DateSpec, location: &str) -> Result<NaiveDate, String> {
+        if let DateSpec::Date(date) = date {
+            return Ok(date);
+        }
+        let today = match self.local_dates.get(&location.to_string()) {
+            Some(today) => today,
+            None => {
+                self.limiter.acquire().await;
+                let today = self
+                    .provider
+                    .local_date(location)
+                    .await
+                    .map_err(|e| error_message(e.as_ref()))?;
+                self.local_dates.insert(location.to_string(), today);
+                today
+            }
+        };
+        Ok(date.resolve(today))
+    }
+}
+
+fn to_record(
+    row: Row,
+    provider: &str,
+    weather: Result<(Option<NaiveDate>, Weather), String>,
+) -> BatchRecord {
+    let mut record = BatchRecord {
+        address: row.address,
+        date: row.date,
+        resolved_date: None,
+        time: None,
+        provider: provider.to_string(),
+        kind: None,
+        temp_c: None,
+        condition: None,
+        condition_kind: None,
+        error: None,
+    };
+    match weather {
+        Ok((date, weather)) => {
+            record.resolved_date = date.map(|date| date.to_string());
+            record.time = weather.time.map(|time| time.to_rfc3339());
+            let kind = match weather.kind {
+                WeatherKind::History => "history",
+                WeatherKind::Current => "current",
+                WeatherKind::Forecast => "forecast",
+            };
+            record.kind = Some(kind.to_string());
+            record.temp_c = Some((weather.temp.to_value(Units::Metric) * 10.0).round() / 10.0);
+            record.condition = Some(weather.condition);
+            record.condition_kind = Some(format!("{:?}", weather.condition_kind));
+        }
+        Err(error) => record.error = Some(error),
+    }
+    record
+}
+
+/// Returns the format matching the extension of the file, JSON Lines for unknown extensions.
+fn format_of(path: &Path) -> BatchFormat {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => BatchFormat::Csv,
+        _ => BatchFormat::Jsonl,
+    }
+}
+
+/// Returns the format of the input file, JSON Lines for the `.jsonl` and `.ndjson` extensions
+/// and CSV for the others and the standard input.
+fn input_format_of(input: Option<&Path>) -> BatchFormat {
+    match input.and_then(Path::extension).and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("jsonl") || ext.eq_ignore_ascii_case("ndjson") => {
+            BatchFormat::Jsonl
+        }
+        _ => BatchFormat::Csv,
+    }
+}
+
+/// Reads the rows from the file, or from the standard input for no path or `-`.
+fn read_rows(input: Option<&Path>, format: BatchFormat) -> io::Result<Vec<Row>> {
+    let parse = |input: Box<dyn io::Read>| match format {
+        BatchFormat::Csv => parse_rows(input),
+        BatchFormat::Jsonl => parse_json_rows(BufReader::new(input)),
+    };
+    match input {
+        Some(path) if path != Path::new("-") => parse(Box::new(File::open(path)?)),
+        _ => parse(Box::new(io::stdin().lock())),
+    }
+}
+
+/// Parses the `address,date` CSV rows, the optional header row starting with `address` is skipped.
+fn parse_rows(input: impl io::Read) -> io::Result<Vec<Row>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(input);
+    let mut rows = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record?;
+        let address = record.get(0).unwrap_or_default();
+        if address.is_empty() || (i == 0 && address.eq_ignore_ascii_case("address")) {
+            continue;
+        }
+        rows.push(Row {
+            address: address.to_string(),
+            date: record.get(1).unwrap_or_default().to_string(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Parses the `{"address": ..., "date": ...}` rows, one per line, the empty lines and addresses
+/// are skipped.
+fn parse_json_rows(input: impl BufRead) -> io::Result<Vec<Row>> {
+    let mut rows = Vec::new();
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row: JsonRow = serde_json::from_str(&line)?;
+        let address = row.address.trim();
+        if address.is_empty() {
+            continue;
+        }
+        rows.push(Row {
+            address: address.to_string(),
+            date: row.date.unwrap_or_default().trim().to_string(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Reads the records written by a previous run.
+fn read_records(path: &Path, format: BatchFormat) -> io::Result<Vec<BatchRecord>> {
+    let file = File::open(path)?;
+    match format {
+        BatchFormat::Csv => csv::Reader::from_reader(file)
+            .deserialize()
+            .map(|record| record.map_err(io::Error::from))
+            .collect(),
+        BatchFormat::Jsonl => BufReader::new(file)
+            .lines()
+            .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect(),
+    }
+}
+
+/// Writes the records in the output format, flushing every record so an interrupted run can
+/// be resumed.
+enum RecordWriter {
+    Csv(Box<csv::Writer<Box<dyn Write>>>),
+    Jsonl(Box<dyn Write>),
+}
+
+impl RecordWriter {
+    /// Creates the writer, the CSV header row is written before the first record if `headers`
+    /// is set.
+    fn new(output: Box<dyn Write>, format: BatchFormat, headers: bool) -> Self {
+        match format {
+            BatchFormat::Csv => RecordWriter::Csv(Box::new(
+                csv::WriterBuilder::new()
+                    .has_headers(headers)
+                    .from_writer(output),
+            )),
+            BatchFormat::Jsonl => RecordWriter::Jsonl(output),
+        }
+    }
+
+    fn write(&mut self, record: &BatchRecord) -> io::Result<()> {
+        match self {
+            RecordWriter::Csv(writer) => {
+                writer.serialize(record)?;
+                writer.flush()
+            }
+            RecordWriter::Jsonl(writer) => {
+                serde_json::to_writer(&mut *writer, record)?;
+                writeln!(writer)?;
+                writer.flush()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rows_skips_header_and_empty_addresses() {
+        let input = "address,date\nLondon,2024-05-01\n\"Kyiv, Ukraine\"\n,2024-05-02\nhome , \n";
+
+        let rows = parse_rows(input.as_bytes()).unwrap();
+
+        let expected = [
+            ("London", "2024-05-01"),
+            ("Kyiv, Ukraine", ""),
+            ("home", ""),
+        ]
+        .map(|(address, date)| Row {
+            address: address.to_string(),
+            date: date.to_string(),
+        });
+        assert_eq!(rows, expected);
+    }
+
+    #[test]
+    fn parse_json_rows_skips_empty_lines_and_addresses() {
+        let input = concat!(
+            "{\"address\": \"London, UK\", \"date\": \"2024-05-01\"}\n",
+            "\n",
+            "{\"address\": \"Kyiv\", \"date\": null}\n",
+            "{\"address\": \" \", \"date\": \"2024-05-02\"}\n",
+            "{\"address\": \"home \"}\n",
+        );
+
+        let rows = parse_json_rows(input.as_bytes()).unwrap();
+
+        let expected =
+            [("London, UK", "2024-05-01"), ("Kyiv", ""), ("home", "")].map(|(address, date)| Row {
+                address: address.to_string(),
+                date: date.to_string(),
+            });
+        assert_eq!(rows, expected);
+        assert!(parse_json_rows("London,2024-05-01\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn input_format_from_extension() {
+        assert_eq!(
+            input_format_of(Some(Path::new("rows.JSONL"))),
+            BatchFormat::Jsonl
+        );
+        assert_eq!(
+            input_format_of(Some(Path::new("rows.ndjson"))),
+            BatchFormat::Jsonl
+        );
+        assert_eq!(
+            input_format_of(Some(Path::new("rows.csv"))),
+            BatchFormat::Csv
+        );
+        assert_eq!(input_format_of(None), BatchFormat::Csv);
+    }
+
+    #[test]
+    fn csv_records_can_be_read_back() {
+        let path = std::env::temp_dir().join(format!("weather-batch-{}.csv", std::process::id()));
+        let records = [
+            to_record(
+                Row {
+                    address: "London".to_string(),
+                    date: "2024-05-01".to_string(),
+                },
+                "weather-api",
+                Ok((
+                    NaiveDate::from_ymd_opt(2024, 5, 1),
+                    Weather::history(weather_abstractions::Temperature::Celsius(12.34), "Sunny"),
+                )),
+            ),
+            to_record(
+                Row {
+                    address: "Nowhere".to_string(),
+                    date: String::new(),
+                },
+                "weather-api",
+                Err("No matching location found.".to_string()),
+            ),
+        ];
+        let mut writer = RecordWriter::new(
+            Box::new(File::create(&path).unwrap()),
+            BatchFormat::Csv,
+            true,
+        );
+        writer.write(&records[0]).unwrap();
+        drop(writer);
+        // A resumed run appends the records without the header
+        let file = OpenOptions::new().append(true).open(&path).unwrap();
+        let mut writer = RecordWriter::new(Box::new(file), BatchFormat::Csv, false);
+        writer.write(&records[1]).unwrap();
+        drop(writer);
+
+        let read = read_records(&path, BatchFormat::Csv).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(read, records);
+        assert_eq!(read[0].temp_c, Some(12.3));
+        assert_eq!(read[0].resolved_date.as_deref(), Some("2024-05-01"));
+        assert_eq!(read[0].condition_kind.as_deref(), Some("Clear"));
+    }
+}
@@ -0,0 +1,193 @@
+use super::{get::get_weather, WeatherCommandResult};
+use crate::{
+    args::{CheckArgs, Condition},
+    AppError, Settings, SettingsError,
+};
+use anyhow::anyhow;
+use clap::ValueEnum;
+use config::Config;
+use std::fmt::{self, Display};
+use weather_abstractions::{ConditionKind, ProviderManager, Units, Weather};
+
+/// Check the weather of a location against the rules of the arguments.
+///
+/// A matching rule makes the process exit with the code 1 and an error with the code 2, see
+/// `WeatherCommandResult::exit_code`, so the command can be used in the `if` statements.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * `SettingsError::NoDefaultLocation` if no address is given and no default location is saved
+/// * `AppError::MissingValue` if the provider doesn't return the value a rule needs
+/// * An error returned by the `get_provider` method of the `ProviderManager`
+/// * An error returned by the `get_weather` method of the selected provider
+pub async fn execute(
+    args: CheckArgs,
+    provider_manger: &mut ProviderManager,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let provider = provider_manger.get_provider(&provider_name)?;
+    let addresses = Vec::from_iter(args.address.clone());
+    let (name, location) = Settings::resolve_locations(&addresses).await?.remove(0);
+    let (date, weather) = get_weather(provider, &location, args.date, false, false)
+        .await
+        .map_err(|e| AppError::Provider(anyhow!(e)))?;
+    let checks = rules(&args)
+        .into_iter()
+        .map(|rule| rule.check(&weather))
+        .collect::<Result<_, _>>()?;
+    Ok(WeatherCommandResult::Check(
+        name,
+        date,
+        checks,
+        args.explain,
+    ))
+}
+
+/// A rule of the check, the temperatures are in degrees Celsius and the wind speed in meters
+/// per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {
+    TempBelow(f32),
+    TempAbove(f32),
+    Rain,
+    WindAbove(f32),
+    Condition(Condition),
+}
+
+/// The outcome of a rule, with the checked value for the explanation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleCheck {
+    pub rule: Rule,
+    pub value: String,
+    pub matched: bool,
+}
+
+/// The rules in the order of the options, one for every condition.
+fn rules(args: &CheckArgs) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    rules.extend(args.temp_below.map(Rule::TempBelow));
+    rules.extend(args.temp_above.map(Rule::TempAbove));
+    if args.rain {
+        rules.push(Rule::Rain);
+    }
+    rules.extend(args.wind_above.map(Rule::WindAbove));
+    rules.extend(args.condition.iter().copied().map(Rule::Condition));
+    rules
+}
+
+impl Rule {
+    /// Matches the rule against the weather.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::MissingValue` if the weather doesn't have the value of the rule, like
+    /// the wind speed for the providers which don't return it.
+    pub fn check(self, weather: &Weather) -> Result<RuleCheck, AppError> {
+        let temp = weather.temp.to_value(Units::Metric);
+        let temp_value = || weather.temp.to_string_value(Units::Metric);
+        let (value, matched) = match self {
+            Rule::TempBelow(limit) => (temp_value(), temp < limit),
+            Rule::TempAbove(limit) => (temp_value(), temp > limit),
+            Rule::Rain => (weather.condition.clone(), weather.condition_kind.is_rain()),
+            Rule::WindAbove(limit) => {
+                let wind_speed = weather
+                    .wind_speed
+                    .ok_or(AppError::MissingValue("wind speed"))?;
+                (format!("{wind_speed:.1} m/s"), wind_speed > limit)
+            }
+            Rule::Condition(condition) => (
+                weather.condition.clone(),
+                weather.condition_kind == ConditionKind::from(condition),
+            ),
+        };
+        Ok(RuleCheck {
+            rule: self,
+            value,
+            matched,
+        })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::TempBelow(limit) => write!(f, "temperature below {limit:.1}°C"),
+            Rule::TempAbove(limit) => write!(f, "temperature above {limit:.1}°C"),
+            Rule::Rain => f.write_str("rain"),
+            Rule::WindAbove(limit) => write!(f, "wind above {limit:.1} m/s"),
+            Rule::Condition(condition) => {
+                let value = condition
+                    .to_possible_value()
+                    .expect("no skipped conditions");
+                write!(f, "condition {}", value.get_name())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use weather_abstractions::Temperature;
+
+    fn weather(temp: f32, condition: &str) -> Weather {
+        Weather::current(Temperature::from_c(temp).unwrap(), condition)
+            .with_condition_kind(ConditionKind::from_description(condition))
+    }
+
+    #[test]
+    fn temperature_rules() {
+        let cold = weather(-2.5, "Clear");
+        assert!(Rule::TempBelow(0.0).check(&cold).unwrap().matched);
+        assert!(!Rule::TempAbove(0.0).check(&cold).unwrap().matched);
+        assert!(!Rule::TempBelow(-2.5).check(&cold).unwrap().matched);
+        assert_eq!(Rule::TempBelow(0.0).check(&cold).unwrap().value, "-2.5°C");
+    }
+
+    #[test]
+    fn rain_includes_thunderstorm() {
+        let storm = weather(20.0, "Thundery outbreaks possible");
+        assert!(Rule::Rain.check(&storm).unwrap().matched);
+        assert!(
+            Rule::Condition(Condition::Thunderstorm)
+                .check(&storm)
+                .unwrap()
+                .matched
+        );
+        assert!(
+            !Rule::Condition(Condition::Rain)
+                .check(&storm)
+                .unwrap()
+                .matched
+        );
+        assert!(!Rule::Rain.check(&weather(20.0, "Sunny")).unwrap().matched);
+    }
+
+    #[test]
+    fn wind_rule_requires_wind_speed() {
+        let calm = weather(10.0, "Sunny");
+        assert!(matches!(
+            Rule::WindAbove(15.0).check(&calm),
+            Err(AppError::MissingValue("wind speed"))
+        ));
+        let windy = calm.with_wind_speed(16.0);
+        let check = Rule::WindAbove(15.0).check(&windy).unwrap();
+        assert!(check.matched);
+        assert_eq!(check.value, "16.0 m/s");
+    }
+
+    #[test]
+    fn rule_descriptions() {
+        assert_eq!(Rule::TempBelow(0.0).to_string(), "temperature below 0.0°C");
+        assert_eq!(
+            Rule::Condition(Condition::HeavyRain).to_string(),
+            "condition heavy-rain"
+        );
+    }
+}
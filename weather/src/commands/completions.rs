@@ -0,0 +1,148 @@
+use clap::{builder::PossibleValuesParser, Command, CommandFactory};
+use clap_complete::generate;
+use weather_abstractions::ProviderManager;
+
+use super::WeatherCommandResult;
+use crate::{
+    args::{CompletionsArgs, WeatherCliArgs},
+    settings::{Settings, SettingsError},
+    APP_NAME,
+};
+
+/// The sub-commands with a location argument, which accepts the saved aliases and the @groups.
+const LOCATION_COMMANDS: &[&str] = &["get", "history", "watch", "alerts", "air", "sun", "check"];
+
+/// Generates the completion script for the requested shell.
+///
+/// The provider names and the provider setting keys are taken from the `ProviderManager`, so the
+/// generated script completes every provider registered in the application. The location
+/// arguments complete the saved aliases and @groups, so the script should be generated again
+/// after they change.
+///
+/// # Arguments
+///
+/// * `args` - The arguments for the completions command.
+/// * `provider_manger` - A reference to the ProviderManager.
+///
+/// # Errors
+///
+/// Returns a `SettingsError` if the saved locations could not be read.
+pub async fn execute(
+    args: CompletionsArgs,
+    provider_manger: &ProviderManager,
+) -> Result<WeatherCommandResult, SettingsError> {
+    let aliases: Vec<String> = Settings::locations().await?.into_keys().collect();
+    let groups: Vec<String> = Settings::location_groups()
+        .await?
+        .into_keys()
+        .map(|name| format!("@{name}"))
+        .collect();
+    let mut cmd = with_locations(cli_command(provider_manger), &aliases, &groups);
+    let mut script = Vec::new();
+    generate(args.shell, &mut cmd, APP_NAME, &mut script);
+    Ok(WeatherCommandResult::Generated(script))
+}
+
+/// Builds the CLI `Command` with the values known only at runtime attached to the arguments.
+pub fn cli_command(provider_manger: &ProviderManager) -> Command {
+    let mut providers = provider_manger.get_list_providers();
+    providers.sort_unstable();
+    let mut keys: Vec<&str> = providers
+        .iter()
+        .flat_map(|p| provider_manger.get_settings_schema(p))
+        .map(|setting| setting.key)
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+    let providers: Vec<String> = providers.into_iter().map(String::from).collect();
+
+    WeatherCliArgs::command()
+        .name(APP_NAME)
+        .mut_subcommand("configure", |configure| {
+            configure.mut_args(|arg| match arg.get_id().as_str() {
+                "provider" => arg.value_parser(PossibleValuesParser::new(&providers)),
+                "key" => arg.value_parser(PossibleValuesParser::new(&keys)),
+                _ => arg,
+            })
+        })
+}
+
+/// Attaches the saved aliases and the @groups to the location arguments, and the aliases or the
+/// groups to the arguments of the `location` sub-commands which name a saved one. The values are
+/// fixed when the script is generated, see the help of the `completions` sub-command.
+fn with_locations(cmd: Command, aliases: &[String], groups: &[String]) -> Command {
+    let locations: Vec<String> = aliases.iter().chain(groups).cloned().collect();
+    let cmd = LOCATION_COMMANDS.iter().fold(cmd, |cmd, name| {
+        cmd.mut_subcommand(name, |command| {
+            command.mut_args(|arg| match arg.get_id().as_str() {
+                "address" | "addresses" => arg.value_parser(PossibleValuesParser::new(&locations)),
+                _ => arg,
+            })
+        })
+    });
+    let names: Vec<String> = groups.iter().map(|group| group[1..].to_string()).collect();
+    cmd.mut_subcommand("location", |location| {
+        location
+            .mut_subcommand("remove", |remove| {
+                remove.mut_arg("alias", |arg| {
+                    arg.value_parser(PossibleValuesParser::new(aliases))
+                })
+            })
+            .mut_subcommand("default", |default| {
+                default.mut_arg("alias", |arg| {
+                    arg.value_parser(PossibleValuesParser::new(aliases))
+                })
+            })
+            .mut_subcommand("ungroup", |ungroup| {
+                ungroup.mut_arg("name", |arg| {
+                    arg.value_parser(PossibleValuesParser::new(&names))
+                })
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use weather_abstractions::ProviderManagerBuilder;
+
+    fn possible_values(cmd: &Command, path: &[&str], arg: &str) -> Vec<String> {
+        let command = path.iter().fold(cmd, |cmd, name| {
+            cmd.find_subcommand(name).expect("the sub-command exists")
+        });
+        let arg = command
+            .get_arguments()
+            .find(|a| a.get_id() == arg)
+            .expect("the argument exists");
+        arg.get_possible_values()
+            .iter()
+            .map(|value| value.get_name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn location_arguments_complete_aliases_and_groups() {
+        let manager = ProviderManagerBuilder::default().build();
+        let aliases = vec!["home".to_string(), "office".to_string()];
+        let groups = vec!["@trip".to_string()];
+        let cmd = with_locations(cli_command(&manager), &aliases, &groups);
+
+        let locations = ["home", "office", "@trip"];
+        assert_eq!(possible_values(&cmd, &["get"], "addresses"), locations);
+        for name in LOCATION_COMMANDS.iter().filter(|name| **name != "get") {
+            assert_eq!(possible_values(&cmd, &[name], "address"), locations);
+        }
+        assert_eq!(
+            possible_values(&cmd, &["location", "remove"], "alias"),
+            ["home", "office"]
+        );
+        assert_eq!(
+            possible_values(&cmd, &["location", "default"], "alias"),
+            ["home", "office"]
+        );
+        assert_eq!(
+            possible_values(&cmd, &["location", "ungroup"], "name"),
+            ["trip"]
+        );
+    }
+}
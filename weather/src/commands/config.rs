@@ -0,0 +1,73 @@
+use serde_json::Value;
+use weather_abstractions::ProviderManager;
+
+use super::{info::hide_sensetive, WeatherCommandResult};
+use crate::{
+    args::ConfigCommand,
+    settings::{Settings, SettingsError},
+};
+
+/// Execute the config command.
+///
+/// This function reads, removes or lists the values in the settings, addressed by the slash
+/// separated paths used by `weather configure`.
+///
+/// # Arguments
+///
+/// * `command` - The config sub-command to execute.
+/// * `provider_manger` - A reference to the ProviderManager, used to hide the secret settings in the list.
+/// * `settings` - The settings of the active profile.
+///
+/// # Errors
+///
+/// Returns `SettingsError::Path` if there is no value for the given path, or another
+/// `SettingsError` if the settings file could not be read or written.
+pub async fn execute(
+    command: ConfigCommand,
+    provider_manger: &ProviderManager,
+    settings: &Settings,
+) -> Result<WeatherCommandResult, SettingsError> {
+    match command {
+        ConfigCommand::Get { path } => {
+            let value = settings.get(&path).await?;
+            Ok(WeatherCommandResult::SettingValue(value))
+        }
+        ConfigCommand::Unset { path } => {
+            settings.unset(&path).await?;
+            Ok(WeatherCommandResult::SettingUnset(path))
+        }
+        ConfigCommand::List => {
+            let values = settings
+                .list()
+                .await?
+                .into_iter()
+                .map(|(path, value)| {
+                    let mut value = to_plain_string(value);
+                    if is_secret(provider_manger, &path) {
+                        hide_sensetive(&mut value);
+                    }
+                    (path, value)
+                })
+                .collect();
+            Ok(WeatherCommandResult::SettingsList(values))
+        }
+    }
+}
+
+/// Returns the string without quotes, or the JSON representation for other values.
+pub fn to_plain_string(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        value => value.to_string(),
+    }
+}
+
+fn is_secret(provider_manger: &ProviderManager, path: &str) -> bool {
+    let Some((provider, key)) = path.split_once('/') else {
+        return false;
+    };
+    provider_manger
+        .get_settings_schema(provider)
+        .iter()
+        .any(|setting| setting.secret && setting.key == key)
+}
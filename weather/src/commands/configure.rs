@@ -1,5 +1,6 @@
-use super::WeatherCommandResult;
+use super::{setup::verify, WeatherCommandResult};
 use crate::{args::ConfigureArgs, AppError, Settings};
+use config::Config;
 use weather_abstractions::ProviderManager;
 
 /// Execute the configure command.
@@ -10,6 +11,8 @@ use weather_abstractions::ProviderManager;
 ///
 /// * `args` - The arguments for the configure command.
 /// * `provider_manager` - A reference to the ProviderManager.
+/// * `settings` - The settings of the active profile, where the values are saved.
+/// * `cfg` - The current configuration, used to verify the settings when `--verify` is given.
 ///
 /// # Returns
 ///
@@ -22,10 +25,13 @@ use weather_abstractions::ProviderManager;
 /// This function can return the following errors:
 ///
 /// * If the provider specified in the arguments is not supported.
+/// * If the verification was requested and the provider rejected the settings.
 /// * If an error occurred while setting the API key or provider in the settings.
 pub async fn execute(
     args: ConfigureArgs,
     provider_manger: &ProviderManager,
+    settings: &Settings,
+    cfg: &Config,
 ) -> Result<WeatherCommandResult, AppError> {
     provider_manger.is_supported(&args.provider)?;
     match args {
@@ -33,13 +39,25 @@ pub async fn execute(
             key: Some(key),
             value: Some(value),
             provider,
+            verify: should_verify,
         } => {
             let path = format!("{provider}/{key}");
-            Settings::set(&path, &value).await?;
+            if should_verify {
+                let values = [(path.clone(), value.clone())];
+                verify(Settings::with_overrides(cfg, &values)?, &provider).await?;
+            }
+            settings.set(&path, &value).await?;
             Ok(WeatherCommandResult::SettingsApplied)
         }
-        ConfigureArgs { provider, .. } => {
-            Settings::set("provider", &provider).await?;
+        ConfigureArgs {
+            provider,
+            verify: should_verify,
+            ..
+        } => {
+            if should_verify {
+                verify(cfg.clone(), &provider).await?;
+            }
+            settings.set("provider", &provider).await?;
             Ok(WeatherCommandResult::ProviderChanged(provider))
         }
     }
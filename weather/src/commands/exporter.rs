@@ -0,0 +1,238 @@
+use super::{error_message, WeatherCommandResult};
+use crate::{args::ExporterArgs, metrics::Metrics, server, AppError, Settings, SettingsError};
+use config::Config;
+use futures::future::join_all;
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+use std::{net::TcpListener, rc::Rc, time::Instant};
+use tokio::task::LocalSet;
+use tracing::Instrument;
+use weather_abstractions::{Coordinates, ProviderManager, WeatherProvider};
+
+/// Export the current weather of the locations as the Prometheus metrics at `/metrics`, until
+/// interrupted.
+///
+/// The weather is requested every `args.interval` through the configured provider, a location
+/// which failed keeps its last weather and counts the error in the metrics.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * `SettingsError::NoDefaultLocation` if no location is given and no default location is saved
+/// * `SettingsError::LocationGroupNotFound` if an unknown location group is given
+/// * An error returned by the `get_provider` method of the `ProviderManager`
+/// * `AppError::Bind` if the address can't be listened on
+/// * `AppError::Server` if the server fails to accept the connections
+pub async fn execute(
+    args: ExporterArgs,
+    provider_manger: &mut ProviderManager,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let provider = provider_manger.get_provider(&provider_name)?;
+    let addresses: Vec<String> = args
+        .locations
+        .iter()
+        .flat_map(|locations| split_locations(locations))
+        .collect();
+    let locations = Settings::resolve_locations(&addresses).await?;
+
+    let bind_error = |e| AppError::Bind(args.bind, e);
+    let listener = TcpListener::bind(args.bind).map_err(bind_error)?;
+    listener.set_nonblocking(true).map_err(bind_error)?;
+    let address = listener.local_addr().map_err(bind_error)?;
+    println!(
+        "Exporting the weather of {} locations on http://{address}/metrics.",
+        locations.len()
+    );
+
+    let metrics = Rc::new(Metrics::new([provider_name.as_str()]));
+    let handler = {
+        let metrics = Rc::clone(&metrics);
+        move |request: Request<Body>| {
+            let metrics = Rc::clone(&metrics);
+            async move { respond(&request, &metrics) }
+        }
+    };
+    let refreshes = async {
+        loop {
+            // Every refresh is a trace of its own, not a part of the long running command
+            let span = tracing::info_span!(parent: None, "refresh", locations = locations.len());
+            refresh(provider, &provider_name, &locations, &metrics)
+                .instrument(span)
+                .await;
+            tokio::time::sleep(args.interval).await;
+        }
+    };
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    LocalSet::new()
+        .run_until(async {
+            tokio::select! {
+                result = server::serve(handler, listener, shutdown) => result,
+                _ = refreshes => Ok(()),
+            }
+        })
+        .await?;
+    Ok(WeatherCommandResult::ServerStopped(address))
+}
+
+/// Requests the current weather of all the locations concurrently and records it with the
+/// requests in the metrics. The errors are reported on the standard error.
+async fn refresh(
+    provider: &dyn WeatherProvider,
+    provider_name: &str,
+    locations: &[(String, String)],
+    metrics: &Metrics,
+) {
+    let requests = locations.iter().map(|(name, location)| async move {
+        let start = Instant::now();
+        let weather = provider.get_weather(location, None).await;
+        metrics.record_request(provider_name, start.elapsed(), weather.is_err());
+        match weather {
+            Ok(weather) => metrics.record_weather(name, provider_name, weather),
+            Err(e) => eprintln!("Unable to refresh {name}: {}", error_message(e.as_ref())),
+        }
+    });
+    join_all(requests).await;
+}
+
+fn respond(request: &Request<Body>, metrics: &Metrics) -> Response<Body> {
+    let (status, content_type, body) = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => (
+            StatusCode::OK,
+            "text/plain; version=0.0.4; charset=utf-8",
+            metrics.render(),
+        ),
+        (_, "/metrics") => (
+            StatusCode::METHOD_NOT_ALLOWED,
+            "text/plain",
+            "Only the GET method is allowed\n".to_string(),
+        ),
+        _ => (
+            StatusCode::NOT_FOUND,
+            "text/plain",
+            "The metrics are at /metrics\n".to_string(),
+        ),
+    };
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .expect("valid response")
+}
+
+/// Splits the comma separated locations, keeping the coordinates like `51.5,-0.13` together.
+/// A comma followed by a space is a part of the address, like in `London, UK`.
+fn split_locations(locations: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (i, _) in locations.match_indices(',') {
+        if !locations[i + 1..].starts_with(char::is_whitespace) {
+            parts.push(locations[start..i].trim());
+            start = i + 1;
+        }
+    }
+    parts.push(locations[start..].trim());
+    let mut locations = Vec::new();
+    let mut i = 0;
+    while i < parts.len() {
+        match parts.get(i + 1) {
+            Some(next)
+                if format!("{},{next}", parts[i])
+                    .parse::<Coordinates>()
+                    .is_ok() =>
+            {
+                locations.push(format!("{},{next}", parts[i]));
+                i += 2;
+            }
+            _ => {
+                locations.push(parts[i].to_string());
+                i += 1;
+            }
+        }
+    }
+    locations.retain(|location| !location.is_empty());
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{
+        matchers::{contains, request, url_decoded},
+        responders::{json_encoded, status_code},
+        Expectation, Server,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn coordinates_kept_together() {
+        assert_eq!(
+            split_locations("home,51.5,-0.13,office,London, UK,50.45, 30.52"),
+            vec!["home", "51.5,-0.13", "office", "London, UK", "50.45, 30.52"]
+        );
+        assert_eq!(split_locations("home,,"), vec!["home"]);
+    }
+
+    #[tokio::test]
+    async fn refresh_records_weather_and_errors() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::query(url_decoded(contains(("q", "Kyiv")))))
+                .respond_with(json_encoded(json!({
+                    "location": { "name": "Kyiv", "region": "", "country": "", "lat": 50.45, "lon": 30.52 },
+                    "current": {
+                        "last_updated": "2024-05-01 10:00",
+                        "temp_c": 18.0,
+                        "pressure_mb": 1016.0,
+                        "condition": { "text": "Sunny", "icon": "", "code": 1000 }
+                    }
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::query(url_decoded(contains(("q", "Nowhere")))))
+                .respond_with(
+                    status_code(400).body(
+                        json!({"error": {"code": 1006, "message": "No matching location found."}})
+                            .to_string(),
+                    ),
+                ),
+        );
+        let conf = Config::builder()
+            .set_override("weather-api.apiKey", "some-api-key")
+            .and_then(|conf| conf.set_override("weather-api.baseUrl", server.url_str("")))
+            .and_then(|conf| conf.build())
+            .expect("config built");
+        let provider = weather_api::WeatherApiBuilder::build(&conf).expect("provider built");
+        let locations = [
+            ("office".to_string(), "Kyiv".to_string()),
+            ("cabin".to_string(), "Nowhere".to_string()),
+        ];
+        let metrics = Metrics::new(["weather-api"]);
+
+        refresh(&provider, "weather-api", &locations, &metrics).await;
+
+        let request = Request::get("/metrics").body(Body::empty()).unwrap();
+        let response = respond(&request, &metrics);
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains(
+            "weather_temperature_celsius{location=\"office\",provider=\"weather-api\"} 18\n"
+        ));
+        assert!(text.contains(
+            "weather_pressure_hectopascals{location=\"office\",provider=\"weather-api\"} 1016\n"
+        ));
+        assert!(!text.contains("location=\"cabin\""));
+        assert!(text.contains("weather_provider_requests_total{provider=\"weather-api\"} 2\n"));
+        assert!(text.contains("weather_provider_errors_total{provider=\"weather-api\"} 1\n"));
+
+        let request = Request::get("/").body(Body::empty()).unwrap();
+        assert_eq!(respond(&request, &metrics).status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,29 +1,39 @@
-use super::WeatherCommandResult;
-use crate::{args::GetWeatherArgs, AppError, SettingsError};
+use super::{error_message, WeatherCommandResult};
+use crate::{args::GetWeatherArgs, AppError, Settings, SettingsError};
 use anyhow::anyhow;
+use chrono::{NaiveDate, Utc};
 use config::Config;
-use weather_abstractions::ProviderManager;
+use futures::{join, stream, StreamExt};
+use std::error::Error;
+use weather_abstractions::{DateTimeSpec, ProviderManager, Weather, WeatherProvider};
 
 /// Retrieve the weather information based on the given command arguments and the current settings
 ///
 /// # Arguments
 ///
-/// * `args` - The arguments given by the user, including the address and date for weather information
+/// * `args` - The arguments given by the user, including the addresses, the location aliases or groups and date for weather information
 /// * `provider_manager` - The manager for all available weather providers, used to determine which provider to use
 /// * `cfg` - The configuration that includes the currently set provider
 ///
 /// # Returns
 ///
 /// A `Result` that either contains the retrieved weather information wrapped in `WeatherCommandResult` or an error
-/// indicating the reason for failure.
+/// indicating the reason for failure. When several locations are requested, they are fetched concurrently, at most
+/// `args.concurrency` at a time, and the failure for a location is returned in the table instead of an error.
+/// The current weather comes with the alerts in effect when `args.alerts` is set, and with the air
+/// quality when `args.air` is set.
 ///
 /// # Errors
 ///
 /// This function may return the following errors:
 ///
 /// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * `SettingsError::NoDefaultLocation` if no address is given and no default location is saved
+/// * `SettingsError::LocationGroupNotFound` if an unknown location group is given
+/// * `DateSpecError` if the date is not valid
 /// * An error returned by the `get_provider` method of the `ProviderManager`
-/// * An error returned by the `get_weather` method of the selected provider
+/// * An error returned by the `get_weather`, `get_alerts` or `get_air_quality` method of the
+///   selected provider
 /// * An error wrapping any unexpected failure, including I/O errors
 pub async fn execute(
     args: GetWeatherArgs,
@@ -34,10 +44,84 @@ pub async fn execute(
         .get_string("provider")
         .map_err(|_| SettingsError::ProviderNotSet)?;
     let provider = provider_manger.get_provider(&provider_name)?;
+    let (addresses, date) = args.addresses_and_date()?;
+    let mut locations = Settings::resolve_locations(addresses).await?;
 
-    let weather = provider
-        .get_weather(&args.address, args.date)
-        .await
-        .map_err(|e| AppError::Provider(anyhow!(e)))?;
-    Ok(WeatherCommandResult::Weather(args, weather))
+    if let [_] = locations.as_slice() {
+        let (name, location) = locations.remove(0);
+        let (date, weather) = get_weather(provider, &location, date, args.alerts, args.air)
+            .await
+            .map_err(|e| AppError::Provider(anyhow!(e)))?;
+        return Ok(WeatherCommandResult::Weather(name, date, weather));
+    }
+
+    let rows: Vec<_> = stream::iter(locations)
+        .map(|(name, location)| async move {
+            let weather = get_weather(provider, &location, date, args.alerts, args.air)
+                .await
+                .map_err(|e| error_message(e.as_ref()));
+            (name, weather)
+        })
+        .buffered(args.concurrency.into())
+        .collect()
+        .await;
+    // The relative dates are resolved for every location, the table shows the first one
+    let date = rows
+        .iter()
+        .find_map(|(_, weather)| weather.as_ref().ok()?.0)
+        .or_else(|| date.map(|date| date.date.resolve(Utc::now().date_naive())));
+    let rows = rows
+        .into_iter()
+        .map(|(name, weather)| (name, weather.map(|(_, weather)| weather)))
+        .collect();
+    Ok(WeatherCommandResult::WeatherTable(date, rows))
+}
+
+/// Resolves the date at the location and retrieves the weather for it, for the closest hourly
+/// slot when a time of the day is given. The current weather comes with the alerts in effect if
+/// `alerts` is set and with the air quality if `air` is set.
+pub(crate) async fn get_weather(
+    provider: &dyn WeatherProvider,
+    location: &str,
+    date: Option<DateTimeSpec>,
+    alerts: bool,
+    air: bool,
+) -> Result<(Option<NaiveDate>, Weather), Box<dyn Error + Send + Sync + 'static>> {
+    let Some(DateTimeSpec { date, time }) = date else {
+        let air_quality = async {
+            if air {
+                Some(provider.get_air_quality(location).await)
+            } else {
+                None
+            }
+        };
+        let alerts = async {
+            if alerts {
+                Some(provider.get_alerts(location).await)
+            } else {
+                None
+            }
+        };
+        let (weather, alerts, air_quality) =
+            join!(provider.get_weather(location, None), alerts, air_quality);
+        let mut weather = weather?;
+        if let Some(alerts) = alerts {
+            let now = Utc::now();
+            let alerts = alerts?
+                .into_iter()
+                .filter(|alert| alert.is_active_at(now))
+                .collect();
+            weather = weather.with_alerts(alerts);
+        }
+        if let Some(air_quality) = air_quality {
+            weather = weather.with_air_quality(air_quality?);
+        }
+        return Ok((None, weather));
+    };
+    let date = date.resolve_for(provider, location).await?;
+    let weather = match time {
+        Some(time) => provider.get_weather_at(location, date, time).await?,
+        None => provider.get_weather(location, Some(date)).await?,
+    };
+    Ok((Some(date), weather))
 }
@@ -0,0 +1,154 @@
+use std::{collections::HashMap, error::Error};
+
+use anyhow::anyhow;
+use chrono::NaiveDate;
+use config::Config;
+use weather_abstractions::{ProviderManager, Units, Weather};
+
+use super::WeatherCommandResult;
+use crate::{args::HistoryArgs, AppError, Settings, SettingsError};
+
+/// The maximum number of days in the requested range.
+pub const MAX_HISTORY_DAYS: i64 = 366;
+
+/// The aggregates of the weather over the days of a range.
+#[derive(Debug, PartialEq)]
+pub struct HistorySummary {
+    /// The mean of the daily temperatures in Celsius
+    pub mean_temp: f32,
+    /// The lowest daily temperature in Celsius
+    pub min_temp: f32,
+    /// The highest daily temperature in Celsius
+    pub max_temp: f32,
+    /// The number of days with rain, drizzle or thunderstorm
+    pub rainy_days: usize,
+    /// The condition of the most days, the earliest one of the equally common conditions
+    pub most_common_condition: String,
+}
+
+impl HistorySummary {
+    /// Aggregates the weather of the days, returns `None` for no days.
+    pub fn new(days: &[(NaiveDate, Weather)]) -> Option<Self> {
+        let temps: Vec<f32> = days
+            .iter()
+            .map(|(_, weather)| weather.temp.to_value(Units::Metric))
+            .collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (_, weather) in days {
+            *counts.entry(&weather.condition).or_default() += 1;
+        }
+        let most_common_condition = days
+            .iter()
+            .map(|(_, weather)| weather.condition.as_str())
+            .rev()
+            .max_by_key(|condition| counts[condition])?;
+        Some(Self {
+            mean_temp: temps.iter().sum::<f32>() / temps.len() as f32,
+            min_temp: temps.iter().copied().fold(f32::INFINITY, f32::min),
+            max_temp: temps.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            rainy_days: days
+                .iter()
+                .filter(|(_, weather)| weather.condition_kind.is_rain())
+                .count(),
+            most_common_condition: most_common_condition.to_string(),
+        })
+    }
+}
+
+/// Execute the history command.
+///
+/// This function retrieves the weather for every day of the range with the provider's
+/// `get_weather_range`, which uses a single request for several days when the provider supports it.
+///
+/// # Arguments
+///
+/// * `args` - The arguments for the history command: the address or the location alias and the range,
+///   the relative dates of the range are resolved against the current date at the location.
+/// * `provider_manger` - The manager for all available weather providers.
+/// * `cfg` - The configuration that includes the currently set provider.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * `SettingsError::NoDefaultLocation` if no address is given and no default location is saved
+/// * An error returned by the `get_provider` method of the `ProviderManager`
+/// * An error returned by the `local_date` method of the selected provider for a relative date
+/// * `AppError::DateRange` if the range is empty or longer than `MAX_HISTORY_DAYS` days
+/// * An error returned by the `get_weather_range` method of the selected provider
+pub async fn execute(
+    args: HistoryArgs,
+    provider_manger: &mut ProviderManager,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let provider = provider_manger.get_provider(&provider_name)?;
+    let addresses = Vec::from_iter(args.address);
+    let (name, location) = Settings::resolve_locations(&addresses).await?.remove(0);
+    let provider_error = |e: Box<dyn Error + Send + Sync>| AppError::Provider(anyhow!(e));
+    let from = args
+        .from
+        .resolve_for(provider, &location)
+        .await
+        .map_err(provider_error)?;
+    let to = args
+        .to
+        .resolve_for(provider, &location)
+        .await
+        .map_err(provider_error)?;
+    let days = to.signed_duration_since(from).num_days() + 1;
+    if !(1..=MAX_HISTORY_DAYS).contains(&days) {
+        return Err(AppError::DateRange(from, to));
+    }
+
+    let days = provider
+        .get_weather_range(&location, from, to)
+        .await
+        .map_err(provider_error)?;
+    let summary = HistorySummary::new(&days);
+    Ok(WeatherCommandResult::History(name, days, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use weather_abstractions::Temperature;
+
+    fn day(day: u32, celsius: f32, condition: &str) -> (NaiveDate, Weather) {
+        let date = NaiveDate::from_ymd_opt(2024, 5, day).unwrap();
+        let temp = Temperature::from_c(celsius).unwrap();
+        (date, Weather::history(temp, condition))
+    }
+
+    #[test]
+    fn summary_aggregates_days() {
+        let days = [
+            day(1, 10.0, "Sunny"),
+            day(2, 14.0, "Light rain"),
+            day(3, 12.0, "Moderate rain"),
+            day(4, 16.0, "Light rain"),
+            day(5, 18.0, "Sunny"),
+        ];
+
+        let summary = HistorySummary::new(&days).unwrap();
+
+        assert_eq!(
+            summary,
+            HistorySummary {
+                mean_temp: 14.0,
+                min_temp: 10.0,
+                max_temp: 18.0,
+                rainy_days: 3,
+                most_common_condition: "Sunny".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn summary_of_no_days_is_none() {
+        assert_eq!(HistorySummary::new(&[]), None);
+    }
+}
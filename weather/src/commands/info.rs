@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use config::Config;
 use weather_abstractions::ProviderManager;
 
-use crate::settings::SettingsError;
+use crate::settings::{Settings, SettingsError};
 
 use super::WeatherCommandResult;
 
@@ -12,6 +12,7 @@ use super::WeatherCommandResult;
 /// # Arguments
 ///
 /// * `provider_manger` - An instance of `ProviderManager` that provides the list of providers and their configurations
+/// * `settings` - The settings of the active profile
 /// * `cfg` - An instance of `Config` that holds the configuration for the weather providers
 ///
 /// # Returns
@@ -23,19 +24,26 @@ use super::WeatherCommandResult;
 /// An error will be returned if there is a failure in accessing the configurations for the providers
 pub async fn execute(
     provider_manger: &mut ProviderManager,
+    settings: &Settings,
     cfg: &Config,
 ) -> Result<WeatherCommandResult, SettingsError> {
     let providers = provider_manger.get_list_providers();
 
-    let mut settings = Vec::new();
+    let mut providers_settings = Vec::new();
 
     for p in providers.into_iter() {
         let provider_conf = cfg.get::<HashMap<String, String>>(p);
         let configuration = match provider_conf {
             Ok(mut configuration) => {
-                configuration
-                    .entry("apiKey".to_string())
-                    .and_modify(hide_sensetive);
+                let secrets = provider_manger
+                    .get_settings_schema(p)
+                    .iter()
+                    .filter(|setting| setting.secret);
+                for setting in secrets {
+                    configuration
+                        .entry(setting.key.to_string())
+                        .and_modify(hide_sensetive);
+                }
                 Some(configuration)
             }
             Err(config::ConfigError::NotFound(_)) => None,
@@ -44,10 +52,14 @@ pub async fn execute(
             }
         };
 
-        settings.push((p.to_string(), configuration));
+        providers_settings.push((p.to_string(), configuration));
     }
     let provider = cfg.get_string("provider").ok();
-    let info = Info { provider, settings };
+    let info = Info {
+        profile: settings.profile().to_string(),
+        provider,
+        settings: providers_settings,
+    };
     Ok(WeatherCommandResult::Info(info))
 }
 
@@ -55,14 +67,17 @@ pub async fn execute(
 ///
 /// # Fields
 ///
+/// * `profile` - A `String` that holds the name of the active profile.
 /// * `provider` - A `String` that holds the name of the current provider being used.
 /// * `settings` - A `Vec` of `(String, Option<HashMap<String, String>>)` tuples, where each tuple represents a weather provider and its configuration, if any.
 pub struct Info {
+    pub profile: String,
     pub provider: Option<String>,
     pub settings: Vec<(String, Option<HashMap<String, String>>)>,
 }
 
-fn hide_sensetive(s: &mut String) {
+/// Replaces the beginning of the sensitive value with asterisks.
+pub fn hide_sensetive(s: &mut String) {
     let len = s.len();
     let hide = match len {
         4.. => (len - 4).max(len / 2),
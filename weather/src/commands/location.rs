@@ -0,0 +1,61 @@
+use super::WeatherCommandResult;
+use crate::{
+    args::LocationCommand,
+    settings::{Settings, SettingsError},
+};
+
+/// Execute the location command.
+///
+/// This function saves, lists and removes the location aliases and groups, and chooses the
+/// default location. The locations are shared by all the profiles.
+///
+/// # Arguments
+///
+/// * `command` - The location sub-command to execute.
+///
+/// # Errors
+///
+/// Returns `SettingsError::LocationNotFound`, `SettingsError::LocationGroupNotFound`, `SettingsError::LocationName` or
+/// `SettingsError::Coordinates` for a wrong alias or location, or another `SettingsError` if the
+/// settings file could not be read or written.
+pub async fn execute(command: LocationCommand) -> Result<WeatherCommandResult, SettingsError> {
+    match command {
+        LocationCommand::Add {
+            alias,
+            location,
+            default,
+        } => {
+            Settings::add_location(&alias, &location).await?;
+            if default {
+                Settings::set_default_location(&alias).await?;
+            }
+            Ok(WeatherCommandResult::LocationAdded(alias))
+        }
+        LocationCommand::List => {
+            let locations = Settings::locations().await?;
+            let default = Settings::default_location().await?;
+            let groups = Settings::location_groups().await?;
+            Ok(WeatherCommandResult::Locations(
+                default,
+                locations.into_iter().collect(),
+                groups.into_iter().collect(),
+            ))
+        }
+        LocationCommand::Remove { alias } => {
+            Settings::remove_location(&alias).await?;
+            Ok(WeatherCommandResult::LocationRemoved(alias))
+        }
+        LocationCommand::Default { alias } => {
+            Settings::set_default_location(&alias).await?;
+            Ok(WeatherCommandResult::DefaultLocationChanged(alias))
+        }
+        LocationCommand::Group { name, members } => {
+            Settings::add_location_group(&name, &members).await?;
+            Ok(WeatherCommandResult::LocationGroupAdded(name))
+        }
+        LocationCommand::Ungroup { name } => {
+            Settings::remove_location_group(&name).await?;
+            Ok(WeatherCommandResult::LocationGroupRemoved(name))
+        }
+    }
+}
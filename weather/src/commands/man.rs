@@ -0,0 +1,16 @@
+use clap_mangen::Man;
+use weather_abstractions::ProviderManager;
+
+use super::{completions::cli_command, WeatherCommandResult};
+use crate::AppError;
+
+/// Renders the man page of the application in the roff format.
+///
+/// # Errors
+///
+/// Returns an error if the man page could not be rendered.
+pub fn execute(provider_manger: &ProviderManager) -> Result<WeatherCommandResult, AppError> {
+    let mut page = Vec::new();
+    Man::new(cli_command(provider_manger)).render(&mut page)?;
+    Ok(WeatherCommandResult::Generated(page))
+}
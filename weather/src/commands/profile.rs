@@ -0,0 +1,46 @@
+use super::WeatherCommandResult;
+use crate::{
+    args::ProfileCommand,
+    settings::{Settings, SettingsError},
+};
+
+/// Execute the profile command.
+///
+/// This function lists, activates, creates or deletes the named settings profiles.
+///
+/// # Arguments
+///
+/// * `command` - The profile sub-command to execute.
+/// * `settings` - The settings of the profile in use, marked as active in the list.
+///
+/// # Errors
+///
+/// Returns `SettingsError::ProfileNotFound`, `SettingsError::ProfileExists` or
+/// `SettingsError::ProfileName` for a wrong profile name, or another `SettingsError` if the
+/// settings file could not be read or written.
+pub async fn execute(
+    command: ProfileCommand,
+    settings: &Settings,
+) -> Result<WeatherCommandResult, SettingsError> {
+    match command {
+        ProfileCommand::List => {
+            let profiles = Settings::profiles().await?;
+            Ok(WeatherCommandResult::Profiles(
+                settings.profile().to_string(),
+                profiles,
+            ))
+        }
+        ProfileCommand::Use { name } => {
+            Settings::use_profile(&name).await?;
+            Ok(WeatherCommandResult::ProfileChanged(name))
+        }
+        ProfileCommand::Create { name } => {
+            Settings::create_profile(&name).await?;
+            Ok(WeatherCommandResult::ProfileCreated(name))
+        }
+        ProfileCommand::Delete { name } => {
+            Settings::delete_profile(&name).await?;
+            Ok(WeatherCommandResult::ProfileDeleted(name))
+        }
+    }
+}
@@ -1,13 +1,41 @@
-use crate::settings::{Settings, SettingsError};
+use weather_abstractions::ProviderManager;
+
+use crate::{
+    args::ResetArgs,
+    settings::{Settings, SettingsError},
+    AppError,
+};
 
 use super::WeatherCommandResult;
 
-/// Resets the settings of the application to its default values.
+/// Resets the settings of the active profile, or of a single provider, to its default values.
+///
+/// # Arguments
+///
+/// * `args` - The arguments for the reset command.
+/// * `provider_manger` - A reference to the ProviderManager, used to check the provider name.
+/// * `settings` - The settings of the active profile.
 ///
 /// # Errors
 ///
-/// Returns an error if there was an issue resetting the settings.
-pub async fn execute() -> Result<WeatherCommandResult, SettingsError> {
-    Settings::reset().await?;
-    Ok(WeatherCommandResult::Reseted)
+/// Returns an error if the provider is not supported or there was an issue resetting the settings.
+pub async fn execute(
+    args: ResetArgs,
+    provider_manger: &ProviderManager,
+    settings: &Settings,
+) -> Result<WeatherCommandResult, AppError> {
+    match args.provider {
+        Some(provider) => {
+            provider_manger.is_supported(&provider)?;
+            match settings.unset(&provider).await {
+                Ok(()) | Err(SettingsError::Path(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+            Ok(WeatherCommandResult::ProviderReseted(provider))
+        }
+        None => {
+            settings.reset().await?;
+            Ok(WeatherCommandResult::Reseted)
+        }
+    }
 }
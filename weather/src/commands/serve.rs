@@ -0,0 +1,47 @@
+use super::WeatherCommandResult;
+use crate::{args::ServeArgs, server, AppError, Settings, SettingsError};
+use config::Config;
+use std::net::TcpListener;
+use tokio::task::LocalSet;
+use weather_abstractions::ProviderManager;
+
+/// Serve the weather over HTTP on `args.bind` until interrupted, see `server::WeatherServer` for the
+/// endpoints.
+///
+/// All the providers are built, the configured provider is the default one and the others
+/// which are configured as well can be requested by their name.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * An error returned by the `get_provider` method of the `ProviderManager` for the default
+///   provider
+/// * `AppError::Bind` if the address can't be listened on
+/// * `AppError::Server` if the server fails to accept the connections
+pub async fn execute(
+    args: ServeArgs,
+    provider_manger: ProviderManager,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let locations = Settings::locations().await?;
+    let api = server::WeatherServer::new(provider_manger, provider_name, locations, args.rate)?;
+
+    let bind_error = |e| AppError::Bind(args.bind, e);
+    let listener = TcpListener::bind(args.bind).map_err(bind_error)?;
+    listener.set_nonblocking(true).map_err(bind_error)?;
+    let address = listener.local_addr().map_err(bind_error)?;
+    println!("Serving the weather on http://{address}, the API is described at /openapi.json.");
+    let shutdown = async {
+        // Without the signal handler the server runs until the process is killed
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    LocalSet::new()
+        .run_until(server::serve_api(api, listener, shutdown))
+        .await?;
+    Ok(WeatherCommandResult::ServerStopped(address))
+}
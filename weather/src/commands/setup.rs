@@ -0,0 +1,92 @@
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use config::Config;
+use dialoguer::{theme::ColorfulTheme, Input, Password, Select};
+use weather_abstractions::ProviderManager;
+
+use super::WeatherCommandResult;
+use crate::{provider_manager, AppError, Settings};
+
+/// Execute the setup command.
+///
+/// This function interactively asks the user to choose a provider and to enter its required
+/// settings, hiding the secret ones. The settings are verified with a request to the provider
+/// and written to the settings file only if the verification succeeds.
+///
+/// # Arguments
+///
+/// * `provider_manger` - A reference to the ProviderManager, used to list the providers and their settings.
+/// * `settings` - The settings of the active profile, where the values are saved.
+/// * `cfg` - The current configuration, the entered settings are applied on top of it for the verification.
+///
+/// # Errors
+///
+/// This function can return the following errors:
+///
+/// * If the terminal input could not be read.
+/// * If the verification request to the provider failed.
+/// * If an error occurred while writing the settings.
+pub async fn execute(
+    provider_manger: &ProviderManager,
+    settings: &Settings,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let theme = ColorfulTheme::default();
+    let mut providers = provider_manger.get_list_providers();
+    providers.sort_unstable();
+    let current = cfg.get_string("provider").ok();
+    let default = providers
+        .iter()
+        .position(|p| Some(*p) == current.as_deref())
+        .unwrap_or_default();
+    let selected = Select::with_theme(&theme)
+        .with_prompt("Weather provider")
+        .items(&providers)
+        .default(default)
+        .interact()?;
+    let provider = providers[selected].to_string();
+
+    let mut values = Vec::new();
+    for setting in provider_manger
+        .get_settings_schema(&provider)
+        .iter()
+        .filter(|s| s.required)
+    {
+        let prompt = format!("{} ({})", setting.description, setting.key);
+        let value = if setting.secret {
+            Password::with_theme(&theme)
+                .with_prompt(prompt)
+                .interact()?
+        } else {
+            Input::with_theme(&theme)
+                .with_prompt(prompt)
+                .interact_text()?
+        };
+        values.push((format!("{provider}/{}", setting.key), value));
+    }
+
+    verify(Settings::with_overrides(cfg, &values)?, &provider).await?;
+    for (path, value) in values.iter() {
+        settings.set(path, value).await?;
+    }
+    settings.set("provider", &provider).await?;
+    Ok(WeatherCommandResult::SetupCompleted(provider))
+}
+
+/// Builds the provider from the given configuration and performs a verification request.
+///
+/// # Errors
+///
+/// Returns `AppError::Verification` if the provider could not be built or the request failed.
+pub async fn verify(cfg: Config, provider_name: &str) -> Result<(), AppError> {
+    let verification_error = |e| AppError::Verification(provider_name.to_string(), e);
+    let mut provider_manger = provider_manager(&Rc::new(cfg));
+    let provider = provider_manger
+        .get_provider(provider_name)
+        .map_err(|e| verification_error(anyhow!(e)))?;
+    provider
+        .verify()
+        .await
+        .map_err(|e| verification_error(anyhow!(e)))
+}
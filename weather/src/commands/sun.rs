@@ -0,0 +1,86 @@
+use super::WeatherCommandResult;
+use crate::{args::SunArgs, AppError, Settings, SettingsError};
+use anyhow::anyhow;
+use chrono::{FixedOffset, Utc};
+use config::Config;
+use std::error::Error;
+use weather_abstractions::{Coordinates, DateSpec, ProviderManager, SolarDay};
+
+/// Retrieve the sun and moon events of a location for a date, today at the location by default.
+///
+/// With `args.offline` the sun events are calculated from the coordinates of the location
+/// instead, so no provider needs to be set and any date is supported.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * `SettingsError::NoDefaultLocation` if no address is given and no default location is saved
+/// * `CoordinatesError` if the location is not the coordinates in the offline mode
+/// * An error returned by the `get_provider` method of the `ProviderManager`
+/// * An error returned by the `get_astronomy` method of the selected provider, including the
+///   providers without the astronomy and the dates they don't support
+pub async fn execute(
+    args: SunArgs,
+    provider_manger: &mut ProviderManager,
+    cfg: &Config,
+) -> Result<WeatherCommandResult, AppError> {
+    let addresses = Vec::from_iter(args.address);
+    let (name, location) = Settings::resolve_locations(&addresses).await?.remove(0);
+    let date = args.date.unwrap_or(DateSpec::Days(0));
+    if args.offline {
+        let coordinates: Coordinates = location.parse()?;
+        let offset = args
+            .offset
+            .unwrap_or_else(|| mean_solar_offset(&coordinates));
+        let date = date.resolve(Utc::now().with_timezone(&offset).date_naive());
+        let solar_day = SolarDay::new(&coordinates, date, offset);
+        return Ok(WeatherCommandResult::SolarDay(name, date, solar_day));
+    }
+
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let provider = provider_manger.get_provider(&provider_name)?;
+    let provider_error = |e: Box<dyn Error + Send + Sync>| AppError::Provider(anyhow!(e));
+    let date = date
+        .resolve_for(provider, &location)
+        .await
+        .map_err(provider_error)?;
+    let astronomy = provider
+        .get_astronomy(&location, date)
+        .await
+        .map_err(provider_error)?;
+    Ok(WeatherCommandResult::Astronomy(name, date, astronomy))
+}
+
+/// The offset of the mean solar time at the longitude rounded to the hour, which is close to the
+/// time zone of most locations and keeps the events on the requested date.
+fn mean_solar_offset(coordinates: &Coordinates) -> FixedOffset {
+    let hours = (coordinates.lon / 15.0).round() as i32;
+    FixedOffset::east_opt(hours * 3600).expect("offset within a day")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_solar_offset_rounded_to_hour() {
+        let offset = |lat, lon| mean_solar_offset(&Coordinates::new(lat, lon).unwrap());
+        assert_eq!(offset(51.5, -0.13), FixedOffset::east_opt(0).unwrap());
+        assert_eq!(
+            offset(50.45, 30.52),
+            FixedOffset::east_opt(2 * 3600).unwrap()
+        );
+        assert_eq!(
+            offset(40.71, -74.0),
+            FixedOffset::west_opt(5 * 3600).unwrap()
+        );
+        assert_eq!(
+            offset(-33.87, 151.21),
+            FixedOffset::east_opt(10 * 3600).unwrap()
+        );
+    }
+}
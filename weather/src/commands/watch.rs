@@ -0,0 +1,156 @@
+use super::{error_message, WeatherCommandResult};
+use crate::{
+    args::WatchArgs, cache::Cache, icons::IconStyle, user_output::WatchView, AppError, Settings,
+    SettingsError,
+};
+use config::Config;
+use std::time::Duration;
+use weather_abstractions::{ProviderManager, Units, Weather};
+
+/// The providers update the current conditions every 10 to 15 minutes, a shorter interval redraws
+/// the weather retrieved less than this time ago instead of requesting it again.
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+/// The longest wait after the failed requests, the wait doubles after every failure.
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// What changed in the weather since the previous refresh.
+#[derive(Debug, Default, PartialEq)]
+pub struct WeatherChanges {
+    /// The difference of the temperature in Celsius, rounded to a tenth of a degree
+    pub temp_delta: Option<f32>,
+    /// The previous condition if the condition changed
+    pub previous_condition: Option<String>,
+}
+
+impl WeatherChanges {
+    /// Compares the weather with the previous one, nothing changed on the first refresh.
+    pub fn new(previous: Option<&Weather>, current: &Weather) -> Self {
+        let Some(previous) = previous else {
+            return Self::default();
+        };
+        let delta = current.temp.to_value(Units::Metric) - previous.temp.to_value(Units::Metric);
+        let delta = (delta * 10.0).round() / 10.0;
+        Self {
+            temp_delta: (delta != 0.0).then_some(delta),
+            previous_condition: (previous.condition != current.condition)
+                .then(|| previous.condition.clone()),
+        }
+    }
+}
+
+/// Watch the current weather of a location, until interrupted or `args.count` refreshes.
+///
+/// The weather is requested every `args.interval`, reusing the weather retrieved less than
+/// `CACHE_TTL` ago. A failed request is shown with the last retrieved weather and retried after
+/// a wait that doubles on every failure, up to `MAX_BACKOFF`, so a rate limited provider is not
+/// requested again too soon.
+///
+/// # Errors
+///
+/// This function may return the following errors:
+///
+/// * `SettingsError::ProviderNotSet` if the provider is not set in the current configuration
+/// * `SettingsError::NoDefaultLocation` if no address is given and no default location is saved
+/// * An error returned by the `get_provider` method of the `ProviderManager`
+pub async fn execute(
+    args: WatchArgs,
+    provider_manger: &mut ProviderManager,
+    cfg: &Config,
+    icons: IconStyle,
+) -> Result<WeatherCommandResult, AppError> {
+    let provider_name: String = cfg
+        .get_string("provider")
+        .map_err(|_| SettingsError::ProviderNotSet)?;
+    let provider = provider_manger.get_provider(&provider_name)?;
+    let addresses = Vec::from_iter(args.address);
+    let (name, location) = Settings::resolve_locations(&addresses).await?.remove(0);
+
+    let cache = Cache::new(CACHE_TTL);
+    let mut view = WatchView::new(&name, icons);
+    let mut previous: Option<Weather> = None;
+    let mut wait = args.interval;
+    let mut refreshes = 0;
+    loop {
+        let weather = match cache.get(&location) {
+            Some(weather) => Ok(weather),
+            None => provider
+                .get_weather(&location, None)
+                .await
+                .inspect(|weather| cache.insert(location.clone(), weather.clone())),
+        };
+        match weather {
+            Ok(weather) => {
+                wait = args.interval;
+                let changes = WeatherChanges::new(previous.as_ref(), &weather);
+                view.show(&weather, &changes, wait)?;
+                previous = Some(weather);
+            }
+            Err(e) => {
+                wait = backoff(wait, args.interval);
+                view.show_error(previous.as_ref(), &error_message(e.as_ref()), wait)?;
+            }
+        }
+        refreshes += 1;
+        if args.count.is_some_and(|count| refreshes >= count) {
+            break;
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    Ok(WeatherCommandResult::WatchStopped(name))
+}
+
+/// The wait after another failure, double the previous one up to `MAX_BACKOFF`, but never
+/// shorter than the interval.
+fn backoff(wait: Duration, interval: Duration) -> Duration {
+    wait.saturating_mul(2).min(MAX_BACKOFF).max(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use weather_abstractions::Temperature;
+
+    fn weather(celsius: f32, condition: &str) -> Weather {
+        Weather::current(Temperature::from_c(celsius).unwrap(), condition.to_string())
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let minute = Duration::from_secs(60);
+        assert_eq!(backoff(minute, minute), 2 * minute);
+        assert_eq!(backoff(MAX_BACKOFF, minute), MAX_BACKOFF);
+        let long = Duration::from_secs(u64::MAX);
+        assert_eq!(backoff(long, long), long);
+    }
+
+    #[test]
+    fn nothing_changed_on_first_or_equal_refresh() {
+        let current = weather(12.0, "Rain");
+        assert_eq!(
+            WeatherChanges::new(None, &current),
+            WeatherChanges::default()
+        );
+        assert_eq!(
+            WeatherChanges::new(Some(&weather(12.0, "Rain")), &current),
+            WeatherChanges::default()
+        );
+    }
+
+    #[test]
+    fn temperature_and_condition_changed() {
+        let changes = WeatherChanges::new(Some(&weather(10.8, "Clear")), &weather(12.0, "Rain"));
+        assert_eq!(
+            changes,
+            WeatherChanges {
+                temp_delta: Some(1.2),
+                previous_condition: Some("Clear".to_string())
+            }
+        );
+        let changes = WeatherChanges::new(Some(&weather(12.5, "Rain")), &weather(12.0, "Rain"));
+        assert_eq!(changes.temp_delta, Some(-0.5));
+        assert_eq!(changes.previous_condition, None);
+    }
+}
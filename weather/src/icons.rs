@@ -0,0 +1,156 @@
+use clap::ValueEnum;
+use colored::{Color, Colorize};
+use weather_abstractions::ConditionKind;
+
+/// The style used to render the condition icon next to the weather.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IconStyle {
+    /// A single emoji, works in most modern terminals
+    Emoji,
+    /// A Nerd Font weather glyph, requires a patched font
+    Nerd,
+    /// Multi-line ASCII art
+    Ascii,
+    /// No icon at all
+    None,
+}
+
+/// The width of every line of the ASCII art.
+pub const ASCII_WIDTH: usize = 13;
+
+/// Renders the icon of the condition in the given style.
+///
+/// Returns no lines for `IconStyle::None`, a single line for `IconStyle::Emoji` and
+/// `IconStyle::Nerd`, and `ASCII_WIDTH` wide lines for `IconStyle::Ascii`.
+pub fn render(kind: ConditionKind, style: IconStyle) -> Vec<String> {
+    match style {
+        IconStyle::Emoji => vec![emoji(kind).to_string()],
+        IconStyle::Nerd => vec![nerd(kind).to_string()],
+        IconStyle::Ascii => ascii(kind)
+            .iter()
+            .map(|(line, color)| format!("{line:<ASCII_WIDTH$}").color(*color).to_string())
+            .collect(),
+        IconStyle::None => Vec::new(),
+    }
+}
+
+fn emoji(kind: ConditionKind) -> &'static str {
+    match kind {
+        ConditionKind::Clear => "☀️",
+        ConditionKind::PartlyCloudy => "⛅",
+        ConditionKind::Cloudy => "☁️",
+        ConditionKind::Fog => "🌫",
+        ConditionKind::Drizzle => "🌦",
+        ConditionKind::Rain | ConditionKind::HeavyRain => "🌧",
+        ConditionKind::Thunderstorm => "⛈",
+        ConditionKind::Snow => "❄️",
+        ConditionKind::Sleet => "🌨",
+        ConditionKind::Unknown => "✨",
+    }
+}
+
+fn nerd(kind: ConditionKind) -> &'static str {
+    match kind {
+        ConditionKind::Clear => "\u{e30d}",
+        ConditionKind::PartlyCloudy => "\u{e302}",
+        ConditionKind::Cloudy => "\u{e312}",
+        ConditionKind::Fog => "\u{e313}",
+        ConditionKind::Drizzle => "\u{e31c}",
+        ConditionKind::Rain => "\u{e318}",
+        ConditionKind::HeavyRain => "\u{e319}",
+        ConditionKind::Thunderstorm => "\u{e31d}",
+        ConditionKind::Snow => "\u{e31a}",
+        ConditionKind::Sleet => "\u{e3ad}",
+        ConditionKind::Unknown => "\u{e374}",
+    }
+}
+
+type ArtLine = (&'static str, Color);
+
+const CLOUD: Color = Color::White;
+const SUN: Color = Color::Yellow;
+const RAIN: Color = Color::Blue;
+const SNOW: Color = Color::BrightWhite;
+const FOG: Color = Color::BrightBlack;
+
+fn ascii(kind: ConditionKind) -> &'static [ArtLine] {
+    match kind {
+        ConditionKind::Clear => &[
+            (r"    \   /", SUN),
+            (r"     .-.", SUN),
+            (r"  - (   ) -", SUN),
+            (r"     `-'", SUN),
+            (r"    /   \", SUN),
+        ],
+        ConditionKind::PartlyCloudy => &[
+            (r"   \  /", SUN),
+            (r#" _ /"".-."#, CLOUD),
+            (r"   \_(   ).", CLOUD),
+            (r"   /(___(__)", CLOUD),
+            ("", CLOUD),
+        ],
+        ConditionKind::Cloudy => &[
+            ("", CLOUD),
+            (r"     .--.", CLOUD),
+            (r"  .-(    ).", CLOUD),
+            (r" (___.__)__)", CLOUD),
+            ("", CLOUD),
+        ],
+        ConditionKind::Fog => &[
+            ("", FOG),
+            (r" _ - _ - _ -", FOG),
+            (r"  _ - _ - _", FOG),
+            (r" _ - _ - _ -", FOG),
+            ("", FOG),
+        ],
+        ConditionKind::Drizzle => &[
+            (r"     .-.", CLOUD),
+            (r"    (   ).", CLOUD),
+            (r"   (___(__)", CLOUD),
+            (r"     '   '", RAIN),
+            (r"    '   '", RAIN),
+        ],
+        ConditionKind::Rain => &[
+            (r"     .-.", CLOUD),
+            (r"    (   ).", CLOUD),
+            (r"   (___(__)", CLOUD),
+            (r"    ' ' ' '", RAIN),
+            (r"   ' ' ' '", RAIN),
+        ],
+        ConditionKind::HeavyRain => &[
+            (r"     .-.", CLOUD),
+            (r"    (   ).", CLOUD),
+            (r"   (___(__)", CLOUD),
+            (r"  ,',',',',", RAIN),
+            (r"  ,',',',',", RAIN),
+        ],
+        ConditionKind::Thunderstorm => &[
+            (r"     .-.", CLOUD),
+            (r"    (   ).", CLOUD),
+            (r"   (___(__)", CLOUD),
+            (r"   ,'/_,'/_", Color::BrightYellow),
+            (r"    ,/',/'", Color::BrightYellow),
+        ],
+        ConditionKind::Snow => &[
+            (r"     .-.", CLOUD),
+            (r"    (   ).", CLOUD),
+            (r"   (___(__)", CLOUD),
+            (r"    *  *  *", SNOW),
+            (r"   *  *  *", SNOW),
+        ],
+        ConditionKind::Sleet => &[
+            (r"     .-.", CLOUD),
+            (r"    (   ).", CLOUD),
+            (r"   (___(__)", CLOUD),
+            (r"    ' * ' *", SNOW),
+            (r"   * ' * '", SNOW),
+        ],
+        ConditionKind::Unknown => &[
+            (r"    .-.", CLOUD),
+            (r"     __)", CLOUD),
+            (r"    (", CLOUD),
+            (r"     `-'", CLOUD),
+            (r"      .", CLOUD),
+        ],
+    }
+}
@@ -1,46 +1,179 @@
 mod args;
+mod cache;
 mod commands;
+mod icons;
+mod metrics;
+mod rate_limit;
+mod server;
 mod settings;
+#[cfg(feature = "otlp")]
+mod telemetry;
 mod user_output;
 
 use anyhow::Result;
-use args::{CliCommand, WeatherCliArgs};
+use args::{CliCommand, ProviderCommand, WeatherCliArgs};
 use clap::Parser;
 use config::Config;
+use icons::IconStyle;
 use settings::*;
-use user_output::print;
-use weather_abstractions::ProviderManagerBuilder;
+use tracing::Instrument;
+use user_output::{init_colors, print};
+use weather_abstractions::{ProviderManager, ProviderManagerBuilder};
 
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args = WeatherCliArgs::parse();
-    print(handle(args).await?);
-    Ok(())
+    init_colors();
+    #[cfg(feature = "otlp")]
+    let _telemetry = match &args.otlp_endpoint {
+        Some(endpoint) => Some(telemetry::Telemetry::init(endpoint)?),
+        None => None,
+    };
+    let icons = args.icons;
+    // The check and the alerts exit with 1 when a rule matches or an alert is in effect
+    let distinct_errors = matches!(
+        args.command,
+        CliCommand::Provider(ProviderCommand::Check(_) | ProviderCommand::Alerts(_))
+    );
+    let span = tracing::info_span!("command", command = args.command.name());
+    let result = match handle(args).instrument(span).await {
+        Ok(result) => result,
+        // The errors of the commands exiting with 1 are told apart by 2
+        Err(e) if distinct_errors => {
+            eprintln!("Error: {:?}", anyhow::Error::from(e));
+            return Ok(ExitCode::from(2));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let exit_code = result.exit_code();
+    print(result, icons).map_err(AppError::Output)?;
+    Ok(exit_code)
 }
 use commands::WeatherCommandResult;
-use std::rc::Rc;
+use std::{process::ExitCode, rc::Rc};
 async fn handle(args: WeatherCliArgs) -> Result<WeatherCommandResult, AppError> {
-    let conf: Config = Settings::conf().await?;
+    let settings = Settings::open(args.profile).await?;
+    match args.command {
+        CliCommand::Profile(command) => Ok(commands::profile::execute(command, &settings).await?),
+        CliCommand::Location(command) => Ok(commands::location::execute(command).await?),
+        CliCommand::Provider(command) => handle_provider(command, args.icons, &settings).await,
+    }
+}
+
+/// Executes the sub-command with the configuration of the profile and the providers built from it.
+async fn handle_provider(
+    command: ProviderCommand,
+    icons: IconStyle,
+    settings: &Settings,
+) -> Result<WeatherCommandResult, AppError> {
+    let conf: Config = settings.conf().await?;
     let conf = Rc::new(conf);
-    let conf_ref1 = Rc::clone(&conf);
-    let conf_ref2 = Rc::clone(&conf);
-    let mut provider_manger = ProviderManagerBuilder::default()
+    let mut provider_manger = provider_manager(&conf);
+    let res = match command {
+        ProviderCommand::Configure(args) => {
+            commands::configure::execute(args, &provider_manger, settings, &conf).await?
+        }
+        ProviderCommand::Get(args) => {
+            commands::get::execute(args, &mut provider_manger, &conf).await?
+        }
+        ProviderCommand::History(args) => {
+            commands::history::execute(args, &mut provider_manger, &conf).await?
+        }
+        ProviderCommand::Air(args) => {
+            commands::air::execute(args, &mut provider_manger, &conf).await?
+        }
+        ProviderCommand::Sun(args) => {
+            commands::sun::execute(args, &mut provider_manger, &conf).await?
+        }
+        ProviderCommand::Check(args) => {
+            commands::check::execute(args, &mut provider_manger, &conf).await?
+        }
+        ProviderCommand::Alerts(args) => {
+            commands::alerts::execute(args, &mut provider_manger, &conf).await?
+        }
+        ProviderCommand::Watch(watch_args) => {
+            commands::watch::execute(watch_args, &mut provider_manger, &conf, icons).await?
+        }
+        ProviderCommand::Batch(args) => {
+            commands::batch::execute(args, &mut provider_manger, &conf).await?
+        }
+        ProviderCommand::Exporter(args) => {
+            commands::exporter::execute(args, &mut provider_manger, &conf).await?
+        }
+        ProviderCommand::Serve(args) => {
+            commands::serve::execute(args, provider_manger, &conf).await?
+        }
+        ProviderCommand::Config(command) => {
+            commands::config::execute(command, &provider_manger, settings).await?
+        }
+        ProviderCommand::Reset(args) => {
+            commands::reset::execute(args, &provider_manger, settings).await?
+        }
+        ProviderCommand::Info => {
+            commands::info::execute(&mut provider_manger, settings, &conf).await?
+        }
+        ProviderCommand::Setup => {
+            commands::setup::execute(&provider_manger, settings, &conf).await?
+        }
+        ProviderCommand::Completions(args) => {
+            commands::completions::execute(args, &provider_manger).await?
+        }
+        ProviderCommand::Man => commands::man::execute(&provider_manger)?,
+    };
+    Ok(res)
+}
+
+/// Registers all the supported weather providers, built lazily from the given configuration.
+///
+/// The plugins found on the `PATH` or listed in the settings are registered first, then the
+/// generic JSON providers declared in the settings, so neither can replace a built-in provider of
+/// the same name.
+fn provider_manager(conf: &Rc<Config>) -> ProviderManager {
+    let mut builder = ProviderManagerBuilder::default();
+    for (name, program) in process_provider::plugins(conf) {
+        let conf = Rc::clone(conf);
+        let provider_name = name.clone();
+        builder = builder.add_provider_builder(name, move || {
+            let plugin = process_provider::ProcessProvider::new(&provider_name, program, &conf)?;
+            Ok(Box::new(plugin))
+        });
+    }
+    for name in generic_json::generic_providers(conf) {
+        let conf = Rc::clone(conf);
+        let provider_name = name.clone();
+        builder = builder
+            .add_provider_builder(name.clone(), move || {
+                let provider = generic_json::GenericJsonProvider::new(&provider_name, &conf)?;
+                Ok(Box::new(provider))
+            })
+            .add_settings_schema(name, generic_json::SETTINGS);
+    }
+    let conf_ref1 = Rc::clone(conf);
+    let conf_ref2 = Rc::clone(conf);
+    let conf_ref3 = Rc::clone(conf);
+    let conf_ref4 = Rc::clone(conf);
+    builder
         .add_provider_builder(open_weather::PROVIDER_NAME, move || {
             let open_weather = open_weather::OpenWeatherMap::new(&conf_ref1)?;
             Ok(Box::new(open_weather))
         })
+        .add_settings_schema(open_weather::PROVIDER_NAME, open_weather::SETTINGS)
         .add_provider_builder(weather_api::PROVIDER_NAME, move || {
             let weather_api = weather_api::WeatherApiBuilder::build(&conf_ref2)?;
             Ok(Box::new(weather_api))
         })
-        .build();
-    let res = match args.command {
-        CliCommand::Configure(args) => commands::configure::execute(args, &provider_manger).await?,
-        CliCommand::Get(args) => commands::get::execute(args, &mut provider_manger, &conf).await?,
-        CliCommand::Reset => commands::reset::execute().await?,
-        CliCommand::Info => commands::info::execute(&mut provider_manger, &conf).await?,
-    };
-    Ok(res)
+        .add_settings_schema(weather_api::PROVIDER_NAME, weather_api::SETTINGS)
+        .add_provider_builder(open_meteo::PROVIDER_NAME, move || {
+            let open_meteo = open_meteo::OpenMeteo::new(&conf_ref3)?;
+            Ok(Box::new(open_meteo))
+        })
+        .add_settings_schema(open_meteo::PROVIDER_NAME, open_meteo::SETTINGS)
+        .add_provider_builder(met_norway::PROVIDER_NAME, move || {
+            let met_norway = met_norway::MetNorway::new(&conf_ref4)?;
+            Ok(Box::new(met_norway))
+        })
+        .add_settings_schema(met_norway::PROVIDER_NAME, met_norway::SETTINGS)
+        .build()
 }
 
 use thiserror::Error;
@@ -53,4 +186,20 @@ pub enum AppError {
     ProviderManager(#[from] weather_abstractions::Error),
     #[error("Provider")]
     Provider(#[from] anyhow::Error),
+    #[error("Verification of the provider '{0}' settings failed")]
+    Verification(String, #[source] anyhow::Error),
+    #[error("The date range from {0} to {1} must contain from 1 to {max} days", max = commands::history::MAX_HISTORY_DAYS)]
+    DateRange(chrono::NaiveDate, chrono::NaiveDate),
+    #[error("Date")]
+    Date(#[from] weather_abstractions::DateSpecError),
+    #[error("The offline calculation requires the coordinates of the location")]
+    Coordinates(#[from] weather_abstractions::CoordinatesError),
+    #[error("The provider doesn't return the {0} needed for the check")]
+    MissingValue(&'static str),
+    #[error("Unable to listen on {0}")]
+    Bind(std::net::SocketAddr, #[source] std::io::Error),
+    #[error("The HTTP server failed")]
+    Server(#[from] hyper::Error),
+    #[error("Unable to read the input or write the output")]
+    Output(#[from] std::io::Error),
 }
@@ -0,0 +1,241 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex, time::Duration};
+
+use weather_abstractions::{Units, Weather};
+
+/// The upper bounds of the request latency buckets in seconds, the providers answer in tenths
+/// of a second and the retried requests take seconds.
+const LATENCY_BUCKETS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// A metric with its name, its help and how its value is read.
+type Metric<T, V> = (&'static str, &'static str, fn(&T) -> V);
+
+/// The gauges of the weather.
+const GAUGES: [Metric<Weather, Option<f32>>; 4] = [
+    (
+        "weather_temperature_celsius",
+        "The temperature in degrees Celsius",
+        |weather| Some(weather.temp.to_value(Units::Metric)),
+    ),
+    (
+        "weather_humidity_percent",
+        "The relative humidity in percent",
+        |weather| weather.humidity,
+    ),
+    (
+        "weather_wind_speed_meters_per_second",
+        "The wind speed in meters per second",
+        |weather| weather.wind_speed,
+    ),
+    (
+        "weather_pressure_hectopascals",
+        "The sea level pressure in hectopascals",
+        |weather| weather.pressure,
+    ),
+];
+
+/// The metrics of the weather and of the requests to the providers, rendered in the Prometheus
+/// text format.
+///
+/// The metrics can be shared by the concurrent requests, the values are guarded by a mutex which
+/// is never held across an `.await`.
+#[derive(Default)]
+pub struct Metrics {
+    values: Mutex<Values>,
+}
+
+#[derive(Default)]
+struct Values {
+    /// The last weather by the location and the provider
+    weather: BTreeMap<(String, String), Weather>,
+    /// The requests by the provider
+    requests: BTreeMap<String, Requests>,
+}
+
+#[derive(Default)]
+struct Requests {
+    total: u64,
+    errors: u64,
+    /// The number of requests in every latency bucket, not cumulative
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    seconds: f64,
+}
+
+impl Metrics {
+    /// Creates the metrics with the request counters of the providers at zero, so the rates are
+    /// known from the first request.
+    pub fn new<'a>(providers: impl IntoIterator<Item = &'a str>) -> Self {
+        let requests = providers
+            .into_iter()
+            .map(|provider| (provider.to_string(), Requests::default()))
+            .collect();
+        Self {
+            values: Mutex::new(Values {
+                requests,
+                ..Default::default()
+            }),
+        }
+    }
+    /// Replaces the weather of the location from the provider.
+    pub fn record_weather(&self, location: &str, provider: &str, weather: Weather) {
+        let mut values = self.values.lock().expect("The metrics lock is poisoned");
+        values
+            .weather
+            .insert((location.to_string(), provider.to_string()), weather);
+    }
+    /// Counts a request to the provider with its duration, `failed` for the errors.
+    pub fn record_request(&self, provider: &str, duration: Duration, failed: bool) {
+        let mut values = self.values.lock().expect("The metrics lock is poisoned");
+        let requests = values.requests.entry(provider.to_string()).or_default();
+        let seconds = duration.as_secs_f64();
+        requests.total += 1;
+        requests.errors += u64::from(failed);
+        requests.seconds += seconds;
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|le| seconds <= *le) {
+            requests.buckets[bucket] += 1;
+        }
+    }
+    /// Renders the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let values = self.values.lock().expect("The metrics lock is poisoned");
+        let mut out = String::new();
+        for (name, help, value) in GAUGES {
+            header(&mut out, name, help, "gauge");
+            for ((location, provider), weather) in &values.weather {
+                if let Some(value) = value(weather) {
+                    let labels = labels(&[("location", location), ("provider", provider)]);
+                    let _ = writeln!(out, "{name}{labels} {value}");
+                }
+            }
+        }
+
+        let counters: [Metric<Requests, u64>; 2] = [
+            (
+                "weather_provider_requests_total",
+                "The requests to the provider",
+                |requests| requests.total,
+            ),
+            (
+                "weather_provider_errors_total",
+                "The requests to the provider which failed",
+                |requests| requests.errors,
+            ),
+        ];
+        for (name, help, value) in counters {
+            header(&mut out, name, help, "counter");
+            for (provider, requests) in &values.requests {
+                let labels = labels(&[("provider", provider)]);
+                let _ = writeln!(out, "{name}{labels} {}", value(requests));
+            }
+        }
+
+        let name = "weather_provider_request_duration_seconds";
+        header(
+            &mut out,
+            name,
+            "The duration of the requests to the provider",
+            "histogram",
+        );
+        for (provider, requests) in &values.requests {
+            let mut count = 0;
+            for (le, in_bucket) in LATENCY_BUCKETS.iter().zip(requests.buckets) {
+                count += in_bucket;
+                let labels = labels(&[("provider", provider), ("le", &le.to_string())]);
+                let _ = writeln!(out, "{name}_bucket{labels} {count}");
+            }
+            let labels_inf = labels(&[("provider", provider), ("le", "+Inf")]);
+            let labels = labels(&[("provider", provider)]);
+            let _ = writeln!(out, "{name}_bucket{labels_inf} {}", requests.total);
+            let _ = writeln!(out, "{name}_sum{labels} {}", requests.seconds);
+            let _ = writeln!(out, "{name}_count{labels} {}", requests.total);
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Formats the labels, escaping the backslashes, the double quotes and the line feeds of the values.
+fn labels(labels: &[(&str, &str)]) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{name}=\"{value}\"")
+        })
+        .collect();
+    format!("{{{}}}", labels.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use weather_abstractions::Temperature;
+
+    #[test]
+    fn gauges_labelled_by_location_and_provider() {
+        let metrics = Metrics::new(["weather-api"]);
+        let weather = Weather::current(Temperature::from_c(21.5).unwrap(), "Sunny")
+            .with_humidity(60.0)
+            .with_wind_speed(5.0);
+        metrics.record_weather("home", "weather-api", weather);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE weather_temperature_celsius gauge\n"));
+        assert!(text.contains(
+            "weather_temperature_celsius{location=\"home\",provider=\"weather-api\"} 21.5\n"
+        ));
+        assert!(text
+            .contains("weather_humidity_percent{location=\"home\",provider=\"weather-api\"} 60\n"));
+        assert!(text.contains(
+            "weather_wind_speed_meters_per_second{location=\"home\",provider=\"weather-api\"} 5\n"
+        ));
+        // The missing values are not exported
+        assert!(!text.contains("weather_pressure_hectopascals{"));
+    }
+
+    #[test]
+    fn requests_counted_with_latency_histogram() {
+        let metrics = Metrics::new(["open-weather", "weather-api"]);
+        metrics.record_request("weather-api", Duration::from_millis(80), false);
+        metrics.record_request("weather-api", Duration::from_millis(300), true);
+        metrics.record_request("weather-api", Duration::from_secs(90), false);
+
+        let text = metrics.render();
+        assert!(text.contains("weather_provider_requests_total{provider=\"open-weather\"} 0\n"));
+        assert!(text.contains("weather_provider_requests_total{provider=\"weather-api\"} 3\n"));
+        assert!(text.contains("weather_provider_errors_total{provider=\"weather-api\"} 1\n"));
+        let histogram = "weather_provider_request_duration_seconds";
+        for (le, count) in [
+            ("0.05", 0),
+            ("0.1", 1),
+            ("0.25", 1),
+            ("0.5", 2),
+            ("60", 2),
+            ("+Inf", 3),
+        ] {
+            let line =
+                format!("{histogram}_bucket{{provider=\"weather-api\",le=\"{le}\"}} {count}\n");
+            assert!(text.contains(&line), "{line}");
+        }
+        assert!(text.contains(&format!(
+            "{histogram}_sum{{provider=\"weather-api\"}} 90.38\n"
+        )));
+        assert!(text.contains(&format!(
+            "{histogram}_count{{provider=\"weather-api\"}} 3\n"
+        )));
+    }
+
+    #[test]
+    fn label_values_escaped() {
+        assert_eq!(
+            labels(&[("location", "Main \"HQ\"\\\nannex")]),
+            "{location=\"Main \\\"HQ\\\"\\\\\\nannex\"}"
+        );
+    }
+}
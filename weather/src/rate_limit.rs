@@ -0,0 +1,69 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Spaces the requests to a provider evenly, so no more than the given number of requests are
+/// started per second.
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter which allows `rate` requests per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rate is not a positive number.
+    pub fn per_second(rate: f64) -> Self {
+        assert!(rate > 0.0, "The rate must be positive");
+        Self {
+            interval: Duration::from_secs_f64(1.0 / rate),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+    /// Waits for the next free slot, the slot is reserved before waiting so the concurrent
+    /// callers are served in the order of the calls.
+    pub async fn acquire(&self) {
+        self.acquire_many(1).await;
+    }
+    /// Waits for the next free slot and reserves the following ones too, for the operations
+    /// making up to `count` requests. The next callers wait for all the reserved slots.
+    pub async fn acquire_many(&self, count: u32) {
+        let slot = {
+            let mut next = self.next.lock().expect("The rate limiter lock is poisoned");
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval * count;
+            slot
+        };
+        tokio::time::sleep_until(slot.into()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn acquire_spaces_requests() {
+        let limiter = RateLimiter::per_second(20.0);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn acquire_many_reserves_the_slots() {
+        let limiter = RateLimiter::per_second(20.0);
+        let start = Instant::now();
+        limiter.acquire_many(3).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+        limiter.acquire().await;
+
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+}
@@ -0,0 +1,758 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::Infallible,
+    future::Future,
+    net::TcpListener,
+    rc::Rc,
+    time::Duration,
+};
+
+use chrono::{Days, NaiveDate};
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use tracing::Instrument;
+use weather_abstractions::{
+    DateSpec, DateSpecError, DateTimeSpec, Error as ProviderError, ProviderManager, Temperature,
+    TimeOfDay, Units, Weather, WeatherKind, WeatherProvider,
+};
+
+use crate::{cache::Cache, commands::error_message, rate_limit::RateLimiter};
+
+/// How long a retrieved weather is served to the other requests for the same location and date.
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+/// The most days of the forecast, today included.
+pub const MAX_FORECAST_DAYS: u8 = 14;
+const DEFAULT_FORECAST_DAYS: u8 = 3;
+/// The longest location accepted, the longer ones are rejected before reaching the provider.
+const MAX_LOCATION_LEN: usize = 256;
+const PATHS: [&str; 4] = [
+    "/v1/weather",
+    "/v1/forecast",
+    "/v1/providers",
+    "/openapi.json",
+];
+const OPENAPI: &str = include_str!("openapi.json");
+
+/// The weather for every day of the forecast.
+type Forecast = Vec<(NaiveDate, Weather)>;
+
+/// The JSON API over the weather providers, the responses of the providers are cached and the
+/// requests to them are rate limited, shared by all the clients.
+pub struct WeatherServer {
+    providers: ProviderManager,
+    /// The names of all the supported providers, including the ones which failed to build
+    names: Vec<String>,
+    default_provider: String,
+    locations: BTreeMap<String, String>,
+    current: Cache<(String, String), Weather>,
+    weather: Cache<(String, String, NaiveDate, Option<TimeOfDay>), Weather>,
+    forecast: Cache<(String, String, NaiveDate, u8), Forecast>,
+    local_dates: Cache<(String, String), NaiveDate>,
+    limiter: RateLimiter,
+}
+
+/// An error response, the message is returned in the `error` field of the body.
+#[derive(Debug)]
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self(StatusCode::BAD_REQUEST, message.into())
+    }
+    fn provider(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self(StatusCode::BAD_GATEWAY, error_message(error.as_ref()))
+    }
+    fn into_response(self) -> Response<Body> {
+        #[derive(Serialize)]
+        struct ErrorBody {
+            error: String,
+        }
+        json_response(self.0, &ErrorBody { error: self.1 })
+    }
+}
+
+/// The weather in the responses, with the values in the metric units.
+#[derive(Debug, Serialize)]
+struct WeatherRecord {
+    date: Option<String>,
+    time: Option<String>,
+    kind: &'static str,
+    temp_c: f32,
+    condition: String,
+    condition_kind: String,
+    humidity: Option<f32>,
+    wind_speed: Option<f32>,
+    pressure: Option<f32>,
+    feels_like_c: Option<f32>,
+    dew_point_c: Option<f32>,
+}
+
+impl WeatherRecord {
+    fn new(date: Option<NaiveDate>, weather: Weather) -> Self {
+        let round = |value: f32| (value * 10.0).round() / 10.0;
+        let comfort = weather.comfort();
+        let celsius = |temp: Temperature| round(temp.to_value(Units::Metric));
+        Self {
+            date: date.map(|date| date.to_string()),
+            time: weather.time.map(|time| time.to_rfc3339()),
+            kind: match weather.kind {
+                WeatherKind::History => "history",
+                WeatherKind::Current => "current",
+                WeatherKind::Forecast => "forecast",
+            },
+            temp_c: celsius(weather.temp),
+            condition_kind: format!("{:?}", weather.condition_kind),
+            condition: weather.condition,
+            humidity: weather.humidity.map(round),
+            wind_speed: weather.wind_speed.map(round),
+            pressure: weather.pressure.map(round),
+            feels_like_c: comfort.apparent_temperature.map(celsius),
+            dew_point_c: comfort.dew_point.map(celsius),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct WeatherResponse {
+    location: String,
+    provider: String,
+    #[serde(flatten)]
+    weather: WeatherRecord,
+}
+
+#[derive(Serialize)]
+struct ForecastResponse {
+    location: String,
+    provider: String,
+    days: Vec<WeatherRecord>,
+}
+
+#[derive(Serialize)]
+struct ProvidersResponse<'a> {
+    default: &'a str,
+    providers: Vec<ProviderStatus<'a>>,
+}
+
+#[derive(Serialize)]
+struct ProviderStatus<'a> {
+    name: &'a str,
+    available: bool,
+}
+
+/// The query parameters of a request, a repeated parameter keeps its last value.
+struct Params(HashMap<String, String>);
+
+impl Params {
+    fn parse(query: Option<&str>) -> Self {
+        let query = query.unwrap_or_default().as_bytes();
+        Self(url::form_urlencoded::parse(query).into_owned().collect())
+    }
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+}
+
+impl WeatherServer {
+    /// Creates the API over the providers of the manager, building all of them. The providers
+    /// failing to build, usually the ones not configured, are listed as not available.
+    ///
+    /// The saved location aliases are resolved in the `location` parameter and the requests to
+    /// the providers are limited to `rate` per second.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the `get_provider` method of the `ProviderManager` if the default
+    /// provider can't be built.
+    pub fn new(
+        mut providers: ProviderManager,
+        default_provider: impl Into<String>,
+        locations: BTreeMap<String, String>,
+        rate: f64,
+    ) -> Result<Self, ProviderError> {
+        let default_provider = default_provider.into();
+        providers.get_provider(&default_provider)?;
+        let mut names: Vec<String> = providers
+            .get_list_providers()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        names.sort_unstable();
+        for name in &names {
+            // The other providers are optional, the failed ones are not available
+            let _ = providers.get_provider(name);
+        }
+        Ok(Self {
+            providers,
+            names,
+            default_provider,
+            locations,
+            current: Cache::new(CACHE_TTL),
+            weather: Cache::new(CACHE_TTL),
+            forecast: Cache::new(CACHE_TTL),
+            local_dates: Cache::new(CACHE_TTL),
+            limiter: RateLimiter::per_second(rate),
+        })
+    }
+
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let path = request.uri().path();
+        if !PATHS.contains(&path) {
+            return ApiError(StatusCode::NOT_FOUND, format!("No resource at '{path}'"))
+                .into_response();
+        }
+        if request.method() != Method::GET {
+            let mut response = ApiError(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Only the GET method is allowed".to_string(),
+            )
+            .into_response();
+            response
+                .headers_mut()
+                .insert(header::ALLOW, header::HeaderValue::from_static("GET"));
+            return response;
+        }
+        let params = Params::parse(request.uri().query());
+        let response = match path {
+            "/v1/weather" => self.weather(&params).await,
+            "/v1/forecast" => self.forecast(&params).await,
+            "/v1/providers" => Ok(self.providers()),
+            _ => Ok(Response::builder()
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(OPENAPI))
+                .expect("valid response")),
+        };
+        response.unwrap_or_else(ApiError::into_response)
+    }
+
+    async fn weather(&self, params: &Params) -> Result<Response<Body>, ApiError> {
+        let (provider_name, provider) = self.provider(params)?;
+        let (name, location) = self.location(params)?;
+        let date = params
+            .get("date")
+            .map(str::parse::<DateTimeSpec>)
+            .transpose()
+            .map_err(|e: DateSpecError| ApiError::bad_request(e.to_string()))?;
+        let (date, weather) = match date {
+            None => {
+                let key = (provider_name.to_string(), location.to_string());
+                let weather = match self.current.get(&key) {
+                    Some(weather) => weather,
+                    None => {
+                        self.limiter.acquire().await;
+                        let weather = provider
+                            .get_weather(location, None)
+                            .await
+                            .map_err(ApiError::provider)?;
+                        self.current.insert(key, weather.clone());
+                        weather
+                    }
+                };
+                (None, weather)
+            }
+            Some(DateTimeSpec { date, time }) => {
+                let date = self
+                    .resolve(provider_name, provider, date, location)
+                    .await?;
+                let key = (provider_name.to_string(), location.to_string(), date, time);
+                let weather = match self.weather.get(&key) {
+                    Some(weather) => weather,
+                    None => {
+                        self.limiter.acquire().await;
+                        let weather = match time {
+                            Some(time) => provider.get_weather_at(location, date, time).await,
+                            None => provider.get_weather(location, Some(date)).await,
+                        }
+                        .map_err(ApiError::provider)?;
+                        self.weather.insert(key, weather.clone());
+                        weather
+                    }
+                };
+                (Some(date), weather)
+            }
+        };
+        Ok(json_response(
+            StatusCode::OK,
+            &WeatherResponse {
+                location: name.to_string(),
+                provider: provider_name.to_string(),
+                weather: WeatherRecord::new(date, weather),
+            },
+        ))
+    }
+
+    async fn forecast(&self, params: &Params) -> Result<Response<Body>, ApiError> {
+        let (provider_name, provider) = self.provider(params)?;
+        let (name, location) = self.location(params)?;
+        let days = match params.get("days") {
+            None => DEFAULT_FORECAST_DAYS,
+            Some(days) => days
+                .parse()
+                .ok()
+                .filter(|days| (1..=MAX_FORECAST_DAYS).contains(days))
+                .ok_or_else(|| {
+                    ApiError::bad_request(format!(
+                        "The days must be a number from 1 to {MAX_FORECAST_DAYS}, got '{days}'"
+                    ))
+                })?,
+        };
+        let today = self
+            .resolve(provider_name, provider, DateSpec::Days(0), location)
+            .await?;
+        let key = (provider_name.to_string(), location.to_string(), today, days);
+        let forecast = match self.forecast.get(&key) {
+            Some(forecast) => forecast,
+            None => {
+                // The providers without a range request retrieve the days one by one
+                self.limiter.acquire_many(days.into()).await;
+                let last = today + Days::new(u64::from(days) - 1);
+                let forecast = provider
+                    .get_weather_range(location, today, last)
+                    .await
+                    .map_err(ApiError::provider)?;
+                self.forecast.insert(key, forecast.clone());
+                forecast
+            }
+        };
+        Ok(json_response(
+            StatusCode::OK,
+            &ForecastResponse {
+                location: name.to_string(),
+                provider: provider_name.to_string(),
+                days: forecast
+                    .into_iter()
+                    .map(|(date, weather)| WeatherRecord::new(Some(date), weather))
+                    .collect(),
+            },
+        ))
+    }
+
+    fn providers(&self) -> Response<Body> {
+        let providers = self
+            .names
+            .iter()
+            .map(|name| ProviderStatus {
+                name,
+                available: self.providers.get_built_provider(name).is_some(),
+            })
+            .collect();
+        json_response(
+            StatusCode::OK,
+            &ProvidersResponse {
+                default: &self.default_provider,
+                providers,
+            },
+        )
+    }
+
+    /// The provider of the `provider` parameter, the default provider if not given.
+    fn provider<'a>(
+        &'a self,
+        params: &'a Params,
+    ) -> Result<(&'a str, &'a dyn WeatherProvider), ApiError> {
+        let name = params.get("provider").unwrap_or(&self.default_provider);
+        let provider = self.providers.get_built_provider(name).ok_or_else(|| {
+            ApiError::bad_request(format!(
+                "The provider '{name}' is unknown or not configured, see /v1/providers"
+            ))
+        })?;
+        Ok((name, provider))
+    }
+
+    /// The name of the location as requested and the location itself, the alias resolved.
+    fn location<'a>(&'a self, params: &'a Params) -> Result<(&'a str, &'a str), ApiError> {
+        let name = params
+            .get("location")
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| ApiError::bad_request("The location parameter is required"))?;
+        if name.len() > MAX_LOCATION_LEN {
+            return Err(ApiError::bad_request(format!(
+                "The location must be at most {MAX_LOCATION_LEN} bytes long"
+            )));
+        }
+        let location = self.locations.get(name).map_or(name, String::as_str);
+        Ok((name, location))
+    }
+
+    /// Resolves the date at the location, the current date of the location is cached.
+    async fn resolve(
+        &self,
+        provider_name: &str,
+        provider: &dyn WeatherProvider,
+        date: DateSpec,
+        location: &str,
+    ) -> Result<NaiveDate, ApiError> {
+        if let DateSpec::Date(date) = date {
+            return Ok(date);
+        }
+        let key = (provider_name.to_string(), location.to_string());
+        let today = match self.local_dates.get(&key) {
+            Some(today) => today,
+            None => {
+                self.limiter.acquire().await;
+                let today = provider
+                    .local_date(location)
+                    .await
+                    .map_err(ApiError::provider)?;
+                self.local_dates.insert(key, today);
+                today
+            }
+        };
+        Ok(date.resolve(today))
+    }
+}
+
+fn json_response(status: StatusCode, body: &impl Serialize) -> Response<Body> {
+    let body = serde_json::to_vec(body).expect("The response serializes to JSON");
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("valid response")
+}
+
+/// Runs the connections on the current thread, so the requests can share the state by `Rc`.
+#[derive(Clone, Copy)]
+struct LocalExec;
+
+impl<F: Future + 'static> hyper::rt::Executor<F> for LocalExec {
+    fn execute(&self, future: F) {
+        tokio::task::spawn_local(future);
+    }
+}
+
+/// Serves the API on the listener until the `shutdown` future completes, see `serve`.
+///
+/// # Errors
+///
+/// Returns an error if the listener can't be used by the server or accepting the connections
+/// fails.
+pub async fn serve_api(
+    api: WeatherServer,
+    listener: TcpListener,
+    shutdown: impl Future<Output = ()>,
+) -> hyper::Result<()> {
+    let api = Rc::new(api);
+    let handler = move |request| {
+        let api = Rc::clone(&api);
+        async move { api.handle(request).await }
+    };
+    serve(handler, listener, shutdown).await
+}
+
+/// Serves the responses of the handler on the listener until the `shutdown` future completes,
+/// the requests in progress are completed before returning.
+///
+/// The connections are handled concurrently on the current thread, so the handler can share its
+/// state by `Rc`, and it must be called within a `tokio::task::LocalSet`.
+///
+/// # Errors
+///
+/// Returns an error if the listener can't be used by the server or accepting the connections
+/// fails.
+pub async fn serve<H, R>(
+    handler: H,
+    listener: TcpListener,
+    shutdown: impl Future<Output = ()>,
+) -> hyper::Result<()>
+where
+    H: Fn(Request<Body>) -> R + Clone + 'static,
+    R: Future<Output = Response<Body>> + 'static,
+{
+    let make_service = make_service_fn(move |_| {
+        let handler = handler.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                // Every request is a trace of its own, not a part of the long running command
+                let span = tracing::info_span!(
+                    parent: None,
+                    "serve request",
+                    otel.name = format!("{} {}", request.method(), request.uri().path()),
+                    otel.kind = "server",
+                    http.method = %request.method(),
+                    http.target = request.uri().path(),
+                    http.status_code = tracing::field::Empty,
+                );
+                let response = handler(request).instrument(span.clone());
+                async move {
+                    let response = response.await;
+                    span.record("http.status_code", response.status().as_u16());
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+    Server::from_tcp(listener)?
+        .executor(LocalExec)
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use config::Config;
+    use httptest::{
+        matchers::{all_of, contains, request, url_decoded},
+        responders::json_encoded,
+        Expectation, Server as FakeServer,
+    };
+    use reqwest::StatusCode as Status;
+    use serde_json::{json, Value};
+    use std::future::pending;
+    use weather_abstractions::ProviderManagerBuilder;
+
+    fn current(temp_c: f32) -> Value {
+        json!({
+            "location": {
+                "name": "London",
+                "region": "City of London, Greater London",
+                "country": "United Kingdom",
+                "lat": 51.52,
+                "lon": -0.11,
+                "localtime": format!("{} 10:00", Utc::now().date_naive())
+            },
+            "current": {
+                "last_updated": format!("{} 09:45", Utc::now().date_naive()),
+                "temp_c": temp_c,
+                "humidity": 60,
+                "wind_kph": 18.0,
+                "condition": { "text": "Sunny", "icon": "", "code": 1000 }
+            }
+        })
+    }
+
+    fn forecast(temp_c: f32) -> Value {
+        json!({
+            "location": { "name": "London", "region": "", "country": "", "lat": 51.52, "lon": -0.11 },
+            "forecast": {
+                "forecastday": [{
+                    "date": (Utc::now().date_naive() + Days::new(1)).to_string(),
+                    "day": {
+                        "avgtemp_c": temp_c,
+                        "condition": { "text": "Light rain", "icon": "", "code": 1183 }
+                    }
+                }]
+            }
+        })
+    }
+
+    /// The API with the WeatherAPI provider requesting the fake server, and OpenWeather which
+    /// is not configured.
+    fn api(fake: &FakeServer) -> WeatherServer {
+        let conf = Config::builder()
+            .set_override("weather-api.apiKey", "some-api-key")
+            .and_then(|conf| conf.set_override("weather-api.baseUrl", fake.url_str("")))
+            .and_then(|conf| conf.build())
+            .expect("config built");
+        let weather_api = weather_api::WeatherApiBuilder::build(&conf).expect("provider built");
+        let providers = ProviderManagerBuilder::default()
+            .add_provider(weather_api::PROVIDER_NAME, weather_api)
+            .add_provider_builder(open_weather::PROVIDER_NAME, || {
+                Err("The API key is not set".into())
+            })
+            .build();
+        let locations = BTreeMap::from([("home".to_string(), "51.5,-0.13".to_string())]);
+        WeatherServer::new(providers, weather_api::PROVIDER_NAME, locations, 1000.0)
+            .expect("default provider built")
+    }
+
+    /// Serves the API on a free port and runs the test with the base URL of the server.
+    async fn with_server<T: Future<Output = ()>>(
+        api: WeatherServer,
+        test: impl FnOnce(String) -> T,
+    ) {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async move {
+                let listener = TcpListener::bind("127.0.0.1:0").expect("port bound");
+                listener
+                    .set_nonblocking(true)
+                    .expect("non-blocking listener");
+                let base = format!("http://{}", listener.local_addr().expect("local address"));
+                tokio::task::spawn_local(serve_api(api, listener, pending()));
+                test(base).await;
+            })
+            .await;
+    }
+
+    async fn get(url: String) -> (Status, Value) {
+        let response = reqwest::get(url).await.expect("response received");
+        let status = response.status();
+        (status, response.json().await.expect("JSON body"))
+    }
+
+    #[tokio::test]
+    async fn current_weather_is_cached() {
+        let fake = FakeServer::run();
+        fake.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1/current.json"),
+                request::query(url_decoded(contains(("q", "London")))),
+            ])
+            .times(1)
+            .respond_with(json_encoded(current(21.0))),
+        );
+
+        with_server(api(&fake), |base| async move {
+            for _ in 0..2 {
+                let (status, body) = get(format!("{base}/v1/weather?location=London")).await;
+                assert_eq!(status, Status::OK, "{body}");
+                assert_eq!(body["location"], "London");
+                assert_eq!(body["provider"], "weather-api");
+                assert_eq!(body["kind"], "current");
+                assert_eq!(body["temp_c"], 21.0);
+                assert_eq!(body["condition_kind"], "Clear");
+                assert_eq!(body["wind_speed"], 5.0);
+                assert_eq!(body["date"], Value::Null);
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn weather_for_relative_date_of_alias() {
+        let fake = FakeServer::run();
+        fake.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1/current.json"),
+                request::query(url_decoded(contains(("q", "51.5,-0.13")))),
+            ])
+            .respond_with(json_encoded(current(21.0))),
+        );
+        fake.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1/forecast.json"),
+                request::query(url_decoded(contains(("q", "51.5,-0.13")))),
+                request::query(url_decoded(contains(("days", "1")))),
+            ])
+            .respond_with(json_encoded(forecast(14.0))),
+        );
+
+        with_server(api(&fake), |base| async move {
+            let url = format!("{base}/v1/weather?location=home&date=tomorrow");
+            let (status, body) = get(url).await;
+            assert_eq!(status, Status::OK);
+            assert_eq!(body["location"], "home");
+            assert_eq!(body["kind"], "forecast");
+            assert_eq!(body["temp_c"], 14.0);
+            let tomorrow = Utc::now().date_naive() + Days::new(1);
+            assert_eq!(body["date"], tomorrow.to_string());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn forecast_from_today() {
+        let fake = FakeServer::run();
+        // The current date at the location and the weather for today
+        fake.expect(
+            Expectation::matching(request::method_path("GET", "/v1/current.json"))
+                .times(2)
+                .respond_with(json_encoded(current(21.0))),
+        );
+        fake.expect(
+            Expectation::matching(request::method_path("GET", "/v1/forecast.json"))
+                .respond_with(json_encoded(forecast(14.0))),
+        );
+
+        with_server(api(&fake), |base| async move {
+            let (status, body) = get(format!("{base}/v1/forecast?location=London&days=2")).await;
+            assert_eq!(status, Status::OK, "{body}");
+            let days = body["days"].as_array().expect("days");
+            assert_eq!(days.len(), 2);
+            assert_eq!(days[0]["temp_c"], 21.0);
+            assert_eq!(days[1]["temp_c"], 14.0);
+            assert_eq!(days[1]["condition_kind"], "Rain");
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn invalid_requests_rejected() {
+        let fake = FakeServer::run();
+        let long = "x".repeat(MAX_LOCATION_LEN + 1);
+
+        with_server(api(&fake), |base| async move {
+            for query in [
+                "/v1/weather".to_string(),
+                "/v1/weather?location=%20".to_string(),
+                format!("/v1/weather?location={long}"),
+                "/v1/weather?location=London&date=someday".to_string(),
+                "/v1/weather?location=London&provider=open-weather".to_string(),
+                "/v1/weather?location=London&provider=unknown".to_string(),
+                "/v1/forecast?location=London&days=0".to_string(),
+                "/v1/forecast?location=London&days=15".to_string(),
+                "/v1/forecast?location=London&days=two".to_string(),
+            ] {
+                let (status, body) = get(format!("{base}{query}")).await;
+                assert_eq!(status, Status::BAD_REQUEST, "{query}");
+                assert!(body["error"].is_string(), "{query}");
+            }
+            let (status, _) = get(format!("{base}/v2/weather")).await;
+            assert_eq!(status, Status::NOT_FOUND);
+            let response = reqwest::Client::new()
+                .post(format!("{base}/v1/weather?location=London"))
+                .send()
+                .await
+                .expect("response received");
+            assert_eq!(response.status(), Status::METHOD_NOT_ALLOWED);
+            assert_eq!(response.headers()["allow"], "GET");
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn provider_error_is_bad_gateway() {
+        let fake = FakeServer::run();
+        fake.expect(
+            Expectation::matching(request::method_path("GET", "/v1/current.json")).respond_with(
+                httptest::responders::status_code(400).body(
+                    json!({"error": {"code": 1006, "message": "No matching location found."}})
+                        .to_string(),
+                ),
+            ),
+        );
+
+        with_server(api(&fake), |base| async move {
+            let (status, body) = get(format!("{base}/v1/weather?location=Nowhere")).await;
+            assert_eq!(status, Status::BAD_GATEWAY);
+            let error = body["error"].as_str().expect("error message");
+            assert!(error.contains("No matching location found."), "{error}");
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn providers_and_openapi_listed() {
+        let fake = FakeServer::run();
+
+        with_server(api(&fake), |base| async move {
+            let (status, body) = get(format!("{base}/v1/providers")).await;
+            assert_eq!(status, Status::OK);
+            assert_eq!(
+                body,
+                json!({
+                    "default": "weather-api",
+                    "providers": [
+                        { "name": "open-weather", "available": false },
+                        { "name": "weather-api", "available": true }
+                    ]
+                })
+            );
+
+            let (status, body) = get(format!("{base}/openapi.json")).await;
+            assert_eq!(status, Status::OK);
+            for path in PATHS {
+                assert!(body["paths"][path]["get"].is_object(), "{path}");
+            }
+        })
+        .await;
+    }
+}
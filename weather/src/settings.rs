@@ -1,34 +1,89 @@
-use config::Config;
+use config::{Config, FileFormat};
 use serde::Serialize;
 use serde_json::{json, Value};
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 use tokio::{
     fs::{create_dir_all, read_to_string, File},
     io::AsyncWriteExt,
 };
+use weather_abstractions::{Coordinates, CoordinatesError};
 pub const APP_NAME: &str = "weather";
 
+/// The name of the profile stored in the root of the settings file.
+pub const DEFAULT_PROFILE: &str = "default";
+/// The settings key of the named profiles section.
+const PROFILES: &str = "profiles";
+/// The settings key of the profile used when no profile is given explicitly.
+const ACTIVE_PROFILE: &str = "activeProfile";
+/// The settings key of the saved locations section, shared by all the profiles.
+const LOCATIONS: &str = "locations";
+/// The settings key of the saved location groups section, shared by all the profiles.
+const LOCATION_GROUPS: &str = "locationGroups";
+/// The settings key of the location alias used when no address is given.
+const DEFAULT_LOCATION: &str = "defaultLocation";
+/// The provider used until another one is configured, it needs no API key.
+pub const DEFAULT_PROVIDER: &str = open_meteo::PROVIDER_NAME;
+
 /// This struct contains the functions fro settings of the application.
-pub struct Settings;
+///
+/// The settings of the default profile are stored in the root of the settings file, the settings
+/// of the named profiles are stored under `profiles/<name>`, with the same structure.
+pub struct Settings {
+    profile: Option<String>,
+}
 impl Settings {
-    /// Returns the configuration of the application.
+    /// Opens the settings of the given profile, or of the active profile if no profile is given.
     ///
     /// # Errors
     ///
-    /// This function will return a `SettingsError` if there was an error reading or constructing the
-    /// configuration file.
-    pub async fn conf() -> Result<Config, SettingsError> {
+    /// This function will return a `SettingsError` if there was an error reading the settings file.
+    pub async fn open(profile: Option<String>) -> Result<Settings, SettingsError> {
         let congif_path = get_conf_path().await;
         if !congif_path.exists() {
-            reset_json_file(congif_path.clone()).await?;
+            reset_json_file(congif_path).await?;
         }
-        let conf = Config::builder()
-            .add_source(config::File::from(congif_path))
+        let config = read_json().await?;
+        let profile = match profile {
+            Some(profile) => profile,
+            None => lookup(&config, ACTIVE_PROFILE)
+                .and_then(Value::as_str)
+                .unwrap_or(DEFAULT_PROFILE)
+                .to_string(),
+        };
+        let profile = (profile != DEFAULT_PROFILE).then_some(profile);
+        Ok(Settings { profile })
+    }
+    /// Returns the name of the opened profile.
+    pub fn profile(&self) -> &str {
+        self.profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+    /// Returns the configuration of the opened profile, with `DEFAULT_PROVIDER` as the provider
+    /// when none is configured.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::ProfileNotFound` if the profile doesn't exist, or
+    /// another `SettingsError` if there was an error reading or constructing the configuration file.
+    pub async fn conf(&self) -> Result<Config, SettingsError> {
+        let congif_path = get_conf_path().await;
+        let source = match &self.profile {
+            None => Config::builder().add_source(config::File::from(congif_path)),
+            Some(name) => {
+                let profile = self.get("").await.map_err(|e| match e {
+                    SettingsError::Path(_) => SettingsError::ProfileNotFound(name.clone()),
+                    e => e,
+                })?;
+                let json = serde_json::to_string(&profile).map_err(|_| SettingsError::Damaged)?;
+                Config::builder().add_source(config::File::from_str(&json, FileFormat::Json))
+            }
+        };
+        let conf = source
+            .set_default("provider", DEFAULT_PROVIDER)?
             .build()
             .expect("Configuration has to be constructed.");
         Ok(conf)
     }
-    /// Sets a new value for a specified path in the configuration.
+    /// Sets a new value for a specified path in the configuration of the opened profile.
     ///
     /// # Errors
     ///
@@ -40,46 +95,420 @@ impl Settings {
     /// ```
     /// use settings::Settings;
     ///
+    /// let settings = Settings::open(None).await?;
     /// let path = "myProvider/baseUrl";
     /// let val = "https://127.0.0.1:8080";
-    /// let result = Settings::set(path, val).await;
+    /// let result = settings.set(path, val).await;
     /// ```
     #[cfg(not(doctest))]
-    pub async fn set<T>(path: &str, val: &T) -> Result<(), SettingsError>
+    pub async fn set<T>(&self, path: &str, val: &T) -> Result<(), SettingsError>
     where
         T: Sized + Serialize,
     {
-        let congif_path = get_conf_path().await;
-        let file_contents = read_to_string(congif_path.as_path()).await?;
-        let mut config: Value =
-            serde_json::from_str(&file_contents).map_err(|_| SettingsError::Damaged)?;
+        let mut config = read_json().await?;
         let str_val = serde_json::to_string(val).map_err(|_| SettingsError::Input)?;
         let val: Value = serde_json::from_str(&str_val).map_err(|_| SettingsError::Input)?;
+        insert(&mut config, &self.path(path), val)?;
+        write_json(&config).await
+    }
+    /// Returns the value for a specified path in the configuration of the opened profile.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::Path` if there is no value for the path, or
+    /// another `SettingsError` if the configuration file could not be read.
+    pub async fn get(&self, path: &str) -> Result<Value, SettingsError> {
+        let config = read_json().await?;
+        lookup(&config, &self.path(path))
+            .cloned()
+            .ok_or(SettingsError::Path(path.to_string()))
+    }
+    /// Removes the value, or the whole section, for a specified path in the configuration of
+    /// the opened profile.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::Path` if there is no value for the path, or
+    /// another `SettingsError` if the configuration file could not be read or written.
+    pub async fn unset(&self, path: &str) -> Result<(), SettingsError> {
+        let mut config = read_json().await?;
+        remove(&mut config, &self.path(path)).ok_or(SettingsError::Path(path.to_string()))?;
+        write_json(&config).await
+    }
+    /// Returns all the values in the configuration of the opened profile with their slash
+    /// separated paths.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError` if the configuration file could not be read.
+    pub async fn list(&self) -> Result<Vec<(String, Value)>, SettingsError> {
+        let mut profile = self.get("").await?;
+        if let Some(root) = profile.as_object_mut() {
+            root.retain(|key, _| !is_global(key));
+        }
+        let mut values = Vec::new();
+        flatten(&profile, String::new(), &mut values);
+        Ok(values)
+    }
+    /// Returns a copy of the configuration with the given values applied on top of it,
+    /// without writing them to the settings file.
+    ///
+    /// # Arguments
+    ///
+    /// * `conf` - The configuration to copy.
+    /// * `values` - The values with the same slash separated paths as used by `Settings::set`.
+    pub fn with_overrides(
+        conf: &Config,
+        values: &[(String, String)],
+    ) -> Result<Config, SettingsError> {
+        let mut builder = Config::builder().add_source(conf.clone());
+        for (path, value) in values {
+            builder = builder.set_override(path.replace('/', "."), value.as_str())?;
+        }
+        Ok(builder.build()?)
+    }
+    /// Resets the settings of the opened profile to its default values.
+    /// The named profiles are kept when the default profile is reset.
+    pub async fn reset(&self) -> Result<(), SettingsError> {
+        let mut config = read_json().await?;
+        let profile = lookup_mut(&mut config, &self.path(""))
+            .and_then(Value::as_object_mut)
+            .ok_or(SettingsError::RootConfig)?;
+        profile.retain(|key, _| is_global(key));
+        write_json(&config).await
+    }
+    /// Returns the names of all the profiles, including the default one.
+    pub async fn profiles() -> Result<Vec<String>, SettingsError> {
+        let config = read_json().await?;
+        let mut profiles = vec![DEFAULT_PROFILE.to_string()];
+        if let Some(named) = lookup(&config, PROFILES).and_then(Value::as_object) {
+            profiles.extend(named.keys().cloned());
+        }
+        Ok(profiles)
+    }
+    /// Creates a new empty profile.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::ProfileExists` if the profile already exists,
+    /// or a `SettingsError::ProfileName` if the name is not valid.
+    pub async fn create_profile(name: &str) -> Result<(), SettingsError> {
+        validate_profile_name(name)?;
+        let mut config = read_json().await?;
+        if name == DEFAULT_PROFILE || lookup(&config, &profile_path(name)).is_some() {
+            return Err(SettingsError::ProfileExists(name.to_string()));
+        }
+        insert(&mut config, &profile_path(name), json!({}))?;
+        write_json(&config).await
+    }
+    /// Deletes the profile, the default profile is used if the deleted profile was active.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::ProfileNotFound` if the profile doesn't exist,
+    /// or a `SettingsError::ProfileName` for the default profile, which can't be deleted.
+    pub async fn delete_profile(name: &str) -> Result<(), SettingsError> {
+        if name == DEFAULT_PROFILE {
+            return Err(SettingsError::ProfileName(name.to_string()));
+        }
+        let mut config = read_json().await?;
+        remove(&mut config, &profile_path(name))
+            .ok_or(SettingsError::ProfileNotFound(name.to_string()))?;
+        if lookup(&config, ACTIVE_PROFILE).and_then(Value::as_str) == Some(name) {
+            remove(&mut config, ACTIVE_PROFILE);
+        }
+        write_json(&config).await
+    }
+    /// Makes the profile active, so it is used when no profile is given explicitly.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::ProfileNotFound` if the profile doesn't exist.
+    pub async fn use_profile(name: &str) -> Result<(), SettingsError> {
+        let mut config = read_json().await?;
+        if name == DEFAULT_PROFILE {
+            remove(&mut config, ACTIVE_PROFILE);
+        } else {
+            lookup(&config, &profile_path(name))
+                .ok_or(SettingsError::ProfileNotFound(name.to_string()))?;
+            insert(&mut config, ACTIVE_PROFILE, json!(name))?;
+        }
+        write_json(&config).await
+    }
+    /// Returns the saved locations by their aliases.
+    pub async fn locations() -> Result<BTreeMap<String, String>, SettingsError> {
+        let config = read_json().await?;
+        let locations = lookup(&config, LOCATIONS)
+            .and_then(Value::as_object)
+            .map(|locations| {
+                locations
+                    .iter()
+                    .filter_map(|(alias, location)| {
+                        Some((alias.clone(), location.as_str()?.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(locations)
+    }
+    /// Returns the alias of the default location, if any.
+    pub async fn default_location() -> Result<Option<String>, SettingsError> {
+        let config = read_json().await?;
+        Ok(lookup(&config, DEFAULT_LOCATION)
+            .and_then(Value::as_str)
+            .map(String::from))
+    }
+    /// Saves the location, an address or coordinates in the `latitude,longitude` format, under the alias.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::LocationName` if the alias is not valid, or
+    /// a `SettingsError::Coordinates` if the location looks like coordinates out of range.
+    pub async fn add_location(alias: &str, location: &str) -> Result<(), SettingsError> {
+        validate_location_name(alias)?;
+        let location = match location.parse::<Coordinates>() {
+            Ok(coordinates) => coordinates.to_string(),
+            Err(CoordinatesError::Format(_)) => location.trim().to_string(),
+            Err(e) => return Err(e.into()),
+        };
+        let mut config = read_json().await?;
+        insert(
+            &mut config,
+            &format!("{LOCATIONS}/{alias}"),
+            json!(location),
+        )?;
+        write_json(&config).await
+    }
+    /// Removes the saved location, and the default location if it was the removed one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::LocationNotFound` if there is no location for the alias.
+    pub async fn remove_location(alias: &str) -> Result<(), SettingsError> {
+        let mut config = read_json().await?;
+        remove(&mut config, &format!("{LOCATIONS}/{alias}"))
+            .ok_or(SettingsError::LocationNotFound(alias.to_string()))?;
+        if lookup(&config, DEFAULT_LOCATION).and_then(Value::as_str) == Some(alias) {
+            remove(&mut config, DEFAULT_LOCATION);
+        }
+        write_json(&config).await
+    }
+    /// Makes the saved location the default one, used when no address is given.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::LocationNotFound` if there is no location for the alias.
+    pub async fn set_default_location(alias: &str) -> Result<(), SettingsError> {
+        let mut config = read_json().await?;
+        lookup(&config, &format!("{LOCATIONS}/{alias}"))
+            .ok_or(SettingsError::LocationNotFound(alias.to_string()))?;
+        insert(&mut config, DEFAULT_LOCATION, json!(alias))?;
+        write_json(&config).await
+    }
+    /// Returns the saved location groups by their names.
+    pub async fn location_groups() -> Result<BTreeMap<String, Vec<String>>, SettingsError> {
+        let config = read_json().await?;
+        let groups = lookup(&config, LOCATION_GROUPS)
+            .and_then(Value::as_object)
+            .map(|groups| {
+                groups
+                    .iter()
+                    .filter_map(|(name, members)| {
+                        Some((name.clone(), serde_json::from_value(members.clone()).ok()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(groups)
+    }
+    /// Saves the group of locations, aliases or addresses, under the name, replacing the saved one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::LocationName` if the name is not valid.
+    pub async fn add_location_group(name: &str, members: &[String]) -> Result<(), SettingsError> {
+        validate_location_name(name)?;
+        let mut config = read_json().await?;
+        insert(
+            &mut config,
+            &format!("{LOCATION_GROUPS}/{name}"),
+            json!(members),
+        )?;
+        write_json(&config).await
+    }
+    /// Removes the saved location group, the locations of the group are kept.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::LocationGroupNotFound` if there is no group with the name.
+    pub async fn remove_location_group(name: &str) -> Result<(), SettingsError> {
+        let mut config = read_json().await?;
+        remove(&mut config, &format!("{LOCATION_GROUPS}/{name}"))
+            .ok_or(SettingsError::LocationGroupNotFound(name.to_string()))?;
+        write_json(&config).await
+    }
+    /// Resolves the addresses given by the user to the locations requested from the provider.
+    ///
+    /// A saved alias is replaced with its location, a group name prefixed with `@` is replaced
+    /// with the locations of the group, other addresses are returned as is, and the default
+    /// location is used when no address is given.
+    ///
+    /// # Returns
+    ///
+    /// The names to show to the user, the aliases or the addresses, with the locations for the provider.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `SettingsError::NoDefaultLocation` if no address is given and
+    /// there is no default location, or a `SettingsError::LocationGroupNotFound` for an unknown group.
+    pub async fn resolve_locations(
+        addresses: &[String],
+    ) -> Result<Vec<(String, String)>, SettingsError> {
+        let config = read_json().await?;
+        let mut names = Vec::new();
+        if addresses.is_empty() {
+            let default = lookup(&config, DEFAULT_LOCATION)
+                .and_then(Value::as_str)
+                .ok_or(SettingsError::NoDefaultLocation)?;
+            names.push(default.to_string());
+        }
+        for address in addresses {
+            match address.strip_prefix('@') {
+                Some(group) => {
+                    let members = lookup(&config, &format!("{LOCATION_GROUPS}/{group}"))
+                        .and_then(Value::as_array)
+                        .ok_or(SettingsError::LocationGroupNotFound(group.to_string()))?;
+                    names.extend(members.iter().filter_map(Value::as_str).map(String::from));
+                }
+                None => names.push(address.clone()),
+            }
+        }
+        let locations = names
+            .into_iter()
+            .map(|name| {
+                let location = lookup(&config, &format!("{LOCATIONS}/{name}"))
+                    .and_then(Value::as_str)
+                    .map_or_else(|| name.clone(), String::from);
+                (name, location)
+            })
+            .collect();
+        Ok(locations)
+    }
+    /// Returns the path in the settings file for the path in the opened profile.
+    fn path(&self, path: &str) -> String {
+        match &self.profile {
+            None => path.to_string(),
+            Some(profile) if path.is_empty() => profile_path(profile),
+            Some(profile) => format!("{}/{path}", profile_path(profile)),
+        }
+    }
+}
 
-        let mut paths: Vec<&str> = path.split('/').collect();
-        let last = paths.pop().ok_or(SettingsError::Path(path.to_string()))?;
+fn profile_path(name: &str) -> String {
+    format!("{PROFILES}/{name}")
+}
 
-        let mut current = config.as_object_mut().ok_or(SettingsError::RootConfig)?;
+/// Whether the root key belongs to the whole settings file rather than to the default profile.
+fn is_global(key: &str) -> bool {
+    [
+        PROFILES,
+        ACTIVE_PROFILE,
+        LOCATIONS,
+        LOCATION_GROUPS,
+        DEFAULT_LOCATION,
+    ]
+    .contains(&key)
+}
 
-        for sub_path in paths.into_iter() {
-            current = current
-                .entry(sub_path)
-                .or_insert(json!({}))
-                .as_object_mut()
-                .ok_or(SettingsError::Structure(sub_path.to_owned()))?;
-        }
-        current.insert(last.to_owned(), val);
+/// Checks that the alias or the group name can be used in a settings path and told apart from
+/// an address, coordinates or a group reference.
+fn validate_location_name(name: &str) -> Result<(), SettingsError> {
+    if name.is_empty() || name.contains(['/', '.', '@', ',']) {
+        return Err(SettingsError::LocationName(name.to_string()));
+    }
+    Ok(())
+}
 
-        let str_conf = serde_json::to_string(&config).map_err(|_| SettingsError::Damaged)?;
-        File::create(congif_path.as_path())
-            .await?
-            .write_all(str_conf.as_bytes())
-            .await?;
-        Ok(())
+fn validate_profile_name(name: &str) -> Result<(), SettingsError> {
+    if name.is_empty() || name.contains(['/', '.']) {
+        return Err(SettingsError::ProfileName(name.to_string()));
     }
-    pub async fn reset() -> Result<(), SettingsError> {
-        let congif_path = get_conf_path().await;
-        reset_json_file(congif_path).await
+    Ok(())
+}
+
+/// Reads the configuration file as a JSON value.
+async fn read_json() -> Result<Value, SettingsError> {
+    let congif_path = get_conf_path().await;
+    let file_contents = read_to_string(congif_path.as_path()).await?;
+    serde_json::from_str(&file_contents).map_err(|_| SettingsError::Damaged)
+}
+
+/// Writes the JSON value to the configuration file.
+async fn write_json(config: &Value) -> Result<(), SettingsError> {
+    let congif_path = get_conf_path().await;
+    let str_conf = serde_json::to_string(config).map_err(|_| SettingsError::Damaged)?;
+    File::create(congif_path.as_path())
+        .await?
+        .write_all(str_conf.as_bytes())
+        .await?;
+    Ok(())
+}
+
+/// Returns the value for the slash separated path, if any. The empty path returns the root.
+fn lookup<'a>(config: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('/')
+        .filter(|sub_path| !sub_path.is_empty())
+        .try_fold(config, |current, sub_path| current.get(sub_path))
+}
+
+/// Returns the mutable value for the slash separated path, if any. The empty path returns the root.
+fn lookup_mut<'a>(config: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('/')
+        .filter(|sub_path| !sub_path.is_empty())
+        .try_fold(config, |current, sub_path| current.get_mut(sub_path))
+}
+
+/// Inserts the value for the slash separated path, creating the missing sections.
+fn insert(config: &mut Value, path: &str, val: Value) -> Result<(), SettingsError> {
+    let mut paths: Vec<&str> = path.split('/').collect();
+    let last = paths.pop().ok_or(SettingsError::Path(path.to_string()))?;
+
+    let mut current = config.as_object_mut().ok_or(SettingsError::RootConfig)?;
+
+    for sub_path in paths.into_iter() {
+        current = current
+            .entry(sub_path)
+            .or_insert(json!({}))
+            .as_object_mut()
+            .ok_or(SettingsError::Structure(sub_path.to_owned()))?;
+    }
+    current.insert(last.to_owned(), val);
+    Ok(())
+}
+
+/// Removes the value for the slash separated path and returns it, if any.
+fn remove(config: &mut Value, path: &str) -> Option<Value> {
+    let (parent, last) = match path.rsplit_once('/') {
+        Some((parent, last)) => (lookup_mut(config, parent)?, last),
+        None => (config, path),
+    };
+    parent.as_object_mut()?.remove(last)
+}
+
+/// Collects the leaf values of the JSON tree with their slash separated paths.
+fn flatten(value: &Value, path: String, values: &mut Vec<(String, Value)>) {
+    match value.as_object() {
+        Some(object) => {
+            for (key, value) in object {
+                let sub_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}/{key}")
+                };
+                flatten(value, sub_path, values);
+            }
+        }
+        None => values.push((path, value.clone())),
     }
 }
 
@@ -121,6 +550,86 @@ pub enum SettingsError {
     Damaged,
     #[error("Provider not set. Please configure the provider")]
     ProviderNotSet,
+    #[error("Profile '{0}' not found. Create it with 'weather profile create {0}'")]
+    ProfileNotFound(String),
+    #[error("Profile '{0}' already exists")]
+    ProfileExists(String),
+    #[error("Not valid profile name '{0}'")]
+    ProfileName(String),
+    #[error("Location '{0}' not found. Save it with 'weather location add {0} <location>'")]
+    LocationNotFound(String),
+    #[error(
+        "Location group '{0}' not found. Save it with 'weather location group {0} <alias>...'"
+    )]
+    LocationGroupNotFound(String),
+    #[error("Not valid location alias '{0}'")]
+    LocationName(String),
+    #[error("No address given and no default location set")]
+    NoDefaultLocation,
+    #[error("Not valid location coordinates")]
+    Coordinates(#[from] CoordinatesError),
     #[error("Configuration error")]
     Configuration(#[from] config::ConfigError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Value {
+        json!({
+            "provider": "open-weather",
+            "open-weather": { "apiKey": "key", "baseUrl": "http://127.0.0.1" }
+        })
+    }
+
+    #[test]
+    fn lookup_nested_value() {
+        let config = config();
+        assert_eq!(lookup(&config, "open-weather/apiKey"), Some(&json!("key")));
+        assert_eq!(lookup(&config, "provider"), Some(&json!("open-weather")));
+        assert_eq!(lookup(&config, "open-weather/missing"), None);
+        assert_eq!(lookup(&config, "provider/apiKey"), None);
+    }
+
+    #[test]
+    fn insert_creates_sections() {
+        let mut config = json!({});
+        insert(&mut config, "profiles/work/provider", json!("weather-api")).expect("inserted");
+        assert_eq!(
+            config,
+            json!({ "profiles": { "work": { "provider": "weather-api" } } })
+        );
+        insert(&mut config, "profiles/work/provider/apiKey", json!("key"))
+            .expect_err("provider is not an object");
+    }
+
+    #[test]
+    fn lookup_empty_path_returns_root() {
+        let config = config();
+        assert_eq!(lookup(&config, ""), Some(&config));
+    }
+
+    #[test]
+    fn remove_value_and_section() {
+        let mut config = config();
+        assert_eq!(
+            remove(&mut config, "open-weather/apiKey"),
+            Some(json!("key"))
+        );
+        assert_eq!(remove(&mut config, "open-weather/apiKey"), None);
+        assert!(remove(&mut config, "open-weather").is_some());
+        assert_eq!(config, json!({ "provider": "open-weather" }));
+    }
+
+    #[test]
+    fn flatten_leaf_values() {
+        let mut values = Vec::new();
+        flatten(&config(), String::new(), &mut values);
+        let paths: Vec<&str> = values.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["open-weather/apiKey", "open-weather/baseUrl", "provider"]
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use opentelemetry::{trace::TraceError, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace, Resource};
+use tracing::Level;
+use tracing_subscriber::{filter::Targets, layer::SubscriberExt, util::SubscriberInitExt, Layer};
+use url::Url;
+
+/// Exports the spans in batches to the OpenTelemetry collector until dropped, when the remaining
+/// spans are flushed.
+pub struct Telemetry;
+
+impl Telemetry {
+    /// Installs the OTLP/HTTP exporter of the spans to the collector at the endpoint, the spans are
+    /// sent to `/v1/traces` of the endpoint.
+    ///
+    /// # Errors
+    ///
+    /// * `TraceError` if the exporter can't be built or another subscriber is already installed
+    pub fn init(endpoint: &Url) -> Result<Self, TraceError> {
+        let exporter = opentelemetry_otlp::new_exporter()
+            .http()
+            .with_endpoint(endpoint.as_str().trim_end_matches('/'));
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(trace::config().with_resource(Resource::new([
+                KeyValue::new("service.name", "weather"),
+                KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+            ])))
+            .install_batch(runtime::TokioCurrentThread)?;
+        tracing_subscriber::registry()
+            // The debug spans of the HTTP clients, the exporter's included, are left out
+            .with(
+                tracing_opentelemetry::layer()
+                    .with_tracer(tracer)
+                    .with_filter(Targets::new().with_default(Level::INFO)),
+            )
+            .try_init()
+            .map_err(|e| TraceError::Other(Box::new(e)))?;
+        Ok(Telemetry)
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+}
@@ -1,52 +1,694 @@
-use weather_abstractions::{ Units, WeatherKind };
+use crate::commands::{
+    check::RuleCheck, config, history::HistorySummary, watch::WeatherChanges, WeatherCommandResult,
+};
+use crate::icons::{self, IconStyle};
+use chrono::NaiveDate;
 use colored::Colorize;
-use crate::commands::WeatherCommandResult;
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+use weather_abstractions::{
+    AirQuality, Alert, Astronomy, Period, SolarDay, Temperature, Units, Weather, WeatherKind,
+};
 
-pub fn print(result: WeatherCommandResult) {
+/// Disables the colors when the `NO_COLOR` environment variable is set or the output is not a terminal.
+pub fn init_colors() {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color || !std::io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+}
+
+/// Prints the result of the command to the standard output.
+///
+/// # Errors
+///
+/// Returns an `io::Error` if the generated content could not be written, for example when the
+/// output is piped to a program which has exited.
+pub fn print(result: WeatherCommandResult, icons: IconStyle) -> io::Result<()> {
     match result {
-        WeatherCommandResult::Weather(args, weather) => {
+        WeatherCommandResult::Weather(location, date, weather) if icons == IconStyle::Ascii => {
+            let date = date.unwrap_or_else(|| chrono::offset::Utc::now().date_naive());
+            let details = [
+                location.bold().to_string(),
+                weather.condition.clone(),
+                weather.temp.to_string_value(Units::Metric),
+                when(date, &weather),
+            ];
+            let art = icons::render(weather.condition_kind, icons);
+            for (i, line) in art.iter().enumerate() {
+                let detail = details.get(i).map(String::as_str).unwrap_or_default();
+                println!("{line} {detail}");
+            }
+            print_details(&weather);
+            if let Some(air_quality) = &weather.air_quality {
+                print_air_quality(air_quality);
+            }
+            print_alerts(&weather.alerts);
+        }
+        WeatherCommandResult::Weather(location, date, weather) => {
             let temp = weather.temp.to_string_value(Units::Metric);
-            let date = args.date.unwrap_or_else(|| chrono::offset::Utc::now().date_naive());
-            let location = args.address;
-            let condition = weather.condition;
+            let date = date.unwrap_or_else(|| chrono::offset::Utc::now().date_naive());
+            let date = when(date, &weather);
+            let condition = weather.condition.clone();
             let weather_message = match weather.kind {
                 WeatherKind::History => format!(
                     "On {date}, the weather in {location} was {condition} with temperature of {temp}."),
-                WeatherKind::Current => 
+                WeatherKind::Current =>
                     format!("Today in {location}, the current weather conditions are {condition} with a temperature of {temp}."),
-                WeatherKind::Forecast => 
+                WeatherKind::Forecast =>
                     format!("The forecast for {location} for {date} is {condition} with a predicted high temperature of {temp}."),
             };
-            println!("{}", weather_message);  
+            match icons::render(weather.condition_kind, icons).first() {
+                Some(icon) => println!("{icon} {weather_message}"),
+                None => println!("{weather_message}"),
+            }
+            print_details(&weather);
+            if let Some(air_quality) = &weather.air_quality {
+                print_air_quality(air_quality);
+            }
+            print_alerts(&weather.alerts);
         }
         WeatherCommandResult::ProviderChanged(provider) => {
             println!("Weather provider changed to: '{}'.", provider)
         }
-        WeatherCommandResult::SettingsApplied => println!("The changes was applied.")
-        ,
+        WeatherCommandResult::SetupCompleted(provider) => {
+            println!(
+                "The settings were verified, weather provider changed to: '{}'.",
+                provider.green().bold()
+            )
+        }
+        WeatherCommandResult::SettingsApplied => println!("The changes was applied."),
         WeatherCommandResult::Reseted => {
             println!("The settings were reset to default.")
-        },
+        }
+        WeatherCommandResult::ProviderReseted(provider) => {
+            println!(
+                "The settings of provider '{}' were reset to default.",
+                provider
+            )
+        }
+        WeatherCommandResult::SettingValue(value) => {
+            println!("{}", config::to_plain_string(value))
+        }
+        WeatherCommandResult::SettingUnset(path) => {
+            println!("The setting '{}' was removed.", path)
+        }
+        WeatherCommandResult::SettingsList(values) => {
+            for (path, value) in values {
+                println!("{}: {}", path.white().bold(), value.green().bold());
+            }
+        }
         WeatherCommandResult::Info(info) => {
+            println!("Current profile: {}", info.profile.green().bold());
             match info.provider {
                 Some(current) => println!("Current provider: {}", current.green().bold()),
                 None => println!("{}", "Provider not set".red().bold()),
             }
 
             let separator = "-".repeat(40);
-        for (p, settings) in info.settings {
-            println!("\n{}", separator);
-            println!("Settings for provider '{}':", p.bold());
-
-            match settings {
-                None => println!("{}", "No settings found".red().bold()),
-                Some(settings) => {
-                    for (field, value) in settings {
-                        println!("{}: {}", field.white().bold(), value.green().bold());
+            for (p, settings) in info.settings {
+                println!("\n{}", separator);
+                println!("Settings for provider '{}':", p.bold());
+
+                match settings {
+                    None => println!("{}", "No settings found".red().bold()),
+                    Some(settings) => {
+                        for (field, value) in settings {
+                            println!("{}: {}", field.white().bold(), value.green().bold());
+                        }
                     }
                 }
             }
         }
-        },
+        WeatherCommandResult::Profiles(active, profiles) => {
+            for profile in profiles {
+                if profile == active {
+                    println!("* {}", profile.green().bold());
+                } else {
+                    println!("  {}", profile);
+                }
+            }
+        }
+        WeatherCommandResult::ProfileChanged(profile) => {
+            println!("Active profile changed to: '{}'.", profile)
+        }
+        WeatherCommandResult::ProfileCreated(profile) => {
+            println!("The profile '{}' was created.", profile)
+        }
+        WeatherCommandResult::ProfileDeleted(profile) => {
+            println!("The profile '{}' was deleted.", profile)
+        }
+        WeatherCommandResult::LocationAdded(alias) => {
+            println!("The location '{}' was saved.", alias)
+        }
+        WeatherCommandResult::Locations(default, locations, groups) => {
+            for (alias, location) in locations {
+                if Some(&alias) == default.as_ref() {
+                    println!("* {} {}", alias.green().bold(), location);
+                } else {
+                    println!("  {} {}", alias, location);
+                }
+            }
+            for (name, members) in groups {
+                println!("  {} {}", format!("@{name}").cyan(), members.join(", "));
+            }
+        }
+        WeatherCommandResult::LocationRemoved(alias) => {
+            println!("The location '{}' was removed.", alias)
+        }
+        WeatherCommandResult::DefaultLocationChanged(alias) => {
+            println!("Default location changed to: '{}'.", alias)
+        }
+        WeatherCommandResult::LocationGroupAdded(name) => {
+            println!("The location group '@{}' was saved.", name)
+        }
+        WeatherCommandResult::LocationGroupRemoved(name) => {
+            println!("The location group '@{}' was removed.", name)
+        }
+        WeatherCommandResult::WeatherTable(date, rows) => print_weather_table(date, rows, icons),
+        WeatherCommandResult::History(location, days, summary) => {
+            print_history(&location, days, summary, icons)
+        }
+        WeatherCommandResult::BatchCompleted(summary) => {
+            // The records may be written to the standard output, so the summary goes to the error output.
+            eprintln!(
+                "The batch was completed: {} succeeded, {} failed, {} skipped.",
+                summary.succeeded.to_string().green(),
+                summary.failed.to_string().red(),
+                summary.skipped
+            )
+        }
+        WeatherCommandResult::Alerts(location, alerts) if alerts.is_empty() => {
+            println!("No alerts for {location}.")
+        }
+        WeatherCommandResult::Alerts(location, alerts) => {
+            println!("{}", format!("Alerts for {location}").bold());
+            print_alerts(&alerts);
+        }
+        WeatherCommandResult::AirQuality(location, air_quality) => {
+            println!("{}", location.bold());
+            print_air_quality(&air_quality);
+        }
+        WeatherCommandResult::Astronomy(location, date, astronomy) => {
+            println!("{}", format!("{location} on {date}").bold());
+            print_astronomy(&astronomy);
+        }
+        WeatherCommandResult::Check(location, date, checks, explain) => {
+            if explain {
+                print_checks(&location, date, &checks);
+            }
+        }
+        WeatherCommandResult::SolarDay(location, date, solar_day) => {
+            println!("{}", format!("{location} on {date}").bold());
+            print_solar_day(&solar_day);
+        }
+        WeatherCommandResult::ServerStopped(address) => {
+            println!("Stopped serving the weather on http://{address}.")
+        }
+        WeatherCommandResult::WatchStopped(location) => {
+            println!("Stopped watching {location}.")
+        }
+        WeatherCommandResult::Generated(content) => {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(&content)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints every rule of the check with the checked value, the matched rules first
+/// and highlighted.
+fn print_checks(location: &str, date: Option<NaiveDate>, checks: &[RuleCheck]) {
+    match date {
+        Some(date) => println!("{}", format!("{location} on {date}").bold()),
+        None => println!("{}", location.bold()),
+    }
+    let (matched, unmatched): (Vec<_>, Vec<_>) = checks.iter().partition(|check| check.matched);
+    for check in &matched {
+        println!(
+            "{} {}: {}",
+            "✗".red(),
+            check.rule.to_string().red(),
+            check.value
+        );
+    }
+    for check in &unmatched {
+        println!("{} {}: {}", "✓".green(), check.rule, check.value);
+    }
+    match matched.len() {
+        0 => println!("{}", "OK, no rule matched".green()),
+        count => println!(
+            "{}",
+            format!("Triggered by {count} of {} rules", checks.len()).red()
+        ),
+    }
+}
+
+/// Prints the sun events with the day length, then the moon events with the phase. The events
+/// which don't happen that day are shown as a dash.
+fn print_astronomy(astronomy: &Astronomy) {
+    let time = |time: Option<chrono::DateTime<chrono::FixedOffset>>| {
+        time.map_or("-".to_string(), |time| time.format("%H:%M").to_string())
+    };
+    let offset = [
+        astronomy.sunrise,
+        astronomy.sunset,
+        astronomy.moonrise,
+        astronomy.moonset,
+    ]
+    .into_iter()
+    .flatten()
+    .next()
+    .map(|time| format!(" (UTC{})", time.format("%:z")))
+    .unwrap_or_default();
+    println!(
+        "☀ Sunrise {}  Sunset {}{offset}",
+        time(astronomy.sunrise).yellow(),
+        time(astronomy.sunset).yellow()
+    );
+    if let Some(length) = astronomy.day_length() {
+        println!(
+            "  Day length {}h {:02}m",
+            length.num_hours(),
+            length.num_minutes() % 60
+        );
+    }
+    let has_moon_events = astronomy.moonrise.is_some() || astronomy.moonset.is_some();
+    if has_moon_events {
+        println!(
+            "☾ Moonrise {}  Moonset {}",
+            time(astronomy.moonrise).cyan(),
+            time(astronomy.moonset).cyan()
+        );
+    }
+    let phase = match (astronomy.moon_phase, astronomy.moon_illumination) {
+        (Some(phase), Some(illumination)) => {
+            Some(format!("{phase}, {illumination:.0}% illuminated"))
+        }
+        (Some(phase), None) => Some(phase.to_string()),
+        (None, Some(illumination)) => Some(format!("{illumination:.0}% illuminated")),
+        (None, None) => None,
+    };
+    if let Some(phase) = phase {
+        let marker = if has_moon_events { " " } else { "☾" };
+        println!("{marker} {phase}");
+    }
+}
+
+/// Prints the calculated sun events like the astronomy of the providers, followed by the solar
+/// noon, the twilight and the golden and blue hours.
+fn print_solar_day(solar_day: &SolarDay) {
+    print_astronomy(&solar_day.astronomy());
+    let time = |time: Option<chrono::DateTime<chrono::FixedOffset>>| {
+        time.map_or("-".to_string(), |time| time.format("%H:%M").to_string())
+    };
+    let periods = |periods: [Option<Period>; 2]| {
+        let periods: Vec<String> = periods
+            .into_iter()
+            .flatten()
+            .map(|period| format!("{}–{}", time(Some(period.start)), time(Some(period.end))))
+            .collect();
+        match periods.is_empty() {
+            true => "-".to_string(),
+            false => periods.join(", "),
+        }
+    };
+    println!("  Solar noon {}", time(Some(solar_day.solar_noon)));
+    println!(
+        "  Civil twilight {}–{}  Nautical twilight {}–{}",
+        time(solar_day.civil_dawn),
+        time(solar_day.civil_dusk),
+        time(solar_day.nautical_dawn),
+        time(solar_day.nautical_dusk)
+    );
+    println!(
+        "  Golden hour {}",
+        periods([solar_day.morning_golden_hour, solar_day.evening_golden_hour]).yellow()
+    );
+    println!(
+        "  Blue hour {}",
+        periods([solar_day.morning_blue_hour, solar_day.evening_blue_hour]).blue()
+    );
+}
+
+/// Prints the humidity, wind and pressure, followed by the comfort metrics, computed locally
+/// when the provider doesn't return them. The metrics out of their validity range are skipped.
+fn print_details(weather: &Weather) {
+    let measurements: Vec<String> = [
+        weather
+            .humidity
+            .map(|humidity| format!("Humidity {humidity:.0}%")),
+        weather
+            .wind_speed
+            .map(|wind_speed| format!("Wind {wind_speed:.1} m/s")),
+        weather
+            .pressure
+            .map(|pressure| format!("Pressure {pressure:.0} hPa")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !measurements.is_empty() {
+        println!("  {}", measurements.join("  "));
+    }
+    let comfort = weather.comfort();
+    let temp = |temp: Temperature| temp.to_string_value(Units::Metric);
+    let metrics: Vec<String> = [
+        comfort
+            .apparent_temperature
+            .map(|feels_like| format!("Feels like {}", temp(feels_like))),
+        comfort
+            .dew_point
+            .map(|dew_point| format!("Dew point {}", temp(dew_point))),
+        comfort
+            .heat_index
+            .map(|heat_index| format!("Heat index {}", temp(heat_index).red())),
+        comfort
+            .wind_chill
+            .map(|wind_chill| format!("Wind chill {}", temp(wind_chill).cyan())),
+        comfort
+            .humidex
+            .map(|humidex| format!("Humidex {humidex:.0}")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !metrics.is_empty() {
+        println!("  {}", metrics.join("  "));
+    }
+}
+
+/// Prints the air quality categories followed by the pollutant concentrations.
+fn print_air_quality(air_quality: &AirQuality) {
+    let mut indexes = Vec::new();
+    if let (Some(index), Some(category)) = (air_quality.us_epa_index, air_quality.us_epa_category())
+    {
+        indexes.push(format!(
+            "{} (US EPA index {index})",
+            epa_colored(index, category)
+        ));
+    }
+    if let (Some(index), Some(band)) = (air_quality.gb_defra_index, air_quality.gb_defra_band()) {
+        indexes.push(format!("{band} (UK DEFRA index {index})"));
+    }
+    if !indexes.is_empty() {
+        println!("Air quality: {}", indexes.join(", "));
+    }
+    let concentrations: Vec<String> = [
+        ("PM2.5", air_quality.pm2_5),
+        ("PM10", air_quality.pm10),
+        ("O3", air_quality.o3),
+        ("NO2", air_quality.no2),
+        ("SO2", air_quality.so2),
+        ("CO", air_quality.co),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Some(format!("{name} {:.1}", value?)))
+    .collect();
+    if !concentrations.is_empty() {
+        println!("  {} μg/m³", concentrations.join("  "));
+    }
+}
+
+/// Colors the text by the US EPA index: green when good, yellow when moderate, red otherwise.
+fn epa_colored(index: u8, text: &str) -> colored::ColoredString {
+    match index {
+        1 => text.green(),
+        2 => text.yellow(),
+        _ => text.red().bold(),
+    }
+}
+
+/// Prints the alerts prominently, with the event, severity and time in red, followed by the
+/// area, sender and the indented description. The alerts which haven't started are marked.
+fn print_alerts(alerts: &[Alert]) {
+    let now = chrono::Utc::now();
+    let time = |time: chrono::DateTime<chrono::FixedOffset>| time.format("%Y-%m-%d %H:%M %:z");
+    for alert in alerts {
+        let mut title = format!("⚠ {}", alert.event);
+        if let Some(severity) = &alert.severity {
+            title += &format!(" ({severity})");
+        }
+        match (alert.start, alert.end) {
+            (Some(start), end) if !alert.is_active_at(now) && start > now => {
+                title += &format!(" from {}", time(start));
+                if let Some(end) = end {
+                    title += &format!(" until {}", time(end));
+                }
+            }
+            (_, Some(end)) => title += &format!(" until {}", time(end)),
+            _ => {}
+        }
+        println!("\n{}", title.red().bold());
+        let details: Vec<String> = [
+            alert.area.as_ref().map(|area| format!("Area: {area}")),
+            alert
+                .sender
+                .as_ref()
+                .map(|sender| format!("Issued by: {sender}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !details.is_empty() {
+            println!("  {}", details.join(", "));
+        }
+        for line in alert
+            .description
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+        {
+            println!("  {}", line.trim());
+        }
+    }
+}
+
+/// Draws the refreshes of the watch command, redrawn in place on a terminal and appended as
+/// lines otherwise, for example when the output is piped to a file. The changed values are
+/// highlighted.
+pub struct WatchView {
+    location: String,
+    icons: IconStyle,
+    in_place: bool,
+    drawn_lines: usize,
+}
+
+impl WatchView {
+    pub fn new(location: &str, icons: IconStyle) -> Self {
+        Self {
+            location: location.to_string(),
+            icons,
+            in_place: io::stdout().is_terminal(),
+            drawn_lines: 0,
+        }
+    }
+    /// Shows the weather with the changes since the previous refresh.
+    pub fn show(
+        &mut self,
+        weather: &Weather,
+        changes: &WeatherChanges,
+        next: Duration,
+    ) -> io::Result<()> {
+        let mut temp = weather.temp.to_string_value(Units::Metric);
+        if let Some(delta) = changes.temp_delta {
+            let arrow = if delta > 0.0 { "▲" } else { "▼" };
+            temp = format!("{temp} {arrow}{:.1}°C", delta.abs())
+                .yellow()
+                .bold()
+                .to_string();
+        }
+        let mut condition = weather.condition.clone();
+        if let Some(previous) = &changes.previous_condition {
+            condition = format!("{condition} (was {previous})")
+                .yellow()
+                .bold()
+                .to_string();
+        }
+        let icon = match self.icons {
+            IconStyle::Ascii => None,
+            icons => icons::render(weather.condition_kind, icons)
+                .into_iter()
+                .next(),
+        };
+        let icon = icon.map(|icon| format!("{icon} ")).unwrap_or_default();
+        let now = chrono::Local::now();
+        if self.in_place {
+            self.draw(&[
+                format!(
+                    "{} {}",
+                    self.location.bold(),
+                    format!(
+                        "updated at {}, next in {}",
+                        now.format("%H:%M:%S"),
+                        format_duration(next)
+                    )
+                    .dimmed()
+                ),
+                format!("{icon}{condition}, {temp}"),
+            ])
+        } else {
+            self.draw(&[format!(
+                "{}  {}  {temp}  {icon}{condition}",
+                now.format("%Y-%m-%d %H:%M:%S"),
+                self.location
+            )])
+        }
+    }
+    /// Shows the error under the last retrieved weather on a terminal, or as a line otherwise.
+    pub fn show_error(
+        &mut self,
+        last: Option<&Weather>,
+        error: &str,
+        retry: Duration,
+    ) -> io::Result<()> {
+        let now = chrono::Local::now();
+        let error = format!("error: {error}, retry in {}", format_duration(retry))
+            .red()
+            .to_string();
+        if !self.in_place {
+            return self.draw(&[format!(
+                "{}  {}  {error}",
+                now.format("%Y-%m-%d %H:%M:%S"),
+                self.location
+            )]);
+        }
+        let mut lines = vec![format!(
+            "{} {}",
+            self.location.bold(),
+            format!("failed at {}", now.format("%H:%M:%S")).dimmed()
+        )];
+        if let Some(weather) = last {
+            lines.push(format!(
+                "{}, {}",
+                weather.condition,
+                weather.temp.to_string_value(Units::Metric)
+            ));
+        }
+        lines.push(error);
+        self.draw(&lines)
+    }
+    /// Replaces the previously drawn lines on a terminal, appends the lines otherwise.
+    fn draw(&mut self, lines: &[String]) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        if self.in_place && self.drawn_lines > 0 {
+            // Moves the cursor to the start of the first drawn line and clears the screen below
+            write!(stdout, "\x1b[{}F\x1b[J", self.drawn_lines)?;
+        }
+        for line in lines {
+            writeln!(stdout, "{line}")?;
+        }
+        self.drawn_lines = lines.len();
+        stdout.flush()
+    }
+}
+
+/// Formats the duration in the largest whole unit, like the watch interval: 90s, 10m or 2h.
+fn format_duration(duration: Duration) -> String {
+    match duration.as_secs() {
+        secs if secs >= 3600 && secs % 3600 == 0 => format!("{}h", secs / 3600),
+        secs if secs >= 60 && secs % 60 == 0 => format!("{}m", secs / 60),
+        secs => format!("{secs}s"),
+    }
+}
+
+/// Returns the date, or the local time of the hourly slot when the weather is for a time of the day.
+fn when(date: NaiveDate, weather: &Weather) -> String {
+    match weather.time {
+        Some(time) => time.format("%Y-%m-%d %H:%M %:z").to_string(),
+        None => date.to_string(),
+    }
+}
+
+/// Prints the weather for several locations as a table, with the error message in place of
+/// the weather for the failed locations. The multi-line ASCII art doesn't fit in a table row,
+/// so no icon is shown for `IconStyle::Ascii`.
+fn print_weather_table(
+    date: Option<NaiveDate>,
+    rows: Vec<(String, Result<Weather, String>)>,
+    icons: IconStyle,
+) {
+    match date {
+        Some(date) => println!("{}", format!("Weather on {date}").bold()),
+        None => println!("{}", "Current weather".bold()),
+    }
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or_default();
+    for (name, weather) in rows {
+        let name = format!("{name:<width$}");
+        match weather {
+            Ok(weather) => {
+                let icon = match icons {
+                    IconStyle::Ascii => None,
+                    _ => icons::render(weather.condition_kind, icons)
+                        .into_iter()
+                        .next(),
+                };
+                let alerts = match weather.alerts.len() {
+                    0 => String::new(),
+                    1 => format!("  {}", "⚠ 1 alert".red().bold()),
+                    count => format!("  {}", format!("⚠ {count} alerts").red().bold()),
+                };
+                let air = match weather
+                    .air_quality
+                    .as_ref()
+                    .and_then(|air| air.us_epa_index)
+                {
+                    Some(index) => format!("  {}", epa_colored(index, &format!("AQI {index}"))),
+                    None => String::new(),
+                };
+                let time = weather
+                    .time
+                    .map(|time| format!("{}  ", time.format("%H:%M %:z")))
+                    .unwrap_or_default();
+                println!(
+                    "{}  {time}{:>8}  {}{}{air}{alerts}",
+                    name.bold(),
+                    weather.temp.to_string_value(Units::Metric),
+                    icon.map(|icon| format!("{icon} ")).unwrap_or_default(),
+                    weather.condition
+                );
+            }
+            Err(error) => println!("{}  {}", name.bold(), format!("error: {error}").red()),
+        }
+    }
+}
+
+/// Prints the weather of every day of the range as a table followed by the summary.
+fn print_history(
+    location: &str,
+    days: Vec<(NaiveDate, Weather)>,
+    summary: Option<HistorySummary>,
+    icons: IconStyle,
+) {
+    println!("{}", location.bold());
+    for (date, weather) in days {
+        let icon = match icons {
+            IconStyle::Ascii => None,
+            _ => icons::render(weather.condition_kind, icons)
+                .into_iter()
+                .next(),
+        };
+        println!(
+            "{date}  {:>8}  {}{}",
+            weather.temp.to_string_value(Units::Metric),
+            icon.map(|icon| format!("{icon} ")).unwrap_or_default(),
+            weather.condition
+        );
+    }
+    if let Some(summary) = summary {
+        let celsius = |temp: f32| format!("{temp:.1}°C");
+        println!();
+        println!(
+            "Temperature: mean {}, min {}, max {}",
+            celsius(summary.mean_temp).bold(),
+            celsius(summary.min_temp).blue(),
+            celsius(summary.max_temp).red()
+        );
+        println!("Rainy days: {}", summary.rainy_days);
+        println!("Most common condition: {}", summary.most_common_condition);
     }
-}
\ No newline at end of file
+}